@@ -0,0 +1,22 @@
+#![no_main]
+
+use hetu::{Ssn, SsnPattern};
+use libfuzzer_sys::fuzz_target;
+
+type Fields = (
+    [Option<u8>; 6],
+    Option<char>,
+    [Option<u8>; 3],
+    Option<char>,
+);
+
+fuzz_target!(|fields: Fields| {
+    let ([d1, d2, m1, m2, y1, y2], sep, [i1, i2, i3], check) = fields;
+    let pattern = match SsnPattern::new(d1, d2, m1, m2, y1, y2, sep, i1, i2, i3, check) {
+        Ok(pattern) => pattern,
+        Err(_) => return,
+    };
+    for ssn in Ssn::iter(&pattern).unwrap().take(16) {
+        assert!(pattern.matches_str(&ssn), "{} doesn't match {}", ssn, pattern);
+    }
+});
@@ -0,0 +1,9 @@
+#![no_main]
+
+use hetu::Ssn;
+use libfuzzer_sys::fuzz_target;
+use std::convert::TryFrom;
+
+fuzz_target!(|data: &str| {
+    let _ = Ssn::try_from(data);
+});
@@ -0,0 +1,14 @@
+#![no_main]
+
+use hetu::{Ssn, SsnPattern};
+use libfuzzer_sys::fuzz_target;
+use std::convert::TryFrom;
+
+fuzz_target!(|data: &str| {
+    if let Ok(pattern) = SsnPattern::try_from(data) {
+        if let Ok(ssn) = Ssn::generate_by_pattern(&pattern) {
+            assert!(pattern.matches_str(&ssn), "{} doesn't match {}", ssn, pattern);
+        }
+        let _ = Ssn::count(&pattern);
+    }
+});
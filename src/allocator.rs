@@ -0,0 +1,482 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::convert::TryFrom;
+use std::error;
+use std::fmt;
+use std::ops::RangeInclusive;
+
+use super::{Date, Gender, Profile, Separators, Ssn};
+
+/// Identifiers of permanent personal identity codes.
+const PERMANENT: RangeInclusive<usize> = 2..=899;
+/// Identifiers of temporary personal identity codes.
+const TEMPORARY: RangeInclusive<usize> = 900..=999;
+
+/// Issues personal identity codes like the population register, keeping the used identifiers of every birth date in
+/// memory.
+///
+/// The next unused identifier in the parity of the gender is issued, skipping reserved identifiers. Temporary
+/// identifiers 900-999 are allocated separately from permanent ones. When every identifier of a birth date is used
+/// with one separator, the next separator of the century in the profile is used, e.g. `Y` after `-`.
+///
+/// # Example
+///
+/// ```
+/// use hetu::{Allocator, Date, Gender, Profile};
+///
+/// let mut allocator = Allocator::new(Profile::Modern).with_reserved(2..=9);
+/// let born = Date::new(1969, 12, 29).unwrap();
+/// assert_eq!(allocator.allocate(born, Gender::Female).unwrap().to_string(), "291269-010H");
+/// assert_eq!(allocator.allocate(born, Gender::Female).unwrap().to_string(), "291269-012K");
+/// assert_eq!(allocator.allocate(born, Gender::Male).unwrap().to_string(), "291269-011J");
+/// assert_eq!(allocator.allocate_temporary(born, Gender::Male).unwrap().to_string(), "291269-9018");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Allocator {
+    profile: Profile,
+    /// Identifiers never issued, in addition to 000 and 001.
+    reserved: BTreeSet<usize>,
+    /// Used identifiers by birth date and separator.
+    used: BTreeMap<(Date, char), BTreeSet<usize>>,
+}
+
+/// Failure to issue a personal identity code.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AllocationError {
+    /// Every identifier of the gender on the birth date is used or reserved with every separator of the profile.
+    Exhausted {
+        birth_date: Date,
+        gender: Gender,
+        temporary: bool,
+    },
+    /// Birth date is in a century without separators in the profile.
+    Century(Date),
+}
+
+impl fmt::Display for AllocationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            AllocationError::Exhausted {
+                birth_date,
+                gender,
+                temporary,
+            } => write!(
+                f,
+                "No {} identifiers left for {} born {}",
+                if temporary { "temporary" } else { "permanent" },
+                match gender {
+                    Gender::Female => "females",
+                    Gender::Male => "males",
+                },
+                birth_date
+            ),
+            AllocationError::Century(birth_date) => {
+                write!(f, "No separator for birth date {}", birth_date)
+            }
+        }
+    }
+}
+
+impl error::Error for AllocationError {}
+
+/// Failure to restore an allocator from a snapshot.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SnapshotError {
+    /// Line number starting from 1.
+    pub line: usize,
+    pub message: &'static str,
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Line {}: {}", self.line, self.message)
+    }
+}
+
+impl error::Error for SnapshotError {}
+
+impl Default for Allocator {
+    fn default() -> Allocator {
+        Allocator::new(Profile::default())
+    }
+}
+
+impl Allocator {
+    /// Allocator that issues codes with the separators of a profile and no identifiers used.
+    pub fn new(profile: Profile) -> Allocator {
+        Allocator {
+            profile,
+            reserved: BTreeSet::new(),
+            used: BTreeMap::new(),
+        }
+    }
+
+    /// Profile whose separators are issued.
+    pub fn profile(&self) -> Profile {
+        self.profile
+    }
+
+    /// Parse identifier or range of identifiers in the format of snapshots, e.g. `12` or `2-9`.
+    ///
+    /// Returns `None` if an identifier is above 999 or the range is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hetu::Allocator;
+    ///
+    /// assert_eq!(Allocator::parse_range("500-599"), Some(500..=599));
+    /// assert_eq!(Allocator::parse_range("12"), Some(12..=12));
+    /// assert_eq!(Allocator::parse_range("9-2"), None);
+    /// assert_eq!(Allocator::parse_range("1000"), None);
+    /// ```
+    pub fn parse_range(value: &str) -> Option<RangeInclusive<usize>> {
+        let (start, end) = match value.split_once('-') {
+            Some((start, end)) => (start.parse().ok()?, end.parse().ok()?),
+            None => {
+                let id = value.parse().ok()?;
+                (id, id)
+            }
+        };
+        if start <= end && end <= *TEMPORARY.end() {
+            Some(start..=end)
+        } else {
+            None
+        }
+    }
+
+    /// Allocator that never issues identifiers in a range, on any birth date.
+    pub fn with_reserved(mut self, identifiers: RangeInclusive<usize>) -> Allocator {
+        self.reserved
+            .extend(identifiers.filter(|id| PERMANENT.contains(id) || TEMPORARY.contains(id)));
+        self
+    }
+
+    /// Issue the next permanent personal identity code for a birth date and gender.
+    pub fn allocate(&mut self, birth_date: Date, gender: Gender) -> Result<Ssn, AllocationError> {
+        self.allocate_in(birth_date, gender, false)
+    }
+
+    /// Issue the next temporary personal identity code, with identifier in range 900-999, for a birth date and gender.
+    pub fn allocate_temporary(
+        &mut self,
+        birth_date: Date,
+        gender: Gender,
+    ) -> Result<Ssn, AllocationError> {
+        self.allocate_in(birth_date, gender, true)
+    }
+
+    /// Mark a personal identity code issued elsewhere as used. Returns `false` if it was already used.
+    pub fn mark_used(&mut self, ssn: &Ssn) -> bool {
+        self.used
+            .entry((ssn.birth_date(), ssn.separator))
+            .or_default()
+            .insert(ssn.identifier)
+    }
+
+    /// Test whether a personal identity code has been issued or marked as used.
+    pub fn is_used(&self, ssn: &Ssn) -> bool {
+        self.used
+            .get(&(ssn.birth_date(), ssn.separator))
+            .is_some_and(|used| used.contains(&ssn.identifier))
+    }
+
+    /// Number of codes that can still be issued for a birth date and gender.
+    pub fn remaining(&self, birth_date: Date, gender: Gender, temporary: bool) -> usize {
+        self.separators(&birth_date)
+            .iter()
+            .map(|separator| {
+                candidates(gender, temporary)
+                    .filter(|id| self.is_free(&birth_date, *separator, *id))
+                    .count()
+            })
+            .sum()
+    }
+
+    /// Snapshot of the reserved and used identifiers, e.g. to save in a file.
+    ///
+    /// The snapshot has one line per setting or birth date, with consecutive identifiers written as ranges:
+    ///
+    /// ```text
+    /// profile modern
+    /// reserved 2-9
+    /// 1969-12-29 - 10-12 901
+    /// ```
+    pub fn snapshot(&self) -> String {
+        let mut lines = vec![format!("profile {}", self.profile)];
+        if !self.reserved.is_empty() {
+            lines.push(format!("reserved {}", ranges(&self.reserved)));
+        }
+        for (&(birth_date, separator), used) in &self.used {
+            if !used.is_empty() {
+                lines.push(format!("{} {} {}", birth_date, separator, ranges(used)));
+            }
+        }
+        lines.join("\n") + "\n"
+    }
+
+    /// Restore an allocator from a snapshot. Blank lines and lines starting with `#` are ignored.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hetu::{Allocator, Date, Gender};
+    ///
+    /// let mut allocator = Allocator::restore("profile legacy\n1969-12-29 - 2-900\n").unwrap();
+    /// let born = Date::new(1969, 12, 29).unwrap();
+    /// assert!(allocator.allocate(born, Gender::Female).is_err());
+    /// assert_eq!(allocator.allocate_temporary(born, Gender::Female).unwrap().to_string(), "291269-9029");
+    /// assert_eq!(Allocator::restore(&allocator.snapshot()), Ok(allocator));
+    /// ```
+    pub fn restore(snapshot: &str) -> Result<Allocator, SnapshotError> {
+        let mut allocator = Allocator::default();
+        for (i, line) in snapshot.lines().enumerate() {
+            let error = |message| SnapshotError {
+                line: i + 1,
+                message,
+            };
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields[0] {
+                "profile" => {
+                    let name = fields.get(1).ok_or_else(|| error("Missing profile"))?;
+                    allocator.profile =
+                        Profile::try_from(*name).map_err(|_| error("Invalid profile"))?;
+                }
+                "reserved" => {
+                    for field in &fields[1..] {
+                        let identifiers = Allocator::parse_range(field)
+                            .ok_or_else(|| error("Invalid identifier range"))?;
+                        allocator = allocator.with_reserved(identifiers);
+                    }
+                }
+                date => {
+                    let birth_date =
+                        Date::try_from(date).map_err(|_| error("Invalid birth date"))?;
+                    let separator =
+                        match fields.get(1).map(|field| field.chars().collect::<Vec<_>>()) {
+                            Some(ref chars) if chars.len() == 1 => chars[0],
+                            _ => return Err(error("Invalid separator")),
+                        };
+                    if !Separators::century(birth_date.year / 100 * 100).contains(separator) {
+                        return Err(error("Invalid separator"));
+                    }
+                    let used = allocator.used.entry((birth_date, separator)).or_default();
+                    for field in &fields[2..] {
+                        let identifiers = Allocator::parse_range(field)
+                            .ok_or_else(|| error("Invalid identifier range"))?;
+                        used.extend(identifiers);
+                    }
+                }
+            }
+        }
+        Ok(allocator)
+    }
+
+    fn allocate_in(
+        &mut self,
+        birth_date: Date,
+        gender: Gender,
+        temporary: bool,
+    ) -> Result<Ssn, AllocationError> {
+        let separators = self.separators(&birth_date);
+        if separators.is_empty() {
+            return Err(AllocationError::Century(birth_date));
+        }
+        for separator in separators {
+            if let Some(identifier) =
+                candidates(gender, temporary).find(|id| self.is_free(&birth_date, separator, *id))
+            {
+                let ssn = Ssn {
+                    day: birth_date.day,
+                    month: birth_date.month,
+                    year: birth_date.year,
+                    gender,
+                    separator,
+                    identifier,
+                };
+                self.mark_used(&ssn);
+                return Ok(ssn);
+            }
+        }
+        Err(AllocationError::Exhausted {
+            birth_date,
+            gender,
+            temporary,
+        })
+    }
+
+    /// Separators of the profile for the century of a birth date, in the order they are taken into use.
+    fn separators(&self, birth_date: &Date) -> Vec<char> {
+        Separators::century(birth_date.year / 100 * 100)
+            .intersection(self.profile.separators())
+            .iter()
+            .collect()
+    }
+
+    fn is_free(&self, birth_date: &Date, separator: char, identifier: usize) -> bool {
+        !self.reserved.contains(&identifier)
+            && !self
+                .used
+                .get(&(*birth_date, separator))
+                .is_some_and(|used| used.contains(&identifier))
+    }
+}
+
+/// Identifiers of a gender in allocation order.
+fn candidates(gender: Gender, temporary: bool) -> impl Iterator<Item = usize> {
+    let range = if temporary { TEMPORARY } else { PERMANENT };
+    let parity = match gender {
+        Gender::Female => 0,
+        Gender::Male => 1,
+    };
+    range.filter(move |id| id % 2 == parity)
+}
+
+/// Identifiers written as space separated numbers and ranges, e.g. `2-9 12 901-903`.
+fn ranges(identifiers: &BTreeSet<usize>) -> String {
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for &id in identifiers {
+        match ranges.last_mut() {
+            Some(&mut (_, ref mut end)) if *end + 1 == id => *end = id,
+            _ => ranges.push((id, id)),
+        }
+    }
+    ranges
+        .iter()
+        .map(|&(start, end)| {
+            if start == end {
+                start.to_string()
+            } else {
+                format!("{}-{}", start, end)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: usize, month: usize, day: usize) -> Date {
+        Date::new(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_allocate_parity() {
+        let mut allocator = Allocator::default();
+        let born = date(2005, 3, 1);
+        for i in 0..10 {
+            let female = allocator.allocate(born, Gender::Female).unwrap();
+            assert_eq!(female.identifier, 2 + 2 * i);
+            assert_eq!(female.separator, 'A');
+            let male = allocator.allocate(born, Gender::Male).unwrap();
+            assert_eq!(male.identifier, 3 + 2 * i);
+            let code = male.to_string();
+            assert_eq!(Ssn::try_from(code.as_str()), Ok(male));
+        }
+        let temporary = allocator.allocate_temporary(born, Gender::Female).unwrap();
+        assert_eq!(temporary.identifier, 900);
+        assert!(temporary.is_temporary());
+        assert!(allocator.is_used(&temporary));
+    }
+
+    #[test]
+    fn test_exhaustion() {
+        let born = date(1969, 12, 29);
+        let mut allocator = Allocator::new(Profile::Legacy).with_reserved(900..=997);
+        assert_eq!(allocator.remaining(born, Gender::Male, true), 1);
+        assert_eq!(
+            allocator
+                .allocate_temporary(born, Gender::Male)
+                .unwrap()
+                .identifier,
+            999
+        );
+        assert_eq!(
+            allocator.allocate_temporary(born, Gender::Male),
+            Err(AllocationError::Exhausted {
+                birth_date: born,
+                gender: Gender::Male,
+                temporary: true
+            })
+        );
+        assert_eq!(
+            allocator
+                .allocate_temporary(born, Gender::Female)
+                .unwrap()
+                .identifier,
+            998
+        );
+        assert_eq!(allocator.remaining(born, Gender::Female, false), 449);
+
+        let mut modern = Allocator::new(Profile::Modern);
+        for _ in 0..449 {
+            assert_eq!(
+                modern.allocate(born, Gender::Female).unwrap().separator,
+                '-'
+            );
+        }
+        let next = modern.allocate(born, Gender::Female).unwrap();
+        assert_eq!((next.separator, next.identifier), ('Y', 2));
+        assert_eq!(modern.remaining(born, Gender::Female, false), 449 * 6 - 450);
+
+        assert_eq!(
+            Allocator::default().allocate(date(1799, 1, 1), Gender::Male),
+            Err(AllocationError::Century(date(1799, 1, 1)))
+        );
+    }
+
+    #[test]
+    fn test_mark_used() {
+        let mut allocator = Allocator::default();
+        let ssn = Ssn::try_from("291269-2763").unwrap();
+        assert!(allocator.mark_used(&Ssn::try_from("291269-0028").unwrap()));
+        assert!(allocator.mark_used(&ssn));
+        assert!(!allocator.mark_used(&ssn));
+        assert_eq!(
+            allocator
+                .allocate(ssn.birth_date(), Gender::Female)
+                .unwrap()
+                .identifier,
+            4
+        );
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let born = date(1969, 12, 29);
+        let mut allocator = Allocator::new(Profile::Legacy).with_reserved(100..=199);
+        for _ in 0..3 {
+            allocator.allocate(born, Gender::Female).unwrap();
+            allocator.allocate(born, Gender::Male).unwrap();
+        }
+        allocator.allocate_temporary(born, Gender::Female).unwrap();
+        allocator.allocate(date(2001, 1, 1), Gender::Male).unwrap();
+        let snapshot = allocator.snapshot();
+        assert_eq!(
+            snapshot,
+            "profile legacy\nreserved 100-199\n1969-12-29 - 2-7 900\n2001-01-01 A 3\n"
+        );
+        let mut restored = Allocator::restore(&format!("# saved\n\n{}", snapshot)).unwrap();
+        assert_eq!(restored, allocator);
+        assert_eq!(
+            restored.allocate(born, Gender::Female),
+            allocator.allocate(born, Gender::Female)
+        );
+
+        assert_eq!(
+            Allocator::restore("profile legacy\n1969-12-29 A 2"),
+            Err(SnapshotError {
+                line: 2,
+                message: "Invalid separator"
+            })
+        );
+        assert!(Allocator::restore("1969-12-29 - 9-2").is_err());
+        assert!(Allocator::restore("1969-13-29 - 2").is_err());
+        assert!(Allocator::restore("profile 2023").is_err());
+    }
+}
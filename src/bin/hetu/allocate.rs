@@ -0,0 +1,134 @@
+use hetu::{Allocator, Profile};
+use std::fs::{self, File, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::ops::RangeInclusive;
+
+use {AllocateArgs, Exit, Gender};
+
+/// Lock file next to a state file, removed when dropped.
+struct Lock(String);
+
+impl Lock {
+    /// Create lock file for a state file, failing if another run holds it.
+    fn acquire(state: &str) -> Result<Lock, Exit> {
+        let path = format!("{}.lock", state);
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(_) => Ok(Lock(path)),
+            Err(ref err) if err.kind() == ErrorKind::AlreadyExists => {
+                eprintln!(
+                    "Error: {}: State is locked by another run. Remove the lock file if no run is in progress",
+                    path
+                );
+                Err(Exit::Io)
+            }
+            Err(err) => {
+                eprintln!("Error: {}: {}", path, err);
+                Err(Exit::Io)
+            }
+        }
+    }
+}
+
+impl Drop for Lock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+/// Issue the next unused codes for a birth date and gender, keeping used identifiers in a state file.
+///
+/// The state file is locked for the run, and replaced with the new state before the codes are printed, so that a
+/// failed run or concurrent runs never issue the same code twice.
+pub fn run(args: &AllocateArgs) -> Result<(), Exit> {
+    let _lock = match args.state {
+        Some(ref path) => Some(Lock::acquire(path)?),
+        None => None,
+    };
+    let profile = args.profile.map(Profile::from);
+    let mut allocator = match args.state {
+        Some(ref path) => load(path)?,
+        None => None,
+    }
+    .unwrap_or_else(|| Allocator::new(profile.unwrap_or_default()));
+    if let Some(profile) = profile.filter(|profile| *profile != allocator.profile()) {
+        eprintln!(
+            "Error: Profile {} conflicts with profile {} of the state file",
+            profile,
+            allocator.profile()
+        );
+        return Err(Exit::Usage);
+    }
+    for range in &args.reserve {
+        allocator = allocator.with_reserved(range.clone());
+    }
+    let gender = match args.gender {
+        Gender::Female => hetu::Gender::Female,
+        Gender::Male => hetu::Gender::Male,
+    };
+    let mut issued = Vec::new();
+    let mut error = None;
+    for _ in 0..args.count {
+        let ssn = if args.temporary {
+            allocator.allocate_temporary(args.born, gender)
+        } else {
+            allocator.allocate(args.born, gender)
+        };
+        match ssn {
+            Ok(ssn) => issued.push(ssn),
+            Err(err) => {
+                error = Some(err);
+                break;
+            }
+        }
+    }
+    if let Some(ref path) = args.state {
+        save(path, &allocator)?;
+    }
+    for ssn in &issued {
+        println!("{}", ssn);
+    }
+    match error {
+        Some(err) => {
+            eprintln!("Error: {}", err);
+            Err(Exit::Generate)
+        }
+        None => Ok(()),
+    }
+}
+
+/// Replace state file with a snapshot of the allocator, writing a temporary file and renaming it over the state.
+fn save(path: &str, allocator: &Allocator) -> Result<(), Exit> {
+    let temporary = format!("{}.tmp", path);
+    let written = File::create(&temporary)
+        .and_then(|mut file| {
+            file.write_all(allocator.snapshot().as_bytes())?;
+            file.sync_all()
+        })
+        .and_then(|_| fs::rename(&temporary, path));
+    written.map_err(|err| {
+        let _ = fs::remove_file(&temporary);
+        eprintln!("Error: {}: {}", path, err);
+        Exit::Io
+    })
+}
+
+/// Restore allocator from a state file, `None` if the file doesn't exist yet.
+fn load(path: &str) -> Result<Option<Allocator>, Exit> {
+    let snapshot = match fs::read_to_string(path) {
+        Ok(snapshot) => snapshot,
+        Err(ref err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(err) => {
+            eprintln!("Error: {}: {}", path, err);
+            return Err(Exit::Io);
+        }
+    };
+    Allocator::restore(&snapshot).map(Some).map_err(|err| {
+        eprintln!("Error: {}:{}: {}", path, err.line, err.message);
+        Exit::Usage
+    })
+}
+
+/// Parse identifier or range of identifiers to reserve, e.g. `500` or `500-599`.
+pub fn parse_range(value: &str) -> Result<RangeInclusive<usize>, String> {
+    Allocator::parse_range(value).ok_or_else(|| format!("invalid identifier range '{}'", value))
+}
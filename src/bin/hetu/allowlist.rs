@@ -0,0 +1,47 @@
+use hetu::{Ssn, SsnPattern};
+use std::convert::TryFrom;
+use std::fs;
+
+use diagnostic::print_error_at;
+use Exit;
+
+/// Personal identity codes that are allowed in scanned text, such as known test codes.
+#[derive(Debug, Default)]
+pub struct Allowlist {
+    patterns: Vec<SsnPattern>,
+    /// Allow temporary codes with identifier in range 900-999.
+    temporary: bool,
+}
+
+impl Allowlist {
+    /// Read allowlist file of codes or patterns, one per line. Empty lines and lines starting with `#` are ignored.
+    pub fn load(path: Option<&str>, temporary: bool) -> Result<Allowlist, Exit> {
+        let mut patterns = Vec::new();
+        if let Some(path) = path {
+            let text = fs::read_to_string(path).map_err(|err| {
+                eprintln!("Error: {}: {}", path, err);
+                Exit::Io
+            })?;
+            for (number, line) in text.lines().enumerate() {
+                let line = line.trim();
+                if line.is_empty() || line.starts_with('#') {
+                    continue;
+                }
+                let pattern = SsnPattern::try_from(line).map_err(|ref err| {
+                    print_error_at(err, line, &format!("{}:{}", path, number + 1));
+                    Exit::Usage
+                })?;
+                patterns.push(pattern);
+            }
+        }
+        Ok(Allowlist {
+            patterns,
+            temporary,
+        })
+    }
+
+    pub fn allows(&self, ssn: &Ssn) -> bool {
+        (self.temporary && ssn.is_temporary())
+            || self.patterns.iter().any(|pattern| pattern.matches(ssn))
+    }
+}
@@ -0,0 +1,224 @@
+use ansi_term::Colour::{Blue, Green, Red};
+use ansi_term::Style;
+use hetu::{ErrorIndexRange, Language, ParseError, QueryError};
+use std::fmt::Display;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+/// Whether diagnostics are colored.
+static COLOR: AtomicBool = AtomicBool::new(false);
+
+/// Enable or disable colors in diagnostics.
+pub fn set_color(enabled: bool) {
+    COLOR.store(enabled, Ordering::Relaxed);
+}
+
+/// Language of diagnostics and decoded information.
+static LANGUAGE: OnceLock<Language> = OnceLock::new();
+
+/// Set the language of diagnostics once, before any are printed.
+pub fn set_language(language: Language) {
+    let _ = LANGUAGE.set(language);
+}
+
+/// Language of diagnostics, English unless set.
+pub fn language() -> Language {
+    LANGUAGE.get().copied().unwrap_or_default()
+}
+
+fn paint(style: Style, text: &str) -> String {
+    if COLOR.load(Ordering::Relaxed) {
+        style.paint(text).to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+/// Span of input with a message.
+#[derive(Debug, PartialEq)]
+pub struct Label {
+    /// Byte range in the input.
+    pub start: usize,
+    pub end: usize,
+    pub message: String,
+    /// Primary labels mark the error, secondary labels the input it depends on.
+    pub primary: bool,
+}
+
+impl Label {
+    pub fn primary(start: usize, end: usize, message: &str) -> Label {
+        Label {
+            start,
+            end,
+            message: message.to_string(),
+            primary: true,
+        }
+    }
+
+    pub fn secondary(start: usize, end: usize, message: &str) -> Label {
+        Label {
+            primary: false,
+            ..Label::primary(start, end, message)
+        }
+    }
+}
+
+/// Error that can be shown with labelled spans of its input.
+pub trait Labelled: Display + ErrorIndexRange {
+    /// Labels of the error, by default the error span without a message.
+    fn labels(&self) -> Vec<Label> {
+        vec![Label::primary(self.start(), self.end(), "")]
+    }
+
+    /// Error message in the language of diagnostics, by default the English message.
+    fn localized(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl Labelled for ParseError<'_> {
+    fn labels(&self) -> Vec<Label> {
+        let language = language();
+        match *self {
+            ParseError::Checksum(_, start, end, expected) => vec![
+                Label::primary(
+                    start,
+                    end,
+                    &language.format(
+                        "expected {}",
+                        &[&paint(Green.bold(), &expected.to_string())],
+                    ),
+                ),
+                Label::secondary(0, 6, language.translate("birth date")),
+                Label::secondary(7, 10, language.translate("identifier")),
+            ],
+            _ => vec![Label::primary(self.start(), self.end(), "")],
+        }
+    }
+
+    fn localized(&self) -> String {
+        self.to_string_in(language())
+    }
+}
+
+impl Labelled for QueryError {
+    fn localized(&self) -> String {
+        self.to_string_in(language())
+    }
+}
+
+/// Print error with the input it was found in and labels pointing at the error location.
+pub fn print_error<E: Labelled>(err: &E, input: &str) {
+    eprintln!(
+        "Error: {}\n\n{}",
+        err.localized(),
+        render(input, &err.labels())
+    );
+}
+
+/// Print error like `print_error`, with the location of the input, e.g. file name and line number.
+pub fn print_error_at<E: Labelled>(err: &E, input: &str, location: &str) {
+    eprintln!(
+        "Error: {}\n --> {}\n\n{}",
+        err.localized(),
+        location,
+        render(input, &err.labels())
+    );
+}
+
+/// Input followed by a line for each label, with marks under the labelled span and the message after the marks.
+fn render(input: &str, labels: &[Label]) -> String {
+    let mut res = format!("  {}", input);
+    for label in labels {
+        let start = floor_char_boundary(input, label.start);
+        let end = ceil_char_boundary(input, label.end.max(label.start));
+        // Tabs are repeated so that marks line up with the input however wide tabs are
+        let padding: String = input[..start]
+            .chars()
+            .map(|c| {
+                if c == '\t' {
+                    "\t".to_string()
+                } else {
+                    " ".repeat(width(c))
+                }
+            })
+            .collect();
+        let (mark, style) = if label.primary {
+            ("^", Red.normal())
+        } else {
+            ("-", Blue.normal())
+        };
+        let marks = mark.repeat(input[start..end].chars().map(width).sum::<usize>().max(1));
+        res.push_str(&format!("\n  {}{}", padding, paint(style, &marks)));
+        if !label.message.is_empty() {
+            res.push(' ');
+            res.push_str(&label.message);
+        }
+    }
+    res
+}
+
+/// Largest character boundary at or before a byte offset.
+fn floor_char_boundary(input: &str, offset: usize) -> usize {
+    let mut offset = offset.min(input.len());
+    while !input.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    offset
+}
+
+/// Smallest character boundary at or after a byte offset.
+fn ceil_char_boundary(input: &str, offset: usize) -> usize {
+    let mut offset = offset.min(input.len());
+    while !input.is_char_boundary(offset) {
+        offset += 1;
+    }
+    offset
+}
+
+/// Number of terminal columns a character takes, two for wide East Asian characters and emoji.
+fn width(c: char) -> usize {
+    match c as u32 {
+        0x0300..=0x036F | 0x200B..=0x200F => 0,
+        0x1100..=0x115F
+        | 0x2E80..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6
+        | 0x1F300..=0x1F64F
+        | 0x1F900..=0x1F9FF
+        | 0x20000..=0x3FFFD => 2,
+        _ => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryFrom;
+
+    use hetu::Ssn;
+
+    #[test]
+    fn test_render_checksum() {
+        let err = Ssn::try_from("291269-2764").unwrap_err();
+        assert_eq!(
+            render("291269-2764", &err.labels()),
+            "  291269-2764\n            ^ expected 3\n  ------ birth date\n         --- identifier"
+        );
+    }
+
+    #[test]
+    fn test_render_multibyte() {
+        // Invalid length is reported in bytes
+        let input = "ää1269-2763";
+        let err = Ssn::try_from(input).unwrap_err();
+        assert_eq!(render(input, &err.labels()), "  ää1269-2763\n  ^^^^^^^^^^^");
+        // Spans inside a character cover the whole character, and wide characters take two columns
+        let labels = [Label::primary(2, 2, "inside"), Label::secondary(3, 6, "")];
+        assert_eq!(render("aä漢b", &labels), "  aä漢b\n   ^ inside\n    --");
+        assert_eq!(render("\tx", &[Label::primary(1, 2, "")]), "  \tx\n  \t^");
+    }
+}
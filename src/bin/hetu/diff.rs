@@ -0,0 +1,199 @@
+use hetu::scan;
+use std::io::{self, Read};
+use std::process::Command;
+
+use allowlist::Allowlist;
+use scan::snippet;
+use {DiffArgs, Exit};
+
+/// Hunk of a unified diff with the codes found on its added lines.
+#[derive(Debug, Default)]
+struct Hunk {
+    path: String,
+    /// Hunk header, e.g. `@@ -1,2 +1,3 @@`.
+    header: String,
+    /// Line number, column and masked snippet of each found code.
+    findings: Vec<(usize, usize, String)>,
+}
+
+/// Report codes on added lines of staged changes, or of a unified diff read from standard input.
+pub fn run(args: &DiffArgs) -> Result<(), Exit> {
+    let allowlist = Allowlist::load(args.allowlist.as_deref(), true)?;
+    let diff = if args.input.as_deref() == Some("-") {
+        let mut diff = String::new();
+        io::stdin().read_to_string(&mut diff).map_err(|err| {
+            eprintln!("Error: {}", err);
+            Exit::Io
+        })?;
+        diff
+    } else {
+        staged()?
+    };
+    let hunks = find(&diff, &allowlist);
+    for hunk in &hunks {
+        println!("{} {}", hunk.path, hunk.header);
+        for &(line, column, ref snippet) in &hunk.findings {
+            println!("  +{}:{}: {}", line, column, snippet);
+        }
+    }
+    if hunks.is_empty() {
+        Ok(())
+    } else {
+        let count: usize = hunks.iter().map(|hunk| hunk.findings.len()).sum();
+        eprintln!(
+            "Error: {} HETUs found on added lines in {} hunks",
+            count,
+            hunks.len()
+        );
+        Err(Exit::Found)
+    }
+}
+
+/// Staged changes from local git.
+fn staged() -> Result<String, Exit> {
+    let output = Command::new("git")
+        .args([
+            "diff",
+            "--cached",
+            "--no-color",
+            "--no-ext-diff",
+            "--unified=0",
+        ])
+        .output()
+        .map_err(|err| {
+            eprintln!("Error: Unable to run git: {}", err);
+            Exit::Io
+        })?;
+    if !output.status.success() {
+        eprint!("{}", String::from_utf8_lossy(&output.stderr));
+        return Err(Exit::Io);
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Find codes that are not allowed on added lines, grouped by hunk. Hunks without codes are left out.
+fn find(diff: &str, allowlist: &Allowlist) -> Vec<Hunk> {
+    let mut hunks = Vec::new();
+    let mut path = String::new();
+    let mut hunk: Option<Hunk> = None;
+    let mut line_number = 0;
+    // Number of old and new lines left in the current hunk, so that content lines are never taken for headers
+    let mut remaining: (usize, usize) = (0, 0);
+    for line in diff.lines() {
+        if remaining != (0, 0) {
+            if let Some(added) = line.strip_prefix('+') {
+                if let Some(ref mut hunk) = hunk {
+                    for m in scan::find(added).filter(|m| !allowlist.allows(&m.ssn)) {
+                        let column = added[..m.start].chars().count() + 1;
+                        hunk.findings.push((line_number, column, snippet(added)));
+                    }
+                }
+                line_number += 1;
+                remaining.1 = remaining.1.saturating_sub(1);
+            } else if line.starts_with('-') {
+                remaining.0 = remaining.0.saturating_sub(1);
+            } else if line.starts_with(' ') || line.is_empty() {
+                line_number += 1;
+                remaining = (remaining.0.saturating_sub(1), remaining.1.saturating_sub(1));
+            }
+            continue;
+        }
+        if let Some(new_path) = line.strip_prefix("+++ ") {
+            let new_path = new_path.trim_end_matches('\t');
+            path = new_path.strip_prefix("b/").unwrap_or(new_path).to_string();
+        } else if line.starts_with("@@ ") {
+            let (header, old, new) = match parse_header(line) {
+                Some(header) => header,
+                None => continue,
+            };
+            hunks.extend(hunk.take());
+            line_number = new.0;
+            remaining = (old, new.1);
+            hunk = Some(Hunk {
+                path: path.clone(),
+                header: header.to_string(),
+                findings: Vec::new(),
+            });
+        }
+    }
+    hunks.extend(hunk);
+    hunks.retain(|hunk| !hunk.findings.is_empty());
+    hunks
+}
+
+/// Parse hunk header `@@ -a,b +c,d @@` into the header without section context, the number of old lines, and
+/// the start and the number of new lines.
+fn parse_header(line: &str) -> Option<(&str, usize, (usize, usize))> {
+    let end = line[3..].find(" @@")? + 6;
+    let mut ranges = line[3..end - 3].split(' ');
+    let old = parse_range(ranges.next()?.strip_prefix('-')?)?;
+    let new = parse_range(ranges.next()?.strip_prefix('+')?)?;
+    Some((&line[..end], old.1, new))
+}
+
+/// Parse range `start,count` or `start` with count of one.
+fn parse_range(range: &str) -> Option<(usize, usize)> {
+    let mut parts = range.splitn(2, ',');
+    let start = parts.next()?.parse().ok()?;
+    let count = match parts.next() {
+        Some(count) => count.parse().ok()?,
+        None => 1,
+    };
+    Some((start, count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find() {
+        let diff = "diff --git a/a.txt b/a.txt
+--- a/a.txt
++++ b/a.txt
+@@ -1,2 +1,4 @@ fn context
+ first
++id 291269-2763
+-removed 010101-123N
++++ 010101-123N
++temporary 010150B902E
+@@ -10 +12 @@
+-old
++new 010101-123N 291269-2763
+diff --git a/b c.txt b/b c.txt
+new file mode 100644
+--- /dev/null
++++ b/b c.txt	
+@@ -0,0 +1 @@
++nothing
+";
+        let hunks = find(diff, &Allowlist::default());
+        assert_eq!(hunks.len(), 2);
+        assert_eq!(hunks[0].path, "a.txt");
+        assert_eq!(hunks[0].header, "@@ -1,2 +1,4 @@");
+        assert_eq!(
+            hunks[0].findings,
+            vec![
+                (2, 4, "id ***********".to_string()),
+                (3, 4, "++ ***********".to_string()),
+                (4, 11, "temporary ***********".to_string()),
+            ]
+        );
+        assert_eq!(hunks[1].header, "@@ -10 +12 @@");
+        assert_eq!(hunks[1].findings.len(), 2);
+        assert_eq!(hunks[1].findings[1].1, 17);
+    }
+
+    #[test]
+    fn test_parse_header() {
+        assert_eq!(
+            parse_header("@@ -1,2 +3,4 @@ fn main() {"),
+            Some(("@@ -1,2 +3,4 @@", 2, (3, 4)))
+        );
+        assert_eq!(
+            parse_header("@@ -0,0 +1 @@"),
+            Some(("@@ -0,0 +1 @@", 0, (1, 1)))
+        );
+        assert_eq!(parse_header("@@ garbage"), None);
+    }
+}
@@ -0,0 +1,742 @@
+use hetu::scan;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::io::{Cursor, Read, Seek, Write};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// Limits on reading a container, to protect against decompression bombs.
+#[derive(Debug)]
+struct Limits {
+    /// Maximum number of entries in the container.
+    entries: usize,
+    /// Maximum number of uncompressed bytes in a part.
+    part: u64,
+    /// Maximum number of uncompressed bytes in every part together.
+    total: u64,
+}
+
+const LIMITS: Limits = Limits {
+    entries: 65_536,
+    part: 256 * 1024 * 1024,
+    total: 1024 * 1024 * 1024,
+};
+
+/// Text of a document at a location, such as a paragraph or a cell.
+#[derive(Debug, PartialEq)]
+pub struct Segment {
+    /// Name of the part in the container, e.g. `word/document.xml`.
+    pub part: String,
+    /// Location in the part, e.g. `paragraph 3` or `B2`.
+    pub location: String,
+    pub text: String,
+}
+
+/// Test whether file is a ZIP based container, such as DOCX, XLSX, ODT, ODS or plain ZIP.
+pub fn is_container(bytes: &[u8]) -> bool {
+    bytes.starts_with(b"PK\x03\x04")
+}
+
+/// Container whose parts are read one at a time within limits.
+struct Container<'a> {
+    archive: ZipArchive<Cursor<&'a [u8]>>,
+    limits: &'a Limits,
+    /// Number of uncompressed bytes read so far.
+    read: u64,
+}
+
+impl<'a> Container<'a> {
+    fn open(bytes: &'a [u8], limits: &'a Limits) -> Result<Container<'a>, String> {
+        let archive = ZipArchive::new(Cursor::new(bytes)).map_err(|err| err.to_string())?;
+        if archive.len() > limits.entries {
+            return Err(format!(
+                "Container has more than {} entries",
+                limits.entries
+            ));
+        }
+        Ok(Container {
+            archive,
+            limits,
+            read: 0,
+        })
+    }
+
+    /// Name and content of a part, `None` for a directory.
+    ///
+    /// Fails instead of truncating a part that is over the limits, so that it's never scanned or redacted partially.
+    fn part(&mut self, index: usize) -> Result<Option<(String, Vec<u8>)>, String> {
+        let file = self
+            .archive
+            .by_index(index)
+            .map_err(|err| err.to_string())?;
+        if file.is_dir() {
+            return Ok(None);
+        }
+        let name = file.name().to_string();
+        let mut content = Vec::new();
+        file.take(self.limits.part + 1)
+            .read_to_end(&mut content)
+            .map_err(|err| format!("{}: {}", name, err))?;
+        if content.len() as u64 > self.limits.part {
+            return Err(format!(
+                "{}: Part is larger than {} bytes uncompressed",
+                name, self.limits.part
+            ));
+        }
+        self.read += content.len() as u64;
+        if self.read > self.limits.total {
+            return Err(format!(
+                "{}: Parts are larger than {} bytes uncompressed in total",
+                name, self.limits.total
+            ));
+        }
+        Ok(Some((name, content)))
+    }
+}
+
+/// Extract text segments from every part of a container.
+///
+/// Word processing paragraphs and spreadsheet cells are extracted from DOCX, XLSX, ODT and ODS parts, and other text
+/// parts are extracted line by line. Binary parts are skipped. Parts are read one at a time, and a container with too
+/// many entries or parts too large to read is an error.
+pub fn segments(bytes: &[u8]) -> Result<Vec<Segment>, String> {
+    segments_within(bytes, &LIMITS)
+}
+
+fn segments_within(bytes: &[u8], limits: &Limits) -> Result<Vec<Segment>, String> {
+    let mut container = Container::open(bytes, limits)?;
+    // Shared strings are read first, as cells refer to them wherever they are in the container
+    let shared_strings = match container.archive.index_for_name("xl/sharedStrings.xml") {
+        Some(index) => match container.part(index)? {
+            Some((_, content)) => shared_strings(&String::from_utf8_lossy(&content))?,
+            None => Vec::new(),
+        },
+        None => Vec::new(),
+    };
+
+    let mut segments = Vec::new();
+    for i in 0..container.archive.len() {
+        // Shared strings are reported in the cells that refer to them
+        if container
+            .archive
+            .name_for_index(i)
+            .is_some_and(|name| part_kind(name) == PartKind::SharedStrings)
+        {
+            continue;
+        }
+        let (name, content) = match container.part(i)? {
+            Some(part) => part,
+            None => continue,
+        };
+        if content.iter().take(8192).any(|b| *b == 0) {
+            continue;
+        }
+        let text = String::from_utf8_lossy(&content);
+        let found = match part_kind(&name) {
+            PartKind::WordProcessing => paragraphs(&text, &[b"w:p"], &[b"w:t"]),
+            PartKind::OpenDocument => open_document(&text),
+            PartKind::Worksheet => cells(&text, &shared_strings),
+            PartKind::SharedStrings => Ok(Vec::new()),
+            PartKind::Text => Ok(text
+                .lines()
+                .enumerate()
+                .map(|(number, line)| (format!("line {}", number + 1), line.to_string()))
+                .collect()),
+        }
+        .map_err(|err| format!("{}: {}", name, err))?;
+        segments.extend(found.into_iter().map(|(location, text)| Segment {
+            part: name.to_string(),
+            location,
+            text,
+        }));
+    }
+    Ok(segments)
+}
+
+/// Test whether a mask character can replace text in XML without changing its structure.
+pub fn is_xml_safe(mask: char) -> bool {
+    !matches!(mask, '<' | '>' | '&' | '"' | '\'') && !mask.is_control()
+}
+
+/// Copy container with every code in its text parts masked.
+///
+/// Returns the redacted container. Codes split across formatting, such as two differently formatted runs of a
+/// paragraph, are not redacted and can be found by scanning the copy. A mask that isn't XML safe, a container with
+/// too many entries or parts too large to read is an error.
+pub fn redact(bytes: &[u8], mask: char) -> Result<Vec<u8>, String> {
+    redact_within(bytes, mask, &LIMITS)
+}
+
+fn redact_within(bytes: &[u8], mask: char, limits: &Limits) -> Result<Vec<u8>, String> {
+    if !is_xml_safe(mask) {
+        return Err(format!(
+            "Mask character {:?} can't be used in XML parts of documents",
+            mask
+        ));
+    }
+    let mut container = Container::open(bytes, limits)?;
+    let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+    for i in 0..container.archive.len() {
+        let redacted = match container.part(i)? {
+            Some((name, content)) => match String::from_utf8(content) {
+                Ok(text) => {
+                    let redacted = scan::redact(&text, mask);
+                    if redacted != text {
+                        Some((name, redacted))
+                    } else {
+                        None
+                    }
+                }
+                Err(_) => None,
+            },
+            None => None,
+        };
+        let file = container
+            .archive
+            .by_index_raw(i)
+            .map_err(|err| err.to_string())?;
+        match redacted {
+            Some((name, redacted)) => {
+                // Keep stored entries, such as the ODF mimetype, uncompressed
+                let options = SimpleFileOptions::default().compression_method(
+                    if file.compression() == CompressionMethod::Stored {
+                        CompressionMethod::Stored
+                    } else {
+                        CompressionMethod::Deflated
+                    },
+                );
+                writer
+                    .start_file(name, options)
+                    .map_err(|err| err.to_string())?;
+                writer
+                    .write_all(redacted.as_bytes())
+                    .map_err(|err| err.to_string())?;
+            }
+            None => writer.raw_copy_file(file).map_err(|err| err.to_string())?,
+        }
+    }
+    finish(writer).map(Cursor::into_inner)
+}
+
+fn finish<W: Write + Seek>(writer: ZipWriter<W>) -> Result<W, String> {
+    writer.finish().map_err(|err| err.to_string())
+}
+
+#[derive(Debug, PartialEq)]
+enum PartKind {
+    /// DOCX document, headers, footers, notes and comments.
+    WordProcessing,
+    /// ODT or ODS content.
+    OpenDocument,
+    /// XLSX worksheet.
+    Worksheet,
+    /// XLSX shared strings.
+    SharedStrings,
+    Text,
+}
+
+fn part_kind(name: &str) -> PartKind {
+    if name.starts_with("word/") && name.ends_with(".xml") && !name[5..].contains('/') {
+        PartKind::WordProcessing
+    } else if name == "content.xml" || name == "styles.xml" {
+        PartKind::OpenDocument
+    } else if name.starts_with("xl/worksheets/") && name.ends_with(".xml") {
+        PartKind::Worksheet
+    } else if name == "xl/sharedStrings.xml" {
+        PartKind::SharedStrings
+    } else {
+        PartKind::Text
+    }
+}
+
+fn xml_error(err: quick_xml::Error) -> String {
+    format!("Invalid XML: {}", err)
+}
+
+/// Texts of paragraph elements, located by paragraph number. Text is taken from text elements, or from every text
+/// node if text elements are not given.
+fn paragraphs(
+    xml: &str,
+    paragraph: &[&[u8]],
+    text: &[&[u8]],
+) -> Result<Vec<(String, String)>, String> {
+    let mut reader = Reader::from_str(xml);
+    let mut res = Vec::new();
+    let mut count = 0;
+    // Nesting depth of paragraphs and of text elements
+    let mut depth = (0, 0);
+    let mut buf = String::new();
+    loop {
+        match reader.read_event().map_err(xml_error)? {
+            Event::Start(ref e) => {
+                if paragraph.contains(&e.name().as_ref()) {
+                    if depth.0 == 0 {
+                        count += 1;
+                        buf.clear();
+                    }
+                    depth.0 += 1;
+                } else if text.contains(&e.name().as_ref()) {
+                    depth.1 += 1;
+                }
+            }
+            Event::End(ref e) => {
+                if paragraph.contains(&e.name().as_ref()) {
+                    depth.0 -= 1;
+                    if depth.0 == 0 && !buf.is_empty() {
+                        res.push((format!("paragraph {}", count), buf.clone()));
+                    }
+                } else if text.contains(&e.name().as_ref()) {
+                    depth.1 -= 1;
+                }
+            }
+            Event::Empty(ref e) => {
+                if paragraph.contains(&e.name().as_ref()) {
+                    count += 1;
+                } else if depth.0 > 0 && is_break(e) {
+                    buf.push(' ');
+                }
+            }
+            Event::Text(ref t) if depth.0 > 0 && (text.is_empty() || depth.1 > 0) => {
+                buf.push_str(&t.unescape().map_err(xml_error)?);
+            }
+            Event::CData(ref t) if depth.0 > 0 && (text.is_empty() || depth.1 > 0) => {
+                buf.push_str(&String::from_utf8_lossy(t));
+            }
+            Event::Eof => break,
+            _ => (),
+        }
+    }
+    Ok(res)
+}
+
+/// Test whether empty element separates words, such as a tab, a line break or a space.
+fn is_break(e: &BytesStart) -> bool {
+    matches!(
+        e.name().as_ref(),
+        b"w:tab" | b"w:br" | b"w:cr" | b"text:tab" | b"text:line-break" | b"text:s"
+    )
+}
+
+/// Paragraphs of ODT content, or cells of ODS content.
+fn open_document(xml: &str) -> Result<Vec<(String, String)>, String> {
+    if xml.contains("<office:spreadsheet") {
+        table_cells(xml)
+    } else {
+        paragraphs(xml, &[b"text:p", b"text:h"], &[])
+    }
+}
+
+/// Texts of ODS cells, located by sheet name and cell reference.
+fn table_cells(xml: &str) -> Result<Vec<(String, String)>, String> {
+    let mut reader = Reader::from_str(xml);
+    let mut res = Vec::new();
+    let mut sheet = String::new();
+    let (mut row, mut column): (usize, usize) = (0, 0);
+    // Number of times the current row repeats
+    let mut rows_repeated = 1;
+    let mut columns_repeated = 1;
+    let mut cell: Option<String> = None;
+    loop {
+        match reader.read_event().map_err(xml_error)? {
+            Event::Start(ref e) => match e.name().as_ref() {
+                b"table:table" => {
+                    sheet = attribute(e, b"table:name")?.unwrap_or_default();
+                    row = 0;
+                }
+                b"table:table-row" => {
+                    row = row.saturating_add(1);
+                    column = 0;
+                    rows_repeated = repeat(e, b"table:number-rows-repeated")?;
+                }
+                b"table:table-cell" | b"table:covered-table-cell" => {
+                    column = column.saturating_add(1);
+                    columns_repeated = repeat(e, b"table:number-columns-repeated")?;
+                    cell = Some(String::new());
+                }
+                b"text:p" | b"text:h" => {
+                    if let Some(ref mut cell) = cell {
+                        if !cell.is_empty() {
+                            cell.push('\n');
+                        }
+                    }
+                }
+                _ => (),
+            },
+            Event::End(ref e) => match e.name().as_ref() {
+                b"table:table-row" => row = row.saturating_add(rows_repeated - 1),
+                b"table:table-cell" | b"table:covered-table-cell" => {
+                    if let Some(text) = cell.take() {
+                        if !text.is_empty() {
+                            res.push((format!("{}!{}{}", sheet, column_name(column), row), text));
+                        }
+                    }
+                    column = column.saturating_add(columns_repeated - 1);
+                }
+                _ => (),
+            },
+            Event::Empty(ref e) => match e.name().as_ref() {
+                b"table:table-row" => {
+                    row = row.saturating_add(repeat(e, b"table:number-rows-repeated")?)
+                }
+                b"table:table-cell" | b"table:covered-table-cell" => {
+                    column = column.saturating_add(repeat(e, b"table:number-columns-repeated")?)
+                }
+                _ => {
+                    if let Some(ref mut cell) = cell {
+                        if is_break(e) {
+                            cell.push(' ');
+                        }
+                    }
+                }
+            },
+            Event::Text(ref t) => {
+                if let Some(ref mut cell) = cell {
+                    cell.push_str(&t.unescape().map_err(xml_error)?);
+                }
+            }
+            Event::Eof => break,
+            _ => (),
+        }
+    }
+    Ok(res)
+}
+
+/// Texts of XLSX shared strings in index order.
+fn shared_strings(xml: &str) -> Result<Vec<String>, String> {
+    let mut reader = Reader::from_str(xml);
+    let mut res = Vec::new();
+    let mut in_text = false;
+    let mut in_phonetic = false;
+    loop {
+        match reader.read_event().map_err(xml_error)? {
+            Event::Start(ref e) => match e.local_name().as_ref() {
+                b"si" => res.push(String::new()),
+                b"t" => in_text = true,
+                b"rPh" => in_phonetic = true,
+                _ => (),
+            },
+            Event::End(ref e) => match e.local_name().as_ref() {
+                b"t" => in_text = false,
+                b"rPh" => in_phonetic = false,
+                _ => (),
+            },
+            Event::Empty(ref e) if e.local_name().as_ref() == b"si" => res.push(String::new()),
+            Event::Text(ref t) if in_text && !in_phonetic => {
+                if let Some(last) = res.last_mut() {
+                    last.push_str(&t.unescape().map_err(xml_error)?);
+                }
+            }
+            Event::Eof => break,
+            _ => (),
+        }
+    }
+    Ok(res)
+}
+
+/// Texts of XLSX worksheet cells, located by cell reference.
+fn cells(xml: &str, shared_strings: &[String]) -> Result<Vec<(String, String)>, String> {
+    let mut reader = Reader::from_str(xml);
+    let mut res = Vec::new();
+    // Reference, type and text of the current cell
+    let mut cell: Option<(String, String, String)> = None;
+    let mut in_value = false;
+    loop {
+        match reader.read_event().map_err(xml_error)? {
+            Event::Start(ref e) => match e.local_name().as_ref() {
+                b"c" => {
+                    cell = Some((
+                        attribute(e, b"r")?.unwrap_or_default(),
+                        attribute(e, b"t")?.unwrap_or_default(),
+                        String::new(),
+                    ))
+                }
+                b"v" | b"t" => in_value = true,
+                _ => (),
+            },
+            Event::End(ref e) => match e.local_name().as_ref() {
+                b"c" => {
+                    if let Some((reference, kind, value)) = cell.take() {
+                        let text = if kind == "s" {
+                            value
+                                .trim()
+                                .parse::<usize>()
+                                .ok()
+                                .and_then(|i| shared_strings.get(i))
+                                .cloned()
+                                .unwrap_or_default()
+                        } else {
+                            value
+                        };
+                        if !text.is_empty() {
+                            res.push((reference, text));
+                        }
+                    }
+                }
+                b"v" | b"t" => in_value = false,
+                _ => (),
+            },
+            Event::Text(ref t) if in_value => {
+                if let Some((_, _, ref mut value)) = cell {
+                    value.push_str(&t.unescape().map_err(xml_error)?);
+                }
+            }
+            Event::Eof => break,
+            _ => (),
+        }
+    }
+    Ok(res)
+}
+
+fn attribute(e: &BytesStart, name: &[u8]) -> Result<Option<String>, String> {
+    match e
+        .try_get_attribute(name)
+        .map_err(|err| format!("Invalid XML: {}", err))?
+    {
+        Some(attr) => Ok(Some(attr.unescape_value().map_err(xml_error)?.into_owned())),
+        None => Ok(None),
+    }
+}
+
+/// Number of repetitions in attribute, at least one and one by default.
+fn repeat(e: &BytesStart, name: &[u8]) -> Result<usize, String> {
+    Ok(attribute(e, name)?
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(1)
+        .max(1))
+}
+
+/// Spreadsheet column name of a 1-based column number, e.g. `A`, `Z` or `AA`.
+fn column_name(column: usize) -> String {
+    let mut name = Vec::new();
+    let mut n = column;
+    while n > 0 {
+        n -= 1;
+        name.push(b'A' + (n % 26) as u8);
+        n /= 26;
+    }
+    name.reverse();
+    String::from_utf8(name).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn container(parts: &[(&str, &str)]) -> Vec<u8> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for &(name, content) in parts {
+            writer
+                .start_file(name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn found(bytes: &[u8]) -> Vec<(String, String, String)> {
+        segments(bytes)
+            .unwrap()
+            .into_iter()
+            .map(|segment| (segment.part, segment.location, segment.text))
+            .collect()
+    }
+
+    #[test]
+    fn test_docx() {
+        let docx = container(&[
+            ("[Content_Types].xml", "<Types/>"),
+            (
+                "word/document.xml",
+                "<w:document><w:body>\
+                 <w:p><w:r><w:t>Title</w:t></w:r></w:p>\
+                 <w:p/>\
+                 <w:p><w:r><w:t xml:space=\"preserve\">Id </w:t></w:r>\
+                 <w:r><w:rPr><w:b/></w:rPr><w:t>291269</w:t></w:r><w:r><w:t>-2763</w:t></w:r>\
+                 <w:r><w:tab/><w:t>&amp; more</w:t></w:r></w:p>\
+                 </w:body></w:document>",
+            ),
+            ("word/media/image1.png", "\u{0}PNG"),
+        ]);
+        assert_eq!(
+            found(&docx),
+            vec![
+                (
+                    "[Content_Types].xml".to_string(),
+                    "line 1".to_string(),
+                    "<Types/>".to_string()
+                ),
+                (
+                    "word/document.xml".to_string(),
+                    "paragraph 1".to_string(),
+                    "Title".to_string()
+                ),
+                (
+                    "word/document.xml".to_string(),
+                    "paragraph 3".to_string(),
+                    "Id 291269-2763 & more".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_xlsx() {
+        let xlsx = container(&[
+            (
+                "xl/sharedStrings.xml",
+                "<sst><si><t>Name</t></si><si><r><t>291269</t></r><r><t>-2763</t></r></si></sst>",
+            ),
+            (
+                "xl/worksheets/sheet1.xml",
+                "<worksheet><sheetData><row r=\"1\">\
+                 <c r=\"A1\" t=\"s\"><v>0</v></c><c r=\"B1\"><v>42</v></c></row>\
+                 <row r=\"2\"><c r=\"A2\" t=\"s\"><v>1</v></c>\
+                 <c r=\"B2\" t=\"inlineStr\"><is><t>010101-123N</t></is></c></row>\
+                 </sheetData></worksheet>",
+            ),
+        ]);
+        let segments = found(&xlsx);
+        let cells: Vec<_> = segments
+            .iter()
+            .map(|(_, location, text)| (location.as_str(), text.as_str()))
+            .collect();
+        assert_eq!(
+            cells,
+            vec![
+                ("A1", "Name"),
+                ("B1", "42"),
+                ("A2", "291269-2763"),
+                ("B2", "010101-123N")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_odf() {
+        let odt = container(&[
+            ("mimetype", "application/vnd.oasis.opendocument.text"),
+            (
+                "content.xml",
+                "<office:document-content><office:body><office:text>\
+                 <text:h>Title</text:h>\
+                 <text:p>Id <text:span>291269</text:span>-2763</text:p>\
+                 </office:text></office:body></office:document-content>",
+            ),
+        ]);
+        assert_eq!(
+            found(&odt)[2],
+            (
+                "content.xml".to_string(),
+                "paragraph 2".to_string(),
+                "Id 291269-2763".to_string()
+            )
+        );
+
+        let ods = container(&[(
+            "content.xml",
+            "<office:document-content><office:body><office:spreadsheet>\
+             <table:table table:name=\"People\">\
+             <table:table-row table:number-rows-repeated=\"2\"><table:table-cell/></table:table-row>\
+             <table:table-row><table:table-cell table:number-columns-repeated=\"2\"/>\
+             <table:table-cell><text:p>291269-2763</text:p></table:table-cell></table:table-row>\
+             </table:table></office:spreadsheet></office:body></office:document-content>",
+        )]);
+        assert_eq!(
+            found(&ods),
+            vec![(
+                "content.xml".to_string(),
+                "People!C3".to_string(),
+                "291269-2763".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_ods_repeat_bounds() {
+        let ods = container(&[(
+            "content.xml",
+            "<office:document-content><office:body><office:spreadsheet>\
+             <table:table table:name=\"People\">\
+             <table:table-row table:number-rows-repeated=\"0\">\
+             <table:table-cell table:number-columns-repeated=\"0\"><text:p>a</text:p></table:table-cell>\
+             </table:table-row>\
+             <table:table-row table:number-rows-repeated=\"18446744073709551615\"/>\
+             <table:table-row><table:table-cell table:number-columns-repeated=\"18446744073709551615\"/>\
+             <table:table-cell><text:p>291269-2763</text:p></table:table-cell></table:table-row>\
+             </table:table></office:spreadsheet></office:body></office:document-content>",
+        )]);
+        let cells: Vec<_> = found(&ods)
+            .into_iter()
+            .map(|(_, location, _)| location)
+            .collect();
+        assert_eq!(cells.len(), 2);
+        assert_eq!(cells[0], "People!A1");
+    }
+
+    #[test]
+    fn test_redact() {
+        let docx = container(&[(
+            "word/document.xml",
+            "<w:p><w:r><w:t>Id 291269-2763</w:t></w:r></w:p>\
+             <w:p><w:r><w:t>291269</w:t></w:r><w:r><w:t>-2763</w:t></w:r></w:p>",
+        )]);
+        let redacted = found(&redact(&docx, '*').unwrap());
+        assert_eq!(redacted[0].2, "Id ***********");
+        assert_eq!(redacted[1].2, "291269-2763");
+    }
+
+    #[test]
+    fn test_redact_mask() {
+        let docx = container(&[(
+            "word/document.xml",
+            "<w:p><w:r><w:t>291269-2763</w:t></w:r></w:p>",
+        )]);
+        for mask in &['<', '&', '"', '\u{1}'] {
+            assert!(!is_xml_safe(*mask));
+            assert!(redact(&docx, *mask).is_err());
+        }
+        assert!(is_xml_safe('X'));
+        assert_eq!(found(&redact(&docx, 'X').unwrap())[0].2, "XXXXXXXXXXX");
+    }
+
+    #[test]
+    fn test_limits() {
+        let limits = |entries, part, total| Limits {
+            entries,
+            part,
+            total,
+        };
+        let docx = container(&[
+            (
+                "word/document.xml",
+                "<w:p><w:r><w:t>291269-2763</w:t></w:r></w:p>",
+            ),
+            ("docProps/app.xml", "<Properties/>"),
+        ]);
+        assert!(segments_within(&docx, &limits(2, 50, 100)).is_ok());
+        assert_eq!(
+            segments_within(&docx, &limits(1, 50, 100)),
+            Err("Container has more than 1 entries".to_string())
+        );
+        assert_eq!(
+            segments_within(&docx, &limits(2, 40, 100)),
+            Err("word/document.xml: Part is larger than 40 bytes uncompressed".to_string())
+        );
+        assert_eq!(
+            segments_within(&docx, &limits(2, 50, 50)),
+            Err(
+                "docProps/app.xml: Parts are larger than 50 bytes uncompressed in total"
+                    .to_string()
+            )
+        );
+        assert!(redact_within(&docx, '*', &limits(2, 40, 100)).is_err());
+    }
+
+    #[test]
+    fn test_column_name() {
+        assert_eq!(column_name(1), "A");
+        assert_eq!(column_name(26), "Z");
+        assert_eq!(column_name(27), "AA");
+        assert_eq!(column_name(703), "AAA");
+    }
+}
@@ -0,0 +1,71 @@
+use hetu::duplicates;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+
+use {DuplicatesArgs, Exit};
+
+/// Print groups of probable duplicates among codes from files or standard input, one code per line.
+pub fn run(args: &DuplicatesArgs) -> Result<(), Exit> {
+    let mut codes = Vec::new();
+    let mut locations = Vec::new();
+    let mut read = |reader: &mut dyn BufRead, path: &str| -> Result<(), Exit> {
+        for (number, line) in reader.lines().enumerate() {
+            let line = line.map_err(|err| {
+                eprintln!("Error: {}: {}", path, err);
+                Exit::Io
+            })?;
+            let code = line.trim();
+            if !code.is_empty() {
+                codes.push(code.to_string());
+                locations.push(format!("{}:{}", path, number + 1));
+            }
+        }
+        Ok(())
+    };
+    if args.files.is_empty() {
+        read(&mut io::stdin().lock(), "-")?;
+    }
+    for path in &args.files {
+        if path == "-" {
+            read(&mut io::stdin().lock(), "-")?;
+            continue;
+        }
+        let file = File::open(path).map_err(|err| {
+            eprintln!("Error: {}: {}", path, err);
+            Exit::Io
+        })?;
+        read(&mut BufReader::new(file), path)?;
+    }
+
+    let groups = duplicates::groups(&codes);
+    let mut count = 0;
+    for (i, group) in groups.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        for duplicate in &group.duplicates {
+            let (a, b) = (duplicate.first, duplicate.second);
+            println!(
+                "{}: {}  {}: {}  {} ({} confidence)",
+                locations[a],
+                codes[a],
+                locations[b],
+                codes[b],
+                duplicate.reason,
+                duplicate.reason.confidence()
+            );
+        }
+        count += group.duplicates.len();
+    }
+    eprintln!(
+        "Found {} probable duplicates in {} groups among {} HETUs",
+        count,
+        groups.len(),
+        codes.len()
+    );
+    if groups.is_empty() {
+        Ok(())
+    } else {
+        Err(Exit::Found)
+    }
+}
@@ -0,0 +1,404 @@
+use csv::{ByteRecord, Reader, ReaderBuilder};
+use hetu::{Claims, Date, Gender, GenderReform, GenderRule, ParseError, Ssn};
+use std::cell::RefCell;
+use std::convert::TryFrom;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::rc::Rc;
+
+use {CsvArgs, Exit};
+
+/// Byte order mark of UTF-8.
+const BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// Names of the columns added to every row.
+const COLUMNS: [&str; 5] = [
+    "hetu_valid",
+    "hetu_error",
+    "hetu_birth_date",
+    "hetu_gender",
+    "hetu_age",
+];
+
+/// Name of the column added when claimed fields are checked.
+const MISMATCHES: &str = "hetu_mismatches";
+
+/// Column of codes, by name or by 1-based index.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Column {
+    Name(String),
+    Index(usize),
+}
+
+/// Parse column from a 1-based index, e.g. `3`, or a header name.
+pub fn parse_column(value: &str) -> Result<Column, String> {
+    match value.parse::<usize>() {
+        Ok(0) => Err("column indexes start from 1".to_string()),
+        Ok(index) => Ok(Column::Index(index - 1)),
+        Err(_) => Ok(Column::Name(value.to_string())),
+    }
+}
+
+/// Parse single byte delimiter.
+pub fn parse_delimiter(value: &str) -> Result<u8, String> {
+    match value {
+        "\\t" | "tab" => Ok(b'\t'),
+        _ if value.len() == 1 => Ok(value.as_bytes()[0]),
+        _ => Err("delimiter must be a single ASCII character".to_string()),
+    }
+}
+
+fn io_error<E: ::std::fmt::Display>(path: &str) -> impl Fn(E) -> Exit + '_ {
+    move |err| {
+        eprintln!("Error: {}: {}", path, err);
+        Exit::Io
+    }
+}
+
+/// Reader that keeps a copy of the bytes it reads.
+struct Recorded<R> {
+    inner: R,
+    read: Rc<RefCell<Vec<u8>>>,
+}
+
+impl<R: Read> Read for Recorded<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.read.borrow_mut().extend_from_slice(&buf[..n]);
+        Ok(n)
+    }
+}
+
+/// CSV reader that returns every record also as the bytes it was read from, so rows can be written out untouched.
+struct Rows<R> {
+    reader: Reader<Recorded<R>>,
+    /// Bytes read but not yet returned in a row.
+    read: Rc<RefCell<Vec<u8>>>,
+    /// Byte offset of the first byte in `read`.
+    offset: u64,
+    /// Line break after the first row.
+    terminator: Option<&'static [u8]>,
+}
+
+impl<R: Read> Rows<R> {
+    fn new(inner: R, delimiter: u8) -> Rows<R> {
+        let read = Rc::new(RefCell::new(Vec::new()));
+        let reader = ReaderBuilder::new()
+            .delimiter(delimiter)
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(Recorded {
+                inner,
+                read: read.clone(),
+            });
+        Rows {
+            reader,
+            read,
+            offset: 0,
+            terminator: None,
+        }
+    }
+
+    /// Read the next record, and return the row it was read from without line breaks, `None` at the end.
+    fn next(&mut self, record: &mut ByteRecord) -> csv::Result<Option<Vec<u8>>> {
+        if !self.reader.read_byte_record(record)? {
+            return Ok(None);
+        }
+        let start = record
+            .position()
+            .map_or(self.offset, |position| position.byte());
+        let end = self.reader.position().byte();
+        let row: Vec<u8> = self
+            .read
+            .borrow_mut()
+            .drain(..(end - self.offset) as usize)
+            .skip(start.saturating_sub(self.offset) as usize)
+            .collect();
+        self.offset = end;
+        // A record starts after the line breaks of earlier rows and ends at the first byte of its own line break
+        let line_break = |byte: &u8| *byte == b'\r' || *byte == b'\n';
+        let first = row
+            .iter()
+            .position(|byte| !line_break(byte))
+            .unwrap_or(row.len());
+        let last = row
+            .iter()
+            .rposition(|byte| !line_break(byte))
+            .map_or(first, |i| i + 1);
+        if self.terminator.is_none() {
+            self.terminator = Some(if row[last..].starts_with(b"\r") {
+                b"\r\n"
+            } else {
+                b"\n"
+            });
+        }
+        Ok(Some(row[first..last].to_vec()))
+    }
+
+    /// Line break of the input, CRLF if the first row ends with one.
+    fn terminator(&self) -> &'static [u8] {
+        self.terminator.unwrap_or(b"\n")
+    }
+}
+
+/// Read CSV, validate a column of codes, and write the rows with decoded fields appended.
+///
+/// Rows are written as they were read, including quoting, with the fields appended and the line break of the input.
+pub fn run(args: &CsvArgs) -> Result<(), Exit> {
+    let input = args.input.as_deref().unwrap_or("-");
+    let mut reader: Box<dyn BufRead> = if input == "-" {
+        Box::new(BufReader::new(io::stdin()))
+    } else {
+        Box::new(BufReader::new(File::open(input).map_err(io_error(input))?))
+    };
+    let bom = reader.fill_buf().map_err(io_error(input))?.starts_with(BOM);
+    if bom {
+        reader.consume(BOM.len());
+    }
+    let mut rows = Rows::new(reader, args.delimiter);
+
+    let mut record = ByteRecord::new();
+    let header = if args.no_header {
+        None
+    } else {
+        match rows.next(&mut record).map_err(io_error(input))? {
+            Some(row) => Some((record.clone(), row)),
+            None => return Ok(()),
+        }
+    };
+    let header_record = header.as_ref().map(|(record, _)| record);
+    let index = position(&args.column, header_record)?;
+    let claimed = ClaimColumns {
+        birth_date: optional_position(&args.birth_date_column, header_record)?,
+        gender: optional_position(&args.gender_column, header_record)?,
+        age: optional_position(&args.age_column, header_record)?,
+    };
+    let check = claimed.birth_date.is_some() || claimed.gender.is_some() || claimed.age.is_some();
+
+    let output = args.output.as_deref().unwrap_or("-");
+    let mut out = writer(output, bom)?;
+    let mut quarantine = match args.quarantine {
+        Some(ref path) => Some((writer(path, bom)?, path.as_str())),
+        None => None,
+    };
+    let (delimiter, terminator) = (args.delimiter, rows.terminator());
+    if let Some((_, row)) = header {
+        if let Some((ref mut quarantine, path)) = quarantine {
+            write_row(quarantine, &row, &[] as &[&str], delimiter, terminator)
+                .map_err(io_error(path))?;
+        }
+        let mut columns = COLUMNS.to_vec();
+        if check {
+            columns.push(MISMATCHES);
+        }
+        write_row(&mut out, &row, &columns, delimiter, terminator).map_err(io_error(output))?;
+    }
+
+    let on = args.on.unwrap_or_else(Date::today);
+    let rule = args
+        .gender_neutral_from
+        .map(|date| GenderReform::new(date).rule_on(&on))
+        .unwrap_or_default();
+    while let Some(row) = rows.next(&mut record).map_err(io_error(input))? {
+        let terminator = rows.terminator();
+        let code = String::from_utf8_lossy(record.get(index).unwrap_or_default());
+        let result = Ssn::try_from(code.trim());
+        if result.is_err() {
+            if let Some((ref mut quarantine, path)) = quarantine {
+                write_row(quarantine, &row, &[] as &[&str], delimiter, terminator)
+                    .map_err(io_error(path))?;
+                continue;
+            }
+            if args.drop_invalid {
+                continue;
+            }
+        }
+        let mismatches = match result {
+            Ok(ref ssn) if check => Some(mismatches(ssn, &claimed, &record, &on, rule)),
+            _ if check => Some(String::new()),
+            _ => None,
+        };
+        let mut added = fields(&result, &on, rule).to_vec();
+        added.extend(mismatches);
+        write_row(&mut out, &row, &added, delimiter, terminator).map_err(io_error(output))?;
+    }
+    out.flush().map_err(io_error(output))?;
+    if let Some((mut quarantine, path)) = quarantine {
+        quarantine.flush().map_err(io_error(path))?;
+    }
+    Ok(())
+}
+
+/// Index of a column by name in the header or by index.
+fn position(column: &Column, header: Option<&ByteRecord>) -> Result<usize, Exit> {
+    match (column, header) {
+        (Column::Index(index), _) => Ok(*index),
+        (Column::Name(name), Some(header)) => header
+            .iter()
+            .position(|field| field == name.as_bytes())
+            .ok_or_else(|| {
+                eprintln!("Error: No column named {}", name);
+                Exit::Usage
+            }),
+        (Column::Name(_), None) => {
+            eprintln!("Error: Column must be an index when there is no header");
+            Err(Exit::Usage)
+        }
+    }
+}
+
+fn optional_position(
+    column: &Option<Column>,
+    header: Option<&ByteRecord>,
+) -> Result<Option<usize>, Exit> {
+    column
+        .as_ref()
+        .map(|column| position(column, header))
+        .transpose()
+}
+
+/// Indexes of the columns of claimed fields.
+struct ClaimColumns {
+    birth_date: Option<usize>,
+    gender: Option<usize>,
+    age: Option<usize>,
+}
+
+/// Names of the claimed fields of a row that contradict the code, separated by semicolons.
+///
+/// Empty claims are not checked, and claims that cannot be parsed are mismatches.
+///
+/// Claimed genders are not checked if the rule doesn't encode gender in the code.
+fn mismatches(
+    ssn: &Ssn,
+    columns: &ClaimColumns,
+    record: &ByteRecord,
+    on: &Date,
+    rule: GenderRule,
+) -> String {
+    let claim = |column: Option<usize>| {
+        column
+            .and_then(|index| record.get(index))
+            .map(|value| String::from_utf8_lossy(value).trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+    let mut invalid = Vec::new();
+    let mut claims = Claims::default();
+    if let Some(value) = claim(columns.birth_date) {
+        match parse_claimed_date(&value) {
+            Some(date) => claims.birth_date = Some(date),
+            None => invalid.push("birth_date"),
+        }
+    }
+    if let Some(value) = claim(columns.gender) {
+        match parse_claimed_gender(&value) {
+            Some(gender) => claims.gender = Some(gender),
+            None => invalid.push("gender"),
+        }
+    }
+    if let Some(value) = claim(columns.age) {
+        match value.parse() {
+            Ok(age) => claims.age = Some((age, *on)),
+            Err(_) => invalid.push("age"),
+        }
+    }
+    let mut fields: Vec<&str> = ssn
+        .mismatches_by(&claims, rule)
+        .iter()
+        .map(|mismatch| mismatch.field())
+        .collect();
+    fields.extend(invalid);
+    fields.join(";")
+}
+
+/// Parse date in ISO 8601 format, e.g. `1969-12-29`, or in Finnish format, e.g. `29.12.1969`.
+fn parse_claimed_date(value: &str) -> Option<Date> {
+    if let Ok(date) = Date::try_from(value) {
+        return Some(date);
+    }
+    let parts: Vec<usize> = value
+        .split('.')
+        .map(|part| part.parse().ok())
+        .collect::<Option<_>>()?;
+    match parts[..] {
+        [day, month, year] if year >= 1000 => Date::new(year, month, day),
+        _ => None,
+    }
+}
+
+/// Parse gender in English, Finnish or Swedish, or by its initial.
+fn parse_claimed_gender(value: &str) -> Option<Gender> {
+    match value.to_lowercase().as_str() {
+        "female" | "f" | "nainen" | "n" | "kvinna" | "k" => Some(Gender::Female),
+        "male" | "m" | "mies" | "man" => Some(Gender::Male),
+        _ => None,
+    }
+}
+
+/// Values of the added columns, with an empty gender if the rule doesn't encode gender in the code.
+fn fields(result: &Result<Ssn, ParseError>, on: &Date, rule: GenderRule) -> [String; 5] {
+    match *result {
+        Ok(ref ssn) => [
+            "true".to_string(),
+            String::new(),
+            ssn.birth_date().to_string(),
+            ssn.gender_by(rule)
+                .map_or_else(String::new, |gender| gender.name().to_string()),
+            ssn.age_on(on)
+                .map_or_else(String::new, |age| age.to_string()),
+        ],
+        Err(ref err) => [
+            "false".to_string(),
+            err.kind().to_string(),
+            String::new(),
+            String::new(),
+            String::new(),
+        ],
+    }
+}
+
+/// Writer to a file or standard output, starting with a byte order mark if the input had one.
+fn writer(path: &str, bom: bool) -> Result<BufWriter<Box<dyn Write>>, Exit> {
+    let out: Box<dyn Write> = if path == "-" {
+        Box::new(io::stdout())
+    } else {
+        Box::new(File::create(path).map_err(io_error(path))?)
+    };
+    let mut out = BufWriter::new(out);
+    if bom {
+        out.write_all(BOM).map_err(io_error(path))?;
+    }
+    Ok(out)
+}
+
+/// Write a row as it was read with fields appended, quoting fields that contain the delimiter, a quote or a line
+/// break.
+fn write_row<W: Write, F: AsRef<[u8]>>(
+    out: &mut W,
+    row: &[u8],
+    fields: &[F],
+    delimiter: u8,
+    terminator: &[u8],
+) -> io::Result<()> {
+    out.write_all(row)?;
+    for field in fields {
+        let field = field.as_ref();
+        out.write_all(&[delimiter])?;
+        if field
+            .iter()
+            .any(|&byte| byte == delimiter || byte == b'"' || byte == b'\r' || byte == b'\n')
+        {
+            out.write_all(b"\"")?;
+            for &byte in field {
+                if byte == b'"' {
+                    out.write_all(b"\"")?;
+                }
+                out.write_all(&[byte])?;
+            }
+            out.write_all(b"\"")?;
+        } else {
+            out.write_all(field)?;
+        }
+    }
+    out.write_all(terminator)
+}
@@ -0,0 +1,274 @@
+use clap::ValueEnum;
+use hetu::{scan, Ssn};
+use rand::{Rng, SeedableRng, StdRng};
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{self, Read, Write};
+
+use diagnostic::language;
+use generate::constraints;
+use {Exit, FillArgs, Gender, GenerateArgs};
+
+/// Placeholder in a template, e.g. `{{hetu#alice gender=f born=1990..1999}}`.
+#[derive(Debug, PartialEq)]
+struct Placeholder {
+    /// Byte range of the placeholder, including braces.
+    start: usize,
+    end: usize,
+    /// Label that shares one code between placeholders.
+    label: Option<String>,
+    /// Options as written, for comparing placeholders with the same label.
+    options: Vec<(String, String)>,
+}
+
+/// Replace placeholders in a template with distinct generated codes.
+pub fn run(args: &FillArgs) -> Result<(), Exit> {
+    let input = args.input.as_deref().unwrap_or("-");
+    let mut template = String::new();
+    let res = if input == "-" {
+        io::stdin().read_to_string(&mut template)
+    } else {
+        File::open(input).and_then(|mut file| file.read_to_string(&mut template))
+    };
+    res.map_err(|err| {
+        eprintln!("Error: {}: {}", input, err);
+        Exit::Io
+    })?;
+
+    let filled = match args.seed {
+        Some(seed) => fill(
+            input,
+            &template,
+            &mut StdRng::from_seed(&[seed as usize][..]),
+        ),
+        None => fill(input, &template, &mut ::rand::thread_rng()),
+    }?;
+
+    let output = args.output.as_deref().unwrap_or("-");
+    let res = if output == "-" {
+        io::stdout().write_all(filled.as_bytes())
+    } else {
+        File::create(output).and_then(|mut file| file.write_all(filled.as_bytes()))
+    };
+    res.map_err(|err| {
+        eprintln!("Error: {}: {}", output, err);
+        Exit::Io
+    })
+}
+
+/// Fill placeholders in template. Generated codes differ from each other and from codes already in the template.
+fn fill<R: Rng>(path: &str, template: &str, rng: &mut R) -> Result<String, Exit> {
+    let placeholders = placeholders(template).map_err(|(offset, message)| {
+        eprintln!(
+            "Error: {}:{}: {}",
+            path,
+            location(template, offset),
+            message
+        );
+        Exit::Usage
+    })?;
+    let mut used: HashSet<String> = scan::find(template)
+        .map(|m| m.as_str(template).to_string())
+        .collect();
+    // Code and first placeholder of each label
+    let mut labels: HashMap<&str, (String, &Placeholder)> = HashMap::new();
+    let mut res = String::with_capacity(template.len());
+    let mut last = 0;
+    for placeholder in &placeholders {
+        let error = |message: String| {
+            eprintln!(
+                "Error: {}:{}: {}",
+                path,
+                location(template, placeholder.start),
+                message
+            );
+        };
+        res.push_str(&template[last..placeholder.start]);
+        last = placeholder.end;
+        if let Some(ref label) = placeholder.label {
+            if let Some((code, first)) = labels.get(label.as_str()) {
+                if !placeholder.options.is_empty() && placeholder.options != first.options {
+                    error(format!("Options differ from earlier #{}", label));
+                    return Err(Exit::Usage);
+                }
+                res.push_str(code);
+                continue;
+            }
+        }
+        let args = generate_args(&placeholder.options).map_err(|message| {
+            error(message);
+            Exit::Usage
+        })?;
+        let constraints = constraints(&args)?;
+        // The iterator starts over after every matching code, so stop after one round
+        let available = Ssn::count_by_constraints(&constraints).unwrap_or(0);
+        let code = Ssn::iter_by_constraints_with_rng(&constraints, rng)
+            .map_err(|err| {
+                error(err.to_string_in(language()));
+                Exit::Generate
+            })?
+            .take(available)
+            .find(|code| !used.contains(code))
+            .ok_or_else(|| {
+                error("No more distinct HETUs match the placeholder".to_string());
+                Exit::Generate
+            })?;
+        used.insert(code.clone());
+        res.push_str(&code);
+        if let Some(ref label) = placeholder.label {
+            labels.insert(label, (code, placeholder));
+        }
+    }
+    res.push_str(&template[last..]);
+    Ok(res)
+}
+
+/// Find `{{hetu}}` placeholders. Other `{{...}}` expressions are left as is. Returns the offset and description of
+/// the first malformed placeholder as an error.
+fn placeholders(template: &str) -> Result<Vec<Placeholder>, (usize, String)> {
+    let mut res = Vec::new();
+    let mut offset = 0;
+    while let Some(i) = template[offset..].find("{{") {
+        let start = offset + i;
+        let body_start = start + 2;
+        let end = match template[body_start..].find("}}") {
+            Some(j) => body_start + j,
+            None => break,
+        };
+        let body = template[body_start..end].trim();
+        let rest = match body.strip_prefix("hetu") {
+            Some(rest) if rest.is_empty() || rest.starts_with(['#', ' ', '\t']) => rest,
+            _ => {
+                offset = body_start;
+                continue;
+            }
+        };
+        let mut words = rest.split_whitespace();
+        let label = if rest.starts_with('#') {
+            let label = &words.next().unwrap_or_default()[1..];
+            if label.is_empty()
+                || !label
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || c == '_' || c == '-' || c == '.')
+            {
+                return Err((start, format!("Invalid label '#{}'", label)));
+            }
+            Some(label.to_string())
+        } else {
+            None
+        };
+        let mut options = Vec::new();
+        for word in words {
+            match word.split_once('=') {
+                Some((key, value)) if !value.is_empty() => {
+                    options.push((key.to_string(), value.to_string()))
+                }
+                _ => return Err((start, format!("Invalid option '{}'", word))),
+            }
+        }
+        res.push(Placeholder {
+            start,
+            end: end + 2,
+            label,
+            options,
+        });
+        offset = end + 2;
+    }
+    Ok(res)
+}
+
+/// Generation arguments from placeholder options.
+fn generate_args(options: &[(String, String)]) -> Result<GenerateArgs, String> {
+    let mut args = GenerateArgs::default();
+    for (key, value) in options {
+        match key.as_str() {
+            "gender" => args.gender = Some(Gender::from_str(value, true)?),
+            "born" => args.born = Some(value.clone()),
+            "pattern" => args.pattern = Some(value.clone()),
+            _ => {
+                return Err(format!(
+                    "Unknown option '{}', expected gender, born or pattern",
+                    key
+                ))
+            }
+        }
+    }
+    Ok(args)
+}
+
+/// 1-based line and column, in characters, of a byte offset.
+fn location(text: &str, offset: usize) -> String {
+    let before = &text[..offset];
+    let line = before.matches('\n').count() + 1;
+    let column = before[before.rfind('\n').map_or(0, |i| i + 1)..]
+        .chars()
+        .count()
+        + 1;
+    format!("{}:{}", line, column)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_placeholders() {
+        let template =
+            "{{ name }} {{hetu}}\n{{ hetu#alice gender=f born=1990..1999 }}{{hetu#alice}}";
+        let found = placeholders(template).unwrap();
+        assert_eq!(found.len(), 3);
+        assert_eq!((found[0].start, found[0].end), (11, 19));
+        assert_eq!(found[1].label, Some("alice".to_string()));
+        assert_eq!(
+            found[1].options,
+            vec![
+                ("gender".to_string(), "f".to_string()),
+                ("born".to_string(), "1990..1999".to_string())
+            ]
+        );
+        assert!(found[2].options.is_empty());
+        assert_eq!(placeholders("{{hetus}} {{hetu"), Ok(Vec::new()));
+        assert!(placeholders("{{hetu gender}}").is_err());
+        assert!(placeholders("{{hetu# x}}").is_err());
+    }
+
+    #[test]
+    fn test_fill() {
+        let template =
+            "a: {{hetu#a gender=f born=1990}}\nb: {{hetu pattern=010101-12??}}\nc: {{hetu#a}}\n";
+        let mut rng = StdRng::from_seed(&[1][..]);
+        let filled = fill("-", template, &mut rng).unwrap();
+        let codes: Vec<&str> = filled.lines().map(|line| &line[3..]).collect();
+        let a = Ssn::try_from(codes[0]).unwrap();
+        assert_eq!(a.gender, hetu::Gender::Female);
+        assert_eq!(a.birth_date().year, 1990);
+        assert!(codes[1].starts_with("010101-12"));
+        assert_eq!(codes[0], codes[2]);
+
+        let mut rng = StdRng::from_seed(&[1][..]);
+        assert_eq!(fill("-", template, &mut rng).unwrap(), filled);
+    }
+
+    #[test]
+    fn test_fill_unique() {
+        // Identifiers 120-129 of the date hold 10 codes, one of which is already in the template
+        let template = format!(
+            "010101-123N\n{}",
+            "{{hetu pattern=010101-12??}}\n".repeat(9)
+        );
+        let mut rng = StdRng::from_seed(&[1][..]);
+        let filled = fill("-", &template, &mut rng).unwrap();
+        let codes: HashSet<_> = scan::find(&filled).map(|m| m.as_str(&filled)).collect();
+        assert_eq!(codes.len(), 10);
+
+        let template = template + "{{hetu pattern=010101-12??}}";
+        assert_eq!(fill("-", &template, &mut rng), Err(Exit::Generate));
+    }
+
+    #[test]
+    fn test_location() {
+        assert_eq!(location("ab\ncä{{", 6), "2:3");
+        assert_eq!(location("{{", 0), "1:1");
+    }
+}
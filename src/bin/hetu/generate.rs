@@ -0,0 +1,150 @@
+use hetu::{Constraints, ErrorIndexRange, GenderRule, Ssn, SsnPattern};
+use rand::{SeedableRng, StdRng};
+use std::convert::TryFrom;
+use std::fmt;
+
+use diagnostic::{language, print_error, Labelled};
+use output::Output;
+use {Exit, Gender, GenerateArgs};
+
+/// Print distinct generated codes that match all given constraints.
+pub fn run(args: &GenerateArgs) -> Result<(), Exit> {
+    let constraints = constraints(args)?;
+    let available = Ssn::count_by_constraints(&constraints).unwrap_or(0);
+    if available == 0 {
+        eprintln!("Error: No HETU matches the constraints");
+        return Err(Exit::Generate);
+    }
+    if args.count > available {
+        eprintln!(
+            "Error: Only {} distinct HETUs match the constraints",
+            available
+        );
+        return Err(Exit::Generate);
+    }
+    let codes: Vec<String> = match args.seed {
+        Some(seed) => {
+            let mut rng = StdRng::from_seed(&[seed as usize][..]);
+            Ssn::iter_by_constraints_with_rng(&constraints, &mut rng)
+                .map(|iter| iter.take(args.count).collect())
+        }
+        None => Ssn::iter_by_constraints(&constraints).map(|iter| iter.take(args.count).collect()),
+    }
+    .map_err(|err| {
+        eprintln!("Error: {}", err.to_string_in(language()));
+        Exit::Generate
+    })?;
+    match Output::new(
+        args.output.format,
+        args.output.on(),
+        args.output.gender_rule(),
+    ) {
+        Some(mut output) => {
+            for code in &codes {
+                output.write(code, None, &Ssn::try_from(code.as_str()), &[])?;
+            }
+            output.finish()
+        }
+        None => {
+            for code in codes {
+                println!("{}", code);
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Error in an argument, or conflicting arguments, when combining arguments into constraints.
+#[derive(Debug)]
+pub struct ConstraintsError {
+    /// Pattern or query that failed to parse, `None` if valid arguments conflict.
+    pub input: Option<String>,
+    pub message: String,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl fmt::Display for ConstraintsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl ErrorIndexRange for ConstraintsError {
+    fn start(&self) -> usize {
+        self.start
+    }
+
+    fn end(&self) -> usize {
+        self.end
+    }
+}
+
+impl Labelled for ConstraintsError {}
+
+/// Combine pattern, query, gender and birth date range into constraints, printing errors.
+pub fn constraints(args: &GenerateArgs) -> Result<Constraints, Exit> {
+    try_constraints(args).map_err(|err| {
+        match err.input {
+            Some(ref input) => print_error(&err, input),
+            None => eprintln!("Error: {}", err),
+        }
+        Exit::Usage
+    })
+}
+
+/// Combine pattern, query, gender and birth date range into constraints.
+pub fn try_constraints(args: &GenerateArgs) -> Result<Constraints, ConstraintsError> {
+    let mut constraints = Constraints::default();
+    if let Some(ref pattern) = args.pattern {
+        let pattern = parse(pattern, SsnPattern::try_from)?;
+        constraints = intersect(&constraints, &Constraints::from(pattern))?;
+    }
+    if let Some(ref query) = args.query {
+        constraints = intersect(&constraints, &parse(query, Constraints::try_from)?)?;
+    }
+    if let Some(gender) = args.gender {
+        if args.output.gender_rule() == GenderRule::Neutral {
+            return Err(ConstraintsError {
+                input: None,
+                message: language()
+                    .translate("Gender is not encoded by gender-neutral codes")
+                    .to_string(),
+                start: 0,
+                end: 0,
+            });
+        }
+        let query = match gender {
+            Gender::Female => "female",
+            Gender::Male => "male",
+        };
+        constraints = intersect(&constraints, &parse(query, Constraints::try_from)?)?;
+    }
+    if let Some(ref born) = args.born {
+        let query = format!("born:{}", born);
+        constraints = intersect(&constraints, &parse(&query, Constraints::try_from)?)?;
+    }
+    Ok(constraints.with_profile(args.profile.into()))
+}
+
+fn parse<'a, T, E, F>(input: &'a str, parse: F) -> Result<T, ConstraintsError>
+where
+    E: Labelled,
+    F: Fn(&'a str) -> Result<T, E>,
+{
+    parse(input).map_err(|err| ConstraintsError {
+        input: Some(input.to_string()),
+        message: err.localized(),
+        start: err.start(),
+        end: err.end(),
+    })
+}
+
+fn intersect(a: &Constraints, b: &Constraints) -> Result<Constraints, ConstraintsError> {
+    a.intersection(b).ok_or_else(|| ConstraintsError {
+        input: None,
+        message: language().translate("Conflicting constraints").to_string(),
+        start: 0,
+        end: 0,
+    })
+}
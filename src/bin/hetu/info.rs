@@ -0,0 +1,123 @@
+use hetu::{Separators, Ssn, CHECKSUM_TABLE};
+use std::convert::TryFrom;
+
+use diagnostic::{language, print_error};
+use output::Output;
+use {Exit, InfoArgs};
+
+/// Print decoded fields of a code and how its checksum is calculated.
+pub fn run(args: &InfoArgs) -> Result<(), Exit> {
+    let on = args.output.on();
+    if let Some(mut output) = Output::new(args.output.format, on, args.output.gender_rule()) {
+        let result = Ssn::try_from(args.code.as_str());
+        output.write(&args.code, None, &result, &[])?;
+        output.finish()?;
+        return result.map(|_| ()).map_err(|_| Exit::Invalid);
+    }
+    let ssn = Ssn::try_from(args.code.as_str()).map_err(|ref err| {
+        print_error(err, &args.code);
+        Exit::Invalid
+    })?;
+    let language = language();
+    let labels = [
+        "Birth date",
+        "Age",
+        "Century",
+        "Separator",
+        "Gender",
+        "Identifier",
+        "Temporary",
+        "Checksum",
+    ];
+    // Values line up after the longest label in the language
+    let width = labels
+        .iter()
+        .map(|label| language.translate(label).chars().count())
+        .max()
+        .unwrap_or_default()
+        + 2;
+    let field = |label: &str, value: String| {
+        let label = format!("{}:", language.translate(label));
+        println!("{:<width$}{}", label, value, width = width);
+    };
+    let born = ssn.birth_date();
+    field(
+        "Birth date",
+        format!(
+            "{}, {}",
+            born,
+            language.translate(&born.weekday().to_string())
+        ),
+    );
+    field(
+        "Age",
+        match ssn.age_on(&on) {
+            Some(age) => language.format("{} on {}", &[&age, &on]),
+            None => language.format("not born on {}", &[&on]),
+        },
+    );
+    field("Century", language.century(ssn.year));
+    field(
+        "Separator",
+        format!(
+            "{} ({})",
+            ssn.separator(),
+            language.translate(if Separators::legacy().contains(ssn.separator()) {
+                "legacy, in use before 2023"
+            } else {
+                "introduced in 2023"
+            })
+        ),
+    );
+    field(
+        "Gender",
+        match ssn.gender_by(args.output.gender_rule()) {
+            Some(gender) => format!(
+                "{} ({})",
+                language.gender(gender),
+                language.format(
+                    "identifier is {}",
+                    &[&language.translate(if ssn.identifier() % 2 == 0 {
+                        "even"
+                    } else {
+                        "odd"
+                    })]
+                )
+            ),
+            None => language
+                .translate("unknown (gender-neutral code)")
+                .to_string(),
+        },
+    );
+    field("Identifier", format!("{:03}", ssn.identifier()));
+    field(
+        "Temporary",
+        language
+            .translate(if ssn.is_temporary() {
+                "yes (identifier in range 900-999)"
+            } else {
+                "no"
+            })
+            .to_string(),
+    );
+    let number = ssn.checksum_number();
+    let remainder = number % 31;
+    field("Checksum", ssn.checksum().to_string());
+    println!(
+        "    {:09} = {}",
+        number,
+        language.format(
+            "birth date {} and identifier {}",
+            &[
+                &format!("{:02}{:02}{:02}", ssn.day, ssn.month, ssn.year % 100),
+                &format!("{:03}", ssn.identifier()),
+            ]
+        )
+    );
+    println!("    {:09} mod 31 = {}", number, remainder);
+    println!(
+        "    CHECKSUM_TABLE[{}] = '{}'",
+        remainder, CHECKSUM_TABLE[remainder]
+    );
+    Ok(())
+}
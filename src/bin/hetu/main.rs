@@ -0,0 +1,569 @@
+extern crate ansi_term;
+extern crate clap;
+extern crate csv;
+extern crate hetu;
+extern crate ignore;
+extern crate quick_xml;
+extern crate rand;
+#[macro_use]
+extern crate serde_json;
+extern crate tiny_http;
+extern crate zip;
+
+mod allocate;
+mod allowlist;
+mod diagnostic;
+mod diff;
+mod document;
+mod duplicates;
+mod enrich;
+mod fill;
+mod generate;
+mod info;
+mod output;
+mod people;
+mod scan;
+mod serve;
+mod validate;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use hetu::{Date, GenderReform, GenderRule, Language, Profile, Severity, WarningKind};
+use std::convert::TryFrom;
+use std::env;
+use std::io::{self, IsTerminal};
+use std::ops::RangeInclusive;
+use std::process;
+
+/// Validator and generator for Finnish Personal Identity Code (HETU).
+#[derive(Parser, Debug)]
+#[command(name = "hetu", version, after_help = EXAMPLES)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+    /// When to color diagnostics. Auto colors them when standard error is a terminal and NO_COLOR is not set
+    #[arg(long, global = true, value_enum, default_value_t = ColorChoice::Auto)]
+    color: ColorChoice,
+    /// Language of messages: fi, sv or en. Defaults to the language of LC_ALL, LC_MESSAGES or LANG, then English
+    #[arg(long, global = true, value_name = "LANG", value_parser = parse_language)]
+    lang: Option<Language>,
+}
+
+impl Cli {
+    /// Language from the option or the locale.
+    fn language(&self) -> Language {
+        self.lang
+            .or_else(|| {
+                ["LC_ALL", "LC_MESSAGES", "LANG"]
+                    .iter()
+                    .filter_map(|name| env::var(name).ok())
+                    .find(|locale| !locale.is_empty())
+                    .and_then(|locale| Language::from_locale(&locale))
+            })
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default, ValueEnum)]
+enum ColorChoice {
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    /// Whether to color diagnostics written to standard error.
+    fn enabled(self) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+                    && io::stderr().is_terminal()
+            }
+        }
+    }
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Validate HETUs from arguments, files or standard input
+    Validate(ValidateArgs),
+    /// Generate HETUs
+    Generate(GenerateArgs),
+    /// Decode and explain a HETU
+    Info(InfoArgs),
+    /// Validate a column of HETUs in CSV and append decoded fields to each row
+    Csv(CsvArgs),
+    /// Find valid HETUs in files and directories
+    Scan(ScanArgs),
+    /// Mask valid HETUs in text or documents
+    Redact(RedactArgs),
+    /// Find HETUs on lines added in staged git changes, e.g. in a pre-commit hook
+    Diff(DiffArgs),
+    /// Replace {{hetu}} placeholders in a template with distinct generated HETUs
+    Fill(FillArgs),
+    /// Generate synthetic people with distinct HETUs and Finnish names
+    People(PeopleArgs),
+    /// Serve validation, generation and redaction as a local HTTP service with JSON endpoints
+    Serve(ServeArgs),
+    /// Issue the next unused HETUs for a birth date like the population register
+    Allocate(AllocateArgs),
+    /// Find probable duplicates among HETUs, e.g. the same person entered with a mistyped HETU
+    Duplicates(DuplicatesArgs),
+}
+
+#[derive(Args, Debug)]
+struct ValidateArgs {
+    /// HETUs to validate. Use a dash ('-') to read from standard input
+    codes: Vec<String>,
+    /// Read HETUs from a file, one per line
+    #[arg(short, long = "file", value_name = "FILE")]
+    files: Vec<String>,
+    /// Stop at the first invalid HETU
+    #[arg(long)]
+    fail_fast: bool,
+    /// Number of invalid HETUs, or a percentage of all HETUs with a '%' suffix, allowed before exiting with an error
+    #[arg(
+        long,
+        value_name = "COUNT|PERCENT%",
+        default_value = "0",
+        value_parser = validate::parse_threshold
+    )]
+    max_invalid: validate::Threshold,
+    /// Severity of a kind of warning about suspicious but valid HETUs, e.g. 'temporary=off'
+    #[arg(long, value_name = "KIND=SEVERITY", long_help = WARN_HELP, value_parser = validate::parse_warn)]
+    warn: Vec<(WarningKind, Severity)>,
+    /// Separators accepted. Legacy rejects the separators introduced in 2023
+    #[arg(long, value_enum, default_value_t = Compatibility::Modern)]
+    profile: Compatibility,
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Args, Debug, Default)]
+struct GenerateArgs {
+    /// Number of distinct HETUs to generate
+    #[arg(short = 'n', long, default_value_t = 1)]
+    count: usize,
+    /// Generate HETUs by pattern
+    #[arg(short, long, long_help = PATTERN_HELP)]
+    pattern: Option<String>,
+    /// Generate HETUs by query
+    #[arg(short, long, long_help = QUERY_HELP)]
+    query: Option<String>,
+    /// Seed for the random number generator to generate the same HETUs on every run
+    #[arg(long)]
+    seed: Option<u64>,
+    /// Gender of the generated HETUs
+    #[arg(short, long)]
+    gender: Option<Gender>,
+    /// Birth date range, e.g. '1980-01-01..1989-12-31', '1980..1989', '1985-06' or '..1999'
+    #[arg(short, long, value_name = "RANGE")]
+    born: Option<String>,
+    /// Separators generated. Legacy avoids the separators introduced in 2023
+    #[arg(long, value_enum, default_value_t = Compatibility::Modern)]
+    profile: Compatibility,
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Args, Debug)]
+struct InfoArgs {
+    /// HETU to decode
+    code: String,
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Args, Debug, Default)]
+struct OutputArgs {
+    /// Output format
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+    /// Reference date for ages, defaults to today
+    #[arg(long, value_name = "DATE", value_parser = parse_date)]
+    on: Option<Date>,
+    /// Interpret HETUs as gender-neutral if the reference date is on or after DATE
+    #[arg(long, value_name = "DATE", value_parser = parse_date)]
+    gender_neutral_from: Option<Date>,
+}
+
+impl OutputArgs {
+    /// Reference date for ages.
+    fn on(&self) -> Date {
+        self.on.unwrap_or_else(Date::today)
+    }
+
+    /// Gender rule in force on the reference date.
+    fn gender_rule(&self) -> GenderRule {
+        self.gender_neutral_from
+            .map(|date| GenderReform::new(date).rule_on(&self.on()))
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default, ValueEnum)]
+enum Format {
+    /// Human-readable text
+    #[default]
+    Text,
+    /// JSON array of records
+    Json,
+    /// JSON Lines, one record per line
+    Jsonl,
+    /// CSV with a header row
+    Csv,
+}
+
+#[derive(Args, Debug)]
+struct CsvArgs {
+    /// CSV file to read. Standard input is read if no file is given
+    input: Option<String>,
+    /// Column of HETUs, by header name or 1-based index
+    #[arg(short, long, default_value = "1", value_parser = enrich::parse_column)]
+    column: enrich::Column,
+    /// Field delimiter, e.g. ';' or 'tab'
+    #[arg(short, long, default_value = ",", value_parser = enrich::parse_delimiter)]
+    delimiter: u8,
+    /// Input doesn't have a header row
+    #[arg(long)]
+    no_header: bool,
+    /// File to write the CSV to. Standard output is written if no file is given
+    #[arg(short, long, value_name = "FILE")]
+    output: Option<String>,
+    /// Leave rows with an invalid HETU out of the output
+    #[arg(long)]
+    drop_invalid: bool,
+    /// Move rows with an invalid HETU to a separate file
+    #[arg(long, value_name = "FILE")]
+    quarantine: Option<String>,
+    /// Reference date for ages, defaults to today
+    #[arg(long, value_name = "DATE", value_parser = parse_date)]
+    on: Option<Date>,
+    /// Interpret HETUs as gender-neutral if the reference date is on or after DATE
+    #[arg(long, value_name = "DATE", value_parser = parse_date)]
+    gender_neutral_from: Option<Date>,
+    /// Column of claimed birth dates to check against the HETU, e.g. 1969-12-29 or 29.12.1969
+    #[arg(long, value_name = "COLUMN", value_parser = enrich::parse_column)]
+    birth_date_column: Option<enrich::Column>,
+    /// Column of claimed genders to check against the HETU, female or male
+    #[arg(long, value_name = "COLUMN", value_parser = enrich::parse_column)]
+    gender_column: Option<enrich::Column>,
+    /// Column of claimed ages on the reference date to check against the HETU
+    #[arg(long, value_name = "COLUMN", value_parser = enrich::parse_column)]
+    age_column: Option<enrich::Column>,
+}
+
+#[derive(Args, Debug)]
+struct ScanArgs {
+    /// Files or directories to scan recursively, including DOCX, XLSX, ODT, ODS and ZIP files. Standard input is
+    /// scanned if no paths are given
+    paths: Vec<String>,
+    /// Output format
+    #[arg(long, value_enum, default_value_t = ScanFormat::Text)]
+    format: ScanFormat,
+    /// Exclude paths that match a .gitignore style glob, in addition to .gitignore and .hetuignore files
+    #[arg(short, long, value_name = "GLOB")]
+    exclude: Vec<String>,
+    /// Scan hidden files and directories
+    #[arg(long)]
+    hidden: bool,
+    /// File of allowed HETUs or patterns, one per line
+    #[arg(long, value_name = "FILE")]
+    allowlist: Option<String>,
+    /// Allow temporary HETUs with identifier in range 900-999
+    #[arg(long)]
+    allow_temporary: bool,
+}
+
+#[derive(Args, Debug)]
+struct DiffArgs {
+    /// Use a dash ('-') to read a unified diff from standard input instead of running 'git diff --cached'
+    input: Option<String>,
+    /// File of allowed HETUs or patterns, one per line. Temporary HETUs are always allowed
+    #[arg(long, value_name = "FILE")]
+    allowlist: Option<String>,
+}
+
+#[derive(Args, Debug)]
+#[command(long_about = FILL_HELP)]
+struct FillArgs {
+    /// Template file. Standard input is read if no file is given
+    input: Option<String>,
+    /// Write to file instead of standard output
+    #[arg(short, long, value_name = "FILE")]
+    output: Option<String>,
+    /// Seed for the random number generator to fill in the same HETUs on every run
+    #[arg(long)]
+    seed: Option<u64>,
+}
+
+#[derive(Args, Debug)]
+struct PeopleArgs {
+    /// Number of people to generate
+    #[arg(short = 'n', long, default_value_t = 1)]
+    count: usize,
+    /// Distribution of ages on the reference date
+    #[arg(long, value_enum, default_value_t = Ages::Uniform)]
+    ages: Ages,
+    /// Seed for the random number generator to generate the same people on every run
+    #[arg(long)]
+    seed: Option<u64>,
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Args, Debug)]
+#[command(long_about = SERVE_HELP)]
+struct ServeArgs {
+    /// Address and port to listen on
+    #[arg(long, value_name = "ADDRESS", default_value = "127.0.0.1:8080")]
+    listen: String,
+    /// Maximum size of a request body in bytes
+    #[arg(long, value_name = "BYTES", default_value_t = 1024 * 1024)]
+    max_body: usize,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default, ValueEnum)]
+enum Ages {
+    /// Every age from 0 to 99 is equally likely
+    #[default]
+    Uniform,
+    /// Ages follow the age structure of the population of Finland
+    Population,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default, ValueEnum)]
+enum ScanFormat {
+    /// Location and masked snippet of each finding
+    #[default]
+    Text,
+    /// JSON array of findings
+    Json,
+    /// SARIF 2.1.0 log for code scanning tools
+    Sarif,
+}
+
+#[derive(Args, Debug)]
+struct RedactArgs {
+    /// Files to redact. Standard input is redacted if no files are given
+    files: Vec<String>,
+    /// Character to replace HETU characters with
+    #[arg(short, long, default_value_t = '*')]
+    mask: char,
+    /// Write to file instead of standard output. Required for DOCX, XLSX, ODT, ODS and ZIP files
+    #[arg(short, long, value_name = "FILE")]
+    output: Option<String>,
+}
+
+#[derive(Args, Debug)]
+#[command(long_about = ALLOCATE_HELP)]
+struct AllocateArgs {
+    /// Birth date of the HETUs
+    #[arg(short, long, value_name = "DATE", value_parser = parse_date)]
+    born: Date,
+    /// Gender of the HETUs
+    #[arg(short, long)]
+    gender: Gender,
+    /// Number of HETUs to issue
+    #[arg(short = 'n', long, default_value_t = 1)]
+    count: usize,
+    /// Issue temporary HETUs with identifier in range 900-999
+    #[arg(long)]
+    temporary: bool,
+    /// File of used identifiers, read before and written after issuing. Created if it doesn't exist
+    #[arg(short, long, value_name = "FILE")]
+    state: Option<String>,
+    /// Never issue an identifier or range of identifiers, e.g. 500-599
+    #[arg(long, value_name = "RANGE", value_parser = allocate::parse_range)]
+    reserve: Vec<RangeInclusive<usize>>,
+    /// Separators of a new state, modern by default. Legacy issues no separators introduced in 2023. Must match the
+    /// profile of an existing state
+    #[arg(long, value_enum)]
+    profile: Option<Compatibility>,
+}
+
+#[derive(Args, Debug)]
+#[command(long_about = DUPLICATES_HELP)]
+struct DuplicatesArgs {
+    /// Files of HETUs, valid or not, one per line. Standard input is read if no files are given
+    files: Vec<String>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+enum Gender {
+    #[value(alias = "f")]
+    Female,
+    #[value(alias = "m")]
+    Male,
+}
+
+/// Separators understood by the systems the HETUs are for.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default, ValueEnum)]
+enum Compatibility {
+    /// Every separator, including Y, X, W, V, U, B, C, D, E and F introduced in 2023
+    #[default]
+    Modern,
+    /// Only +, - and A for systems built before 2023
+    Legacy,
+}
+
+impl From<Compatibility> for Profile {
+    fn from(compatibility: Compatibility) -> Profile {
+        match compatibility {
+            Compatibility::Modern => Profile::Modern,
+            Compatibility::Legacy => Profile::Legacy,
+        }
+    }
+}
+
+/// Exit status by class of failure.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Exit {
+    /// Invalid HETU.
+    Invalid = 1,
+    /// Invalid arguments, pattern or query.
+    Usage = 2,
+    /// No HETU matches pattern or query.
+    Generate = 3,
+    /// Reading or writing failed.
+    Io = 4,
+    /// HETUs found in scanned text.
+    Found = 5,
+}
+
+fn parse_language(value: &str) -> Result<Language, String> {
+    Language::try_from(value).map_err(|_| "expected fi, sv or en".to_string())
+}
+
+fn parse_date(value: &str) -> Result<Date, String> {
+    Date::try_from(value).map_err(|err| err.to_string())
+}
+
+pub fn main() {
+    let cli = Cli::parse();
+    diagnostic::set_color(cli.color.enabled());
+    diagnostic::set_language(cli.language());
+    let res = match cli.command {
+        Some(Command::Validate(ref args)) => validate::run(args),
+        Some(Command::Generate(ref args)) => generate::run(args),
+        Some(Command::Info(ref args)) => info::run(args),
+        Some(Command::Csv(ref args)) => enrich::run(args),
+        Some(Command::Scan(ref args)) => scan::scan(args),
+        Some(Command::Redact(ref args)) => scan::redact(args),
+        Some(Command::Diff(ref args)) => diff::run(args),
+        Some(Command::Fill(ref args)) => fill::run(args),
+        Some(Command::People(ref args)) => people::run(args),
+        Some(Command::Serve(ref args)) => serve::run(args),
+        Some(Command::Allocate(ref args)) => allocate::run(args),
+        Some(Command::Duplicates(ref args)) => duplicates::run(args),
+        None => generate::run(&GenerateArgs {
+            count: 1,
+            ..GenerateArgs::default()
+        }),
+    };
+    if let Err(exit) = res {
+        process::exit(exit as i32);
+    }
+}
+
+const PATTERN_HELP: &str =
+    "Generate HETUs by pattern. Patterns use a question mark ('?') for wildcard and
+wildcards can appear at any location in the pattern. Digits can be limited with a class,
+e.g. '[0-2]' or '[^9]', and the last identifier digit with 'f' (female) or 'm' (male). The
+separator can be limited with a class, e.g. '[-A]', or a century, '{18}', '{19}' or '{20}'.";
+
+const WARN_HELP: &str =
+    "Severity of a kind of warning about suspicious but valid HETUs, off, info, warning or error.
+Warnings are checked against the reference date, e.g. when the record was created:
+  future-birth-date  birth date after the reference date (default warning)
+  old-age            age over 120 (default warning)
+  plus-separator     '+' separator of people born in the 1800s (default warning)
+  early-separator    separator introduced in 2023 used before 2023-01-01 (default warning)
+  temporary          temporary identifier 900-999 (default info)
+  legacy-incompatible  separator introduced in 2023, not understood by legacy systems (default off)
+Warnings with severity error make the HETU count as invalid.";
+
+const QUERY_HELP: &str = "Generate HETUs by query of whitespace separated constraints:
+  born:1980-01-01..1989-12-31, born:1980..1989 or born:..1999
+  age:>=18, age:<65 or age:18..30, optionally on a date age:>=18@2026-10-01
+  adult or minor
+  gender:female or gender:male, or just female or male
+  temporary:yes or temporary:no
+  separator:legacy, separator:modern or a pattern separator, e.g. separator:{19}
+  pattern:<PATTERN>";
+
+const FILL_HELP: &str = "Replace placeholders in a template with distinct generated HETUs, e.g. to create test fixtures.
+
+Placeholders are written as {{hetu}}, optionally with a label and options:
+  {{hetu}}                              any HETU
+  {{hetu gender=f born=1990..1999}}     HETU by gender, birth date range or pattern=<PATTERN>
+  {{hetu#alice}}                        same HETU wherever the label #alice appears
+
+Generated HETUs differ from each other and from HETUs already in the template.";
+
+const ALLOCATE_HELP: &str =
+    "Issue the next unused HETUs for a birth date like the population register, e.g. for
+registry-like test systems.
+
+The lowest unused identifier in the parity of the gender is issued, even for female and odd
+for male. Temporary identifiers 900-999 are allocated separately. When every identifier of the
+birth date is used, the next separator of the century is taken into use, e.g. Y after -.
+
+With --state, used identifiers are kept in a file with one line per birth date and separator:
+  profile modern
+  reserved 500-599
+  1969-12-29 - 2-40 276 901
+
+Exits with status 3 when the identifiers of the birth date and gender run out.";
+
+const DUPLICATES_HELP: &str =
+    "Find probable duplicates among HETUs, valid or not, e.g. the same person entered with a
+mistyped HETU. Pairs of HETUs are printed with a reason and its confidence, grouped by the
+HETUs they connect:
+  identical      same HETU ignoring case and whitespace (high)
+  checksum       invalid HETU with its checksum corrected is the other HETU (high)
+  separator      same birth date and identifier with another separator of the century (high)
+  transposition  two adjacent characters swapped (medium)
+  substitution   one character differs (low)
+
+Exits with status 5 when duplicates are found.";
+
+const SERVE_HELP: &str = "Serve validation, generation and redaction as a local HTTP service with JSON endpoints:
+  GET  /health     status and version
+  POST /validate   {\"code\": CODE} or {\"codes\": [CODE, ...]}, optionally with {\"on\": DATE} for ages
+  POST /generate   {\"count\", \"pattern\", \"query\", \"gender\", \"born\", \"seed\"}, all optional
+  POST /redact     {\"text\": TEXT}, optionally with {\"mask\": CHARACTER}
+
+Requests with a body larger than --max-body are rejected with status 413.";
+
+const EXAMPLES: &str = "Examples:
+    * Validate HETU:
+
+        $ hetu validate 291269-2763
+
+    * Generate HETU by pattern:
+
+        $ hetu generate -p '291269-????'
+        291269-7767
+
+    * Generate three HETUs for women born in the 1990s:
+
+        $ hetu generate -n 3 --gender female --born 1990..1999
+        070994Y766R
+        301192W574F
+        250196W0145
+
+    * Generate HETU for an adult man born in the 1980s:
+
+        $ hetu generate -q 'born:1980..1989 male adult'
+        030584-591P
+
+    * Redact HETUs in a file:
+
+        $ hetu redact export.txt > redacted.txt
+
+    * Redact HETUs in a Word document:
+
+        $ hetu redact report.docx -o redacted.docx";
@@ -0,0 +1,186 @@
+use csv;
+use hetu::{Date, ErrorIndexRange, GenderRule, ParseError, Severity, Ssn, Warning};
+use serde_json::Value;
+use std::io::{self, Stdout};
+
+use {Exit, Format};
+
+/// Names of the columns in CSV output.
+const COLUMNS: [&str; 16] = [
+    "code",
+    "location",
+    "valid",
+    "birth_date",
+    "century",
+    "gender",
+    "identifier",
+    "separator",
+    "temporary",
+    "age",
+    "error_kind",
+    "error_message",
+    "expected_checksum",
+    "error_start",
+    "error_end",
+    "warnings",
+];
+
+/// Writer of decoded fields and errors of codes in a machine-readable format.
+pub struct Output {
+    format: Format,
+    /// Names of the columns in CSV output.
+    columns: &'static [&'static str],
+    /// Reference date for ages.
+    on: Date,
+    /// Interpretation of gender.
+    rule: GenderRule,
+    /// Records of JSON output, written as an array when finished.
+    records: Vec<Value>,
+    csv: Option<csv::Writer<Stdout>>,
+}
+
+impl Output {
+    /// Create writer for a machine-readable format, `None` for text output.
+    pub fn new(format: Format, on: Date, rule: GenderRule) -> Option<Output> {
+        Output::with_columns(format, on, &COLUMNS).map(|output| Output { rule, ..output })
+    }
+
+    /// Create writer of records with the given CSV columns for a machine-readable format, `None` for text output.
+    pub fn with_columns(
+        format: Format,
+        on: Date,
+        columns: &'static [&'static str],
+    ) -> Option<Output> {
+        if format == Format::Text {
+            return None;
+        }
+        Some(Output {
+            format,
+            columns,
+            on,
+            rule: GenderRule::default(),
+            records: Vec::new(),
+            csv: None,
+        })
+    }
+
+    /// Write decoded fields and warnings of a valid code or the error of an invalid code.
+    pub fn write(
+        &mut self,
+        code: &str,
+        location: Option<&str>,
+        result: &Result<Ssn, ParseError>,
+        warnings: &[Warning],
+    ) -> Result<(), Exit> {
+        let record = record(code, location, result, warnings, &self.on, self.rule);
+        self.write_record(record)
+    }
+
+    /// Write flat record with fields named by the columns.
+    pub fn write_record(&mut self, record: Value) -> Result<(), Exit> {
+        match self.format {
+            Format::Text => Ok(()),
+            Format::Json => {
+                self.records.push(record);
+                Ok(())
+            }
+            Format::Jsonl => {
+                println!("{}", record);
+                Ok(())
+            }
+            Format::Csv => {
+                if self.csv.is_none() {
+                    let mut writer = csv::Writer::from_writer(io::stdout());
+                    writer
+                        .write_record(self.columns.iter())
+                        .map_err(csv_error)?;
+                    self.csv = Some(writer);
+                }
+                let row = self.columns.iter().map(|column| match record.get(*column) {
+                    Some(Value::String(s)) => s.clone(),
+                    Some(Value::Null) | None => String::new(),
+                    // Warnings are listed by kind, like mismatched fields of enriched CSV
+                    Some(Value::Array(items)) => items
+                        .iter()
+                        .filter_map(|item| item["kind"].as_str())
+                        .collect::<Vec<_>>()
+                        .join(";"),
+                    Some(value) => value.to_string(),
+                });
+                let writer = self.csv.as_mut().ok_or(Exit::Io)?;
+                writer.write_record(row).map_err(csv_error)
+            }
+        }
+    }
+
+    /// Write buffered output.
+    pub fn finish(self) -> Result<(), Exit> {
+        match self.format {
+            Format::Json => {
+                println!("{}", Value::Array(self.records));
+                Ok(())
+            }
+            Format::Csv => match self.csv {
+                Some(mut writer) => writer.flush().map_err(|err| {
+                    eprintln!("Error: {}", err);
+                    Exit::Io
+                }),
+                None => Ok(()),
+            },
+            _ => Ok(()),
+        }
+    }
+}
+
+fn csv_error(err: csv::Error) -> Exit {
+    eprintln!("Error: {}", err);
+    Exit::Io
+}
+
+/// Flat record of decoded fields and warnings, or error.
+///
+/// A valid code with a warning of severity error is not valid.
+pub fn record(
+    code: &str,
+    location: Option<&str>,
+    result: &Result<Ssn, ParseError>,
+    warnings: &[Warning],
+    on: &Date,
+    rule: GenderRule,
+) -> Value {
+    match *result {
+        Ok(ref ssn) => json!({
+            "code": code,
+            "location": location,
+            "valid": warnings.iter().all(|warning| warning.severity != Severity::Error),
+            "birth_date": ssn.birth_date().to_string(),
+            "century": ssn.year / 100 * 100,
+            "gender": ssn.gender_by(rule).map(|gender| gender.name()),
+            "identifier": ssn.identifier(),
+            "separator": ssn.separator().to_string(),
+            "temporary": ssn.is_temporary(),
+            "age": ssn.age_on(on),
+            "warnings": warnings
+                .iter()
+                .map(|warning| json!({
+                    "kind": warning.kind.name(),
+                    "severity": warning.severity.name(),
+                    "message": warning.to_string(),
+                }))
+                .collect::<Vec<_>>(),
+        }),
+        Err(ref err) => json!({
+            "code": code,
+            "location": location,
+            "valid": false,
+            "error_kind": err.kind(),
+            "error_message": err.message(),
+            "expected_checksum": match *err {
+                ParseError::Checksum(_, _, _, checksum) => Some(checksum.to_string()),
+                _ => None,
+            },
+            "error_start": err.start(),
+            "error_end": err.end(),
+        }),
+    }
+}
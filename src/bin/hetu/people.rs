@@ -0,0 +1,76 @@
+use hetu::{AgeDistribution, Date, GenderRule, People, Person};
+use rand::{SeedableRng, StdRng};
+use serde_json::Value;
+
+use output::Output;
+use {Ages, Exit, PeopleArgs};
+
+/// Names of the columns in CSV output.
+const COLUMNS: [&str; 6] = [
+    "code",
+    "first_name",
+    "last_name",
+    "birth_date",
+    "gender",
+    "age",
+];
+
+/// Print synthetic people with distinct codes.
+pub fn run(args: &PeopleArgs) -> Result<(), Exit> {
+    let distribution = match args.ages {
+        Ages::Uniform => AgeDistribution::Uniform,
+        Ages::Population => AgeDistribution::Population,
+    };
+    let on = args.output.on();
+    let rule = args.output.gender_rule();
+    let out_of_range = |_| {
+        eprintln!(
+            "Error: People of every age on {} can't be born in years 1800-2099",
+            on
+        );
+        Exit::Usage
+    };
+    let people: Vec<Person> = match args.seed {
+        Some(seed) => People::with_rng(distribution, on, StdRng::from_seed(&[seed as usize][..]))
+            .map_err(out_of_range)?
+            .take(args.count)
+            .collect(),
+        None => People::new(distribution, on)
+            .map_err(out_of_range)?
+            .take(args.count)
+            .collect(),
+    };
+    if people.len() < args.count {
+        eprintln!(
+            "Error: Only {} people with distinct HETUs were generated",
+            people.len()
+        );
+        return Err(Exit::Generate);
+    }
+    match Output::with_columns(args.output.format, on, &COLUMNS) {
+        Some(mut output) => {
+            for person in &people {
+                output.write_record(record(person, &on, rule))?;
+            }
+            output.finish()
+        }
+        None => {
+            for person in &people {
+                println!("{} {} {}", person.ssn, person.first_name, person.last_name);
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Flat record of a person, with a null gender if the rule doesn't encode gender in the code.
+fn record(person: &Person, on: &Date, rule: GenderRule) -> Value {
+    json!({
+        "code": person.ssn.to_string(),
+        "first_name": person.first_name,
+        "last_name": person.last_name,
+        "birth_date": person.ssn.birth_date().to_string(),
+        "gender": person.ssn.gender_by(rule).map(|gender| gender.name()),
+        "age": person.ssn.age_on(on),
+    })
+}
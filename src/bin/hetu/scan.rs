@@ -0,0 +1,428 @@
+use hetu::scan;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::WalkBuilder;
+use serde_json::Value;
+use std::fs::File;
+use std::io::{self, Read, Write};
+
+use allowlist::Allowlist;
+use document::{self, Segment};
+use {Exit, RedactArgs, ScanArgs, ScanFormat};
+
+/// Maximum number of characters in a snippet.
+const SNIPPET_LEN: usize = 120;
+
+/// Identifier of the SARIF rule for found personal identity codes.
+const RULE_ID: &str = "hetu/personal-identity-code";
+
+/// Personal identity code found in a file.
+#[derive(Debug)]
+struct Finding {
+    path: String,
+    location: Location,
+    /// Line with every personal identity code masked.
+    snippet: String,
+}
+
+/// Location of a finding in a file.
+#[derive(Debug)]
+enum Location {
+    /// 1-based line number and 1-based column of the first character, in characters.
+    Line(usize, usize),
+    /// Part of a document and location in the part, e.g. `word/document.xml` and `paragraph 3`.
+    Part(String, String),
+}
+
+/// Content of a scanned file.
+enum Content {
+    Text(String),
+    Document(Vec<Segment>),
+}
+
+/// Report location and masked snippet of every valid code in files, directories or standard input.
+///
+/// Files are scanned one at a time and only their findings are kept. Files that can't be read are reported and
+/// skipped, and fail the scan with `Exit::Io` unless codes are found.
+pub fn scan(args: &ScanArgs) -> Result<(), Exit> {
+    let allowlist = Allowlist::load(args.allowlist.as_deref(), args.allow_temporary)?;
+    // Text findings are printed as soon as a file is scanned, and reports are written after every file
+    let mut found = 0;
+    let mut findings = Vec::new();
+    let complete = files(args, |path, content| {
+        let in_file = match content {
+            Content::Text(text) => find(&path, &text, &allowlist),
+            Content::Document(segments) => find_in_document(&path, &segments, &allowlist),
+        };
+        found += in_file.len();
+        match args.format {
+            ScanFormat::Text => {
+                for finding in &in_file {
+                    match finding.location {
+                        Location::Line(line, column) => {
+                            println!("{}:{}:{}: {}", finding.path, line, column, finding.snippet)
+                        }
+                        Location::Part(ref part, ref location) => println!(
+                            "{}:{}:{}: {}",
+                            finding.path, part, location, finding.snippet
+                        ),
+                    }
+                }
+            }
+            ScanFormat::Json | ScanFormat::Sarif => findings.extend(in_file),
+        }
+    })?;
+    match args.format {
+        ScanFormat::Text => {}
+        ScanFormat::Json => println!("{}", json(&findings)),
+        ScanFormat::Sarif => println!("{}", sarif(&findings)),
+    }
+    if found > 0 {
+        Err(Exit::Found)
+    } else if !complete {
+        Err(Exit::Io)
+    } else {
+        Ok(())
+    }
+}
+
+/// Find codes that are not allowed in text.
+fn find(path: &str, text: &str, allowlist: &Allowlist) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        let mut snippet = None;
+        for m in scan::find(line).filter(|m| !allowlist.allows(&m.ssn)) {
+            let snippet = snippet.get_or_insert_with(|| self::snippet(line)).clone();
+            findings.push(Finding {
+                path: path.to_string(),
+                location: Location::Line(number + 1, line[..m.start].chars().count() + 1),
+                snippet,
+            });
+        }
+    }
+    findings
+}
+
+/// Find codes that are not allowed in text segments of a document.
+fn find_in_document(path: &str, segments: &[Segment], allowlist: &Allowlist) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for segment in segments {
+        let text = segment.text.replace('\n', " ");
+        for _ in scan::find(&text).filter(|m| !allowlist.allows(&m.ssn)) {
+            findings.push(Finding {
+                path: path.to_string(),
+                location: Location::Part(segment.part.clone(), segment.location.clone()),
+                snippet: snippet(&text),
+            });
+        }
+    }
+    findings
+}
+
+/// Line with every code masked, truncated to a maximum length.
+pub fn snippet(line: &str) -> String {
+    let masked = scan::redact(line.trim(), '*');
+    if masked.chars().count() <= SNIPPET_LEN {
+        masked
+    } else {
+        masked
+            .chars()
+            .take(SNIPPET_LEN - 1)
+            .chain(Some('…'))
+            .collect()
+    }
+}
+
+fn json(findings: &[Finding]) -> Value {
+    Value::Array(
+        findings
+            .iter()
+            .map(|finding| match finding.location {
+                Location::Line(line, column) => json!({
+                    "path": finding.path,
+                    "line": line,
+                    "column": column,
+                    "snippet": finding.snippet,
+                }),
+                Location::Part(ref part, ref location) => json!({
+                    "path": finding.path,
+                    "part": part,
+                    "location": location,
+                    "snippet": finding.snippet,
+                }),
+            })
+            .collect(),
+    )
+}
+
+/// SARIF 2.1.0 log of findings.
+fn sarif(findings: &[Finding]) -> Value {
+    let results: Vec<Value> = findings
+        .iter()
+        .map(|finding| {
+            let uri = finding.path.trim_start_matches("./");
+            let location = match finding.location {
+                Location::Line(line, column) => json!({
+                    "physicalLocation": {
+                        "artifactLocation": {
+                            "uri": uri,
+                        },
+                        "region": {
+                            "startLine": line,
+                            "startColumn": column,
+                            "endColumn": column + 11,
+                            "snippet": {
+                                "text": finding.snippet,
+                            },
+                        },
+                    },
+                }),
+                // Regions address text files, so locations in documents are logical
+                Location::Part(ref part, ref location) => json!({
+                    "physicalLocation": {
+                        "artifactLocation": {
+                            "uri": uri,
+                        },
+                    },
+                    "logicalLocations": [{
+                        "name": location,
+                        "fullyQualifiedName": format!("{}/{}", part, location),
+                        "kind": "element",
+                    }],
+                }),
+            };
+            json!({
+                "ruleId": RULE_ID,
+                "level": "error",
+                "message": {
+                    "text": "Finnish personal identity code (HETU) found",
+                },
+                "locations": [location],
+            })
+        })
+        .collect();
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "hetu",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": "https://github.com/jelovirt/hetu",
+                    "rules": [{
+                        "id": RULE_ID,
+                        "name": "PersonalIdentityCode",
+                        "shortDescription": {
+                            "text": "Finnish personal identity code",
+                        },
+                        "fullDescription": {
+                            "text": "Text contains a valid Finnish personal identity code (henkilötunnus, HETU), which is personal data.",
+                        },
+                        "defaultConfiguration": {
+                            "level": "error",
+                        },
+                    }],
+                },
+            },
+            "columnKind": "unicodeCodePoints",
+            "results": results,
+        }],
+    })
+}
+
+/// Visit text files and documents in paths one at a time, walking directories recursively and honouring ignore files
+/// and excludes.
+///
+/// Entries that can't be walked or read are reported and skipped. Returns `false` if any were skipped.
+fn files<F: FnMut(String, Content)>(args: &ScanArgs, mut visit: F) -> Result<bool, Exit> {
+    if args.paths.is_empty() || args.paths == ["-"] {
+        for (path, text) in inputs(&[])? {
+            visit(path, Content::Text(text));
+        }
+        return Ok(true);
+    }
+    let excludes = excludes(&args.exclude)?;
+    let mut walk = WalkBuilder::new(&args.paths[0]);
+    for path in &args.paths[1..] {
+        walk.add(path);
+    }
+    walk.hidden(!args.hidden)
+        .require_git(false)
+        .sort_by_file_name(|a, b| a.cmp(b))
+        .add_custom_ignore_filename(".hetuignore")
+        .filter_entry(move |entry| {
+            let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+            // Entries are filtered top-down, so excluded directories are never entered
+            !excludes.matched(entry.path(), is_dir).is_ignore()
+        });
+    let mut complete = true;
+    for entry in walk.build() {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(err) => {
+                eprintln!("Error: {}", err);
+                complete = false;
+                continue;
+            }
+        };
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            continue;
+        }
+        let path = entry.path().to_string_lossy().into_owned();
+        let bytes = match read(&path) {
+            Ok(bytes) => bytes,
+            Err(_) => {
+                complete = false;
+                continue;
+            }
+        };
+        if document::is_container(&bytes) {
+            // A corrupt archive is reported without stopping the scan of other files
+            match document::segments(&bytes) {
+                Ok(segments) => visit(path, Content::Document(segments)),
+                Err(err) => {
+                    eprintln!("Error: {}: {}", path, err);
+                    complete = false;
+                }
+            }
+            continue;
+        }
+        // Skip binary files
+        if bytes.iter().take(8192).any(|b| *b == 0) {
+            continue;
+        }
+        let text = String::from_utf8_lossy(&bytes).into_owned();
+        visit(path, Content::Text(text));
+    }
+    Ok(complete)
+}
+
+/// Read file, or standard input if path is a dash.
+fn read(path: &str) -> Result<Vec<u8>, Exit> {
+    let mut bytes = Vec::new();
+    let res = if path == "-" {
+        io::stdin().read_to_end(&mut bytes)
+    } else {
+        File::open(path).and_then(|mut file| file.read_to_end(&mut bytes))
+    };
+    res.map(|_| bytes).map_err(|err| {
+        eprintln!("Error: {}: {}", path, err);
+        Exit::Io
+    })
+}
+
+/// Matcher of `.gitignore` style exclude globs.
+fn excludes(globs: &[String]) -> Result<Gitignore, Exit> {
+    let mut builder = GitignoreBuilder::new(".");
+    for glob in globs {
+        builder.add_line(None, glob).map_err(|err| {
+            eprintln!("Error: {}", err);
+            Exit::Usage
+        })?;
+    }
+    builder.build().map_err(|err| {
+        eprintln!("Error: {}", err);
+        Exit::Usage
+    })
+}
+
+/// Print files or standard input with every valid code masked, or write a redacted copy of a document.
+pub fn redact(args: &RedactArgs) -> Result<(), Exit> {
+    let paths = if args.files.is_empty() {
+        vec!["-".to_string()]
+    } else {
+        args.files.clone()
+    };
+    if args.output.is_some() && paths.len() > 1 {
+        eprintln!("Error: Only one file can be redacted with --output");
+        return Err(Exit::Usage);
+    }
+    let output = args.output.as_deref().unwrap_or("-");
+    // Output is created after the first input is redacted, so that failed redaction leaves no file behind
+    let mut out: Option<Box<dyn Write>> = None;
+    for path in &paths {
+        let bytes = read(path)?;
+        let redacted = if document::is_container(&bytes) {
+            if args.output.is_none() {
+                eprintln!("Error: {}: Redacting a document requires --output", path);
+                return Err(Exit::Usage);
+            }
+            redact_document(path, &bytes, args.mask)?
+        } else {
+            let text = String::from_utf8(bytes).map_err(|err| {
+                eprintln!("Error: {}: {}", path, err);
+                Exit::Io
+            })?;
+            scan::redact(&text, args.mask).into_bytes()
+        };
+        if out.is_none() {
+            out = Some(if output == "-" {
+                Box::new(io::stdout())
+            } else {
+                Box::new(File::create(output).map_err(|err| {
+                    eprintln!("Error: {}: {}", output, err);
+                    Exit::Io
+                })?)
+            });
+        }
+        let out = out.as_mut().expect("output is created");
+        out.write_all(&redacted).map_err(|err| {
+            eprintln!("Error: {}: {}", output, err);
+            Exit::Io
+        })?;
+    }
+    Ok(())
+}
+
+/// Redact document and check that no codes remain, e.g. split across differently formatted text.
+fn redact_document(path: &str, bytes: &[u8], mask: char) -> Result<Vec<u8>, Exit> {
+    if !document::is_xml_safe(mask) {
+        eprintln!(
+            "Error: {}: Mask character {:?} can't be used in documents",
+            path, mask
+        );
+        return Err(Exit::Usage);
+    }
+    let error = |err| {
+        eprintln!("Error: {}: {}", path, err);
+        Exit::Io
+    };
+    let redacted = document::redact(bytes, mask).map_err(error)?;
+    let remaining = find_in_document(
+        path,
+        &document::segments(&redacted).map_err(error)?,
+        &Allowlist::default(),
+    );
+    if remaining.is_empty() {
+        return Ok(redacted);
+    }
+    for finding in &remaining {
+        if let Location::Part(ref part, ref location) = finding.location {
+            eprintln!("{}:{}:{}: {}", path, part, location, finding.snippet);
+        }
+    }
+    eprintln!(
+        "Error: {} HETUs could not be redacted, because they are split by formatting",
+        remaining.len()
+    );
+    Err(Exit::Found)
+}
+
+/// Read files, or standard input if there are no files, into strings.
+fn inputs(files: &[String]) -> Result<Vec<(String, String)>, Exit> {
+    let paths = if files.is_empty() {
+        vec!["-".to_string()]
+    } else {
+        files.to_vec()
+    };
+    paths
+        .into_iter()
+        .map(|path| {
+            let text = String::from_utf8(read(&path)?).map_err(|err| {
+                eprintln!("Error: {}: {}", path, err);
+                Exit::Io
+            })?;
+            Ok((path, text))
+        })
+        .collect()
+}
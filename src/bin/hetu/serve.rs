@@ -0,0 +1,403 @@
+use hetu::{scan, Constraints, Date, GenderReform, GenderRule, Ssn};
+use rand::{SeedableRng, StdRng};
+use serde_json::Value;
+use std::convert::TryFrom;
+use std::io::Read;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, Sender};
+use std::sync::Arc;
+use std::thread;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use generate::try_constraints;
+use output::record;
+use {Exit, Gender, GenerateArgs, ServeArgs};
+
+/// Number of threads that handle requests.
+const WORKERS: usize = 4;
+
+/// Maximum number of codes generated by one request.
+const MAX_COUNT: usize = 10_000;
+
+/// Response status and JSON body.
+type Reply = (u16, Value);
+
+/// Notifies the server when a worker thread ends, also by panicking.
+struct Stopped(Sender<()>);
+
+impl Drop for Stopped {
+    fn drop(&mut self) {
+        let _ = self.0.send(());
+    }
+}
+
+/// Serve validation, generation and redaction as JSON over HTTP until the process is stopped.
+///
+/// Returns an error if a worker thread stops, because requests would be left unanswered.
+pub fn run(args: &ServeArgs) -> Result<(), Exit> {
+    let server = Server::http(&args.listen).map_err(|err| {
+        eprintln!("Error: {}: {}", args.listen, err);
+        Exit::Io
+    })?;
+    eprintln!("Listening on http://{}", server.server_addr());
+    let server = Arc::new(server);
+    let (stopped, workers_stopped) = mpsc::channel();
+    for _ in 0..WORKERS {
+        let server = Arc::clone(&server);
+        let max_body = args.max_body;
+        let stopped = Stopped(stopped.clone());
+        thread::spawn(move || {
+            let _stopped = stopped;
+            for request in server.incoming_requests() {
+                handle(request, max_body);
+            }
+        });
+    }
+    drop(stopped);
+    let _ = workers_stopped.recv();
+    eprintln!("Error: Request handler stopped");
+    Err(Exit::Io)
+}
+
+fn handle(mut request: Request, max_body: usize) {
+    let (status, body) = guarded(|| match read_body(&mut request, max_body) {
+        Ok(body) => route(request.method(), request.url(), &body),
+        Err(reply) => reply,
+    });
+    eprintln!("{} {} {}", request.method(), request.url(), status);
+    let mut response = Response::from_string(body.to_string())
+        .with_status_code(status)
+        .with_header(header("Content-Type", "application/json"));
+    if status == 405 {
+        response.add_header(header("Allow", allowed(request.url())));
+    }
+    if let Err(err) = request.respond(response) {
+        eprintln!("Error: {}", err);
+    }
+}
+
+/// Reply of a request handler, or internal server error if the handler panics.
+fn guarded<F: FnOnce() -> Reply>(handler: F) -> Reply {
+    panic::catch_unwind(AssertUnwindSafe(handler))
+        .unwrap_or_else(|_| error(500, "Internal server error".to_string()))
+}
+
+fn header(name: &str, value: &str) -> Header {
+    // Names and values are known to be valid
+    Header::from_bytes(name, value).expect("valid header")
+}
+
+/// Read request body, up to the maximum size.
+fn read_body(request: &mut Request, max_body: usize) -> Result<Vec<u8>, Reply> {
+    let too_large = || error(413, format!("Request body exceeds {} bytes", max_body));
+    if request.body_length().is_some_and(|len| len > max_body) {
+        return Err(too_large());
+    }
+    let mut body = Vec::new();
+    request
+        .as_reader()
+        .take(max_body as u64 + 1)
+        .read_to_end(&mut body)
+        .map_err(|err| error(400, err.to_string()))?;
+    if body.len() > max_body {
+        return Err(too_large());
+    }
+    Ok(body)
+}
+
+fn route(method: &Method, url: &str, body: &[u8]) -> Reply {
+    let path = url.split('?').next().unwrap_or_default();
+    match (method, path) {
+        (Method::Get, "/health") => Ok((
+            200,
+            json!({"status": "ok", "version": env!("CARGO_PKG_VERSION")}),
+        )),
+        (Method::Post, "/validate") => parse(body).and_then(|req| validate(&req)),
+        (Method::Post, "/generate") => parse(body).and_then(|req| generate(&req)),
+        (Method::Post, "/redact") => parse(body).and_then(|req| redact(&req)),
+        (_, "/health") | (_, "/validate") | (_, "/generate") | (_, "/redact") => {
+            Err(error(405, format!("Method {} not allowed", method)))
+        }
+        _ => Err(error(404, format!("No endpoint {}", path))),
+    }
+    .unwrap_or_else(|reply| reply)
+}
+
+/// Allowed method of an endpoint.
+fn allowed(url: &str) -> &'static str {
+    if url.starts_with("/health") {
+        "GET"
+    } else {
+        "POST"
+    }
+}
+
+fn error(status: u16, message: String) -> Reply {
+    (status, json!({ "error": { "message": message } }))
+}
+
+/// Parse JSON object from request body.
+fn parse(body: &[u8]) -> Result<Value, Reply> {
+    match serde_json::from_slice(body) {
+        Ok(value @ Value::Object(_)) => Ok(value),
+        Ok(_) => Err(error(400, "Request body must be a JSON object".to_string())),
+        Err(err) => Err(error(400, format!("Invalid JSON: {}", err))),
+    }
+}
+
+/// Optional string field of a request.
+fn string<'a>(req: &'a Value, field: &str) -> Result<Option<&'a str>, Reply> {
+    match req.get(field) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(s)) => Ok(Some(s)),
+        Some(_) => Err(error(400, format!("Field {} must be a string", field))),
+    }
+}
+
+/// Optional non-negative integer field of a request.
+fn number(req: &Value, field: &str) -> Result<Option<u64>, Reply> {
+    match req.get(field) {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => value.as_u64().map(Some).ok_or_else(|| {
+            error(
+                400,
+                format!("Field {} must be a non-negative integer", field),
+            )
+        }),
+    }
+}
+
+/// Validate `code`, or an array of `codes`, with ages on `on`, today by default.
+///
+/// Genders are null if `on` is on or after the date in `gender_neutral_from`.
+fn validate(req: &Value) -> Result<Reply, Reply> {
+    let on = match string(req, "on")? {
+        Some(on) => Date::try_from(on).map_err(|err| error(400, format!("Field on: {}", err)))?,
+        None => Date::today(),
+    };
+    let rule = match string(req, "gender_neutral_from")? {
+        Some(date) => Date::try_from(date)
+            .map(|date| GenderReform::new(date).rule_on(&on))
+            .map_err(|err| error(400, format!("Field gender_neutral_from: {}", err)))?,
+        None => GenderRule::default(),
+    };
+    let result = |code: &str| {
+        let mut record = record(code, None, &Ssn::try_from(code), &[], &on, rule);
+        if let Value::Object(ref mut fields) = record {
+            fields.remove("location");
+        }
+        record
+    };
+    if let Some(code) = string(req, "code")? {
+        return Ok((200, result(code)));
+    }
+    let codes = match req.get("codes") {
+        Some(Value::Array(codes)) => codes,
+        _ => {
+            return Err(error(
+                400,
+                "Field code or an array of codes is required".to_string(),
+            ))
+        }
+    };
+    let mut results = Vec::with_capacity(codes.len());
+    for code in codes {
+        match code.as_str() {
+            Some(code) => results.push(result(code)),
+            None => return Err(error(400, "Codes must be strings".to_string())),
+        }
+    }
+    let valid = results.iter().filter(|r| r["valid"] == true).count();
+    Ok((
+        200,
+        json!({
+            "results": results,
+            "valid": valid,
+            "invalid": results.len() - valid,
+        }),
+    ))
+}
+
+/// Generate `count` distinct codes by `pattern`, `query`, `gender` and `born`, optionally with a `seed`.
+fn generate(req: &Value) -> Result<Reply, Reply> {
+    let count = number(req, "count")?.unwrap_or(1) as usize;
+    if count > MAX_COUNT {
+        return Err(error(
+            400,
+            format!("Field count exceeds {} codes", MAX_COUNT),
+        ));
+    }
+    let gender = match string(req, "gender")? {
+        Some("female") | Some("f") => Some(Gender::Female),
+        Some("male") | Some("m") => Some(Gender::Male),
+        Some(_) => {
+            return Err(error(
+                400,
+                "Field gender must be female or male".to_string(),
+            ))
+        }
+        None => None,
+    };
+    let args = GenerateArgs {
+        count,
+        pattern: string(req, "pattern")?.map(String::from),
+        query: string(req, "query")?.map(String::from),
+        gender,
+        born: string(req, "born")?.map(String::from),
+        seed: number(req, "seed")?,
+        ..GenerateArgs::default()
+    };
+    let constraints = try_constraints(&args).map_err(|err| {
+        (
+            400,
+            json!({
+                "error": {
+                    "message": err.message,
+                    "input": err.input,
+                    "start": err.start,
+                    "end": err.end,
+                }
+            }),
+        )
+    })?;
+    let codes = codes(&constraints, &args)?;
+    Ok((200, json!({ "codes": codes })))
+}
+
+fn codes(constraints: &Constraints, args: &GenerateArgs) -> Result<Vec<String>, Reply> {
+    let available = Ssn::count_by_constraints(constraints).unwrap_or(0);
+    if args.count > available {
+        return Err(error(
+            422,
+            format!("Only {} distinct HETUs match the constraints", available),
+        ));
+    }
+    match args.seed {
+        Some(seed) => {
+            let mut rng = StdRng::from_seed(&[seed as usize][..]);
+            Ssn::iter_by_constraints_with_rng(constraints, &mut rng)
+                .map(|iter| iter.take(args.count).collect())
+        }
+        None => Ssn::iter_by_constraints(constraints).map(|iter| iter.take(args.count).collect()),
+    }
+    .map_err(|err| error(422, err.to_string()))
+}
+
+/// Mask every valid code in `text` with `mask`, `*` by default.
+fn redact(req: &Value) -> Result<Reply, Reply> {
+    let text =
+        string(req, "text")?.ok_or_else(|| error(400, "Field text is required".to_string()))?;
+    let mask = match string(req, "mask")? {
+        Some(mask) => {
+            let mut chars = mask.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => c,
+                _ => {
+                    return Err(error(
+                        400,
+                        "Field mask must be a single character".to_string(),
+                    ))
+                }
+            }
+        }
+        None => '*',
+    };
+    Ok((
+        200,
+        json!({
+            "text": scan::redact(text, mask),
+            "count": scan::find(text).count(),
+        }),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn post(path: &str, body: &str) -> Reply {
+        route(&Method::Post, path, body.as_bytes())
+    }
+
+    #[test]
+    fn test_validate() {
+        let (status, body) = post(
+            "/validate",
+            r#"{"code": "291269-2763", "on": "2026-01-01"}"#,
+        );
+        assert_eq!(status, 200);
+        assert_eq!(body["valid"], true);
+        assert_eq!(body["age"], 56);
+        assert!(body.get("location").is_none());
+
+        let (status, body) = post("/validate", r#"{"codes": ["291269-2763", "291269-2764"]}"#);
+        assert_eq!(status, 200);
+        assert_eq!(body["invalid"], 1);
+        assert_eq!(body["results"][1]["error_kind"], "checksum");
+        assert_eq!(body["results"][1]["error_start"], 10);
+
+        assert_eq!(post("/validate", r#"{"codes": [1]}"#).0, 400);
+        assert_eq!(post("/validate", "[]").0, 400);
+        assert_eq!(post("/validate", "{").0, 400);
+    }
+
+    #[test]
+    fn test_validate_gender_neutral() {
+        let validate = |from: &str| {
+            let body = format!(
+                r#"{{"code": "291269-2763", "on": "2027-06-01", "gender_neutral_from": "{}"}}"#,
+                from
+            );
+            post("/validate", &body)
+        };
+        assert_eq!(validate("2027-01-01").1["gender"], Value::Null);
+        assert_eq!(validate("2028-01-01").1["gender"], "female");
+        assert_eq!(validate("2027").0, 400);
+    }
+
+    #[test]
+    fn test_generate() {
+        let (status, body) = post(
+            "/generate",
+            r#"{"count": 3, "pattern": "291269-????", "gender": "f", "seed": 1}"#,
+        );
+        assert_eq!(status, 200);
+        assert_eq!(body["codes"].as_array().unwrap().len(), 3);
+        assert_eq!(
+            post(
+                "/generate",
+                r#"{"count": 3, "pattern": "291269-????", "gender": "f", "seed": 1}"#
+            )
+            .1,
+            body
+        );
+
+        let (status, body) = post("/generate", r#"{"pattern": "29126"}"#);
+        assert_eq!(status, 400);
+        assert_eq!(body["error"]["input"], "29126");
+        assert_eq!(
+            post("/generate", r#"{"count": 20, "pattern": "010101-12??"}"#).0,
+            422
+        );
+        assert_eq!(post("/generate", r#"{"count": 10001}"#).0, 400);
+        assert_eq!(post("/generate", r#"{"count": -1}"#).0, 400);
+    }
+
+    #[test]
+    fn test_routes() {
+        assert_eq!(route(&Method::Get, "/health", b"").0, 200);
+        assert_eq!(route(&Method::Get, "/validate", b"").0, 405);
+        assert_eq!(route(&Method::Get, "/nothing", b"").0, 404);
+        let (status, body) = post("/redact", r#"{"text": "id 291269-2763", "mask": "X"}"#);
+        assert_eq!(status, 200);
+        assert_eq!(body, json!({"text": "id XXXXXXXXXXX", "count": 1}));
+    }
+
+    #[test]
+    fn test_guarded() {
+        assert_eq!(guarded(|| post("/redact", r#"{"text": ""}"#)).0, 200);
+        let (status, body) = guarded(|| panic!("handler failed"));
+        assert_eq!(status, 500);
+        assert_eq!(body["error"]["message"], "Internal server error");
+    }
+}
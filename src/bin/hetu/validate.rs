@@ -0,0 +1,242 @@
+use hetu::{Plausibility, Severity, Ssn, Warning, WarningKind};
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+
+use diagnostic::{language, print_error, print_error_at};
+use output::Output;
+use {Exit, ValidateArgs};
+
+/// Number or percentage of invalid codes allowed before validation fails.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Threshold {
+    Count(usize),
+    Percent(f64),
+}
+
+/// Parse threshold from a count, e.g. `10`, or a percentage, e.g. `0.5%`.
+pub fn parse_threshold(value: &str) -> Result<Threshold, String> {
+    match value.strip_suffix('%') {
+        Some(percent) => match percent.parse::<f64>() {
+            Ok(percent) if (0.0..=100.0).contains(&percent) => Ok(Threshold::Percent(percent)),
+            _ => Err("percentage must be between 0% and 100%".to_string()),
+        },
+        None => value
+            .parse::<usize>()
+            .map(Threshold::Count)
+            .map_err(|_| "expected a count or a percentage".to_string()),
+    }
+}
+
+/// Parse severity of a kind of warning, e.g. `temporary=off`.
+pub fn parse_warn(value: &str) -> Result<(WarningKind, Severity), String> {
+    let (kind, severity) = value
+        .split_once('=')
+        .ok_or_else(|| "expected KIND=SEVERITY".to_string())?;
+    let kind = WarningKind::try_from(kind).map_err(|_| {
+        let kinds: Vec<&str> = WarningKind::all().iter().map(|kind| kind.name()).collect();
+        format!(
+            "unknown kind {}, expected one of {}",
+            kind,
+            kinds.join(", ")
+        )
+    })?;
+    let severity = Severity::try_from(severity)
+        .map_err(|_| "severity must be off, info, warning or error".to_string())?;
+    Ok((kind, severity))
+}
+
+/// Validation results of a batch.
+#[derive(Debug, Default)]
+struct Summary {
+    valid: usize,
+    invalid: usize,
+    /// Number of invalid codes by error or warning kind and message.
+    errors: BTreeMap<(&'static str, String), usize>,
+}
+
+impl Summary {
+    fn total(&self) -> usize {
+        self.valid + self.invalid
+    }
+
+    fn exceeds(&self, threshold: Threshold) -> bool {
+        match threshold {
+            Threshold::Count(count) => self.invalid > count,
+            Threshold::Percent(percent) => {
+                self.invalid as f64 > self.total() as f64 * percent / 100.0
+            }
+        }
+    }
+
+    fn print(&self) {
+        eprintln!(
+            "Validated {} HETUs: {} valid, {} invalid",
+            self.total(),
+            self.valid,
+            self.invalid
+        );
+        for (&(kind, ref message), count) in &self.errors {
+            eprintln!("  {:>8} {}: {}", count, kind, message);
+        }
+    }
+}
+
+/// Validation of codes from arguments, files and standard input.
+struct Validator<'a> {
+    args: &'a ValidateArgs,
+    plausibility: Plausibility,
+    summary: Summary,
+    /// Writer for machine-readable output, `None` for diagnostics.
+    output: Option<Output>,
+    /// Validation stopped at the first invalid code.
+    stopped: bool,
+}
+
+impl<'a> Validator<'a> {
+    fn validate(&mut self, code: &str, location: Option<&str>) -> Result<(), Exit> {
+        let result = Ssn::parse_with_profile(code, self.args.profile.into());
+        let warnings = match result {
+            Ok(ref ssn) => self.plausibility.check(ssn),
+            Err(_) => Vec::new(),
+        };
+        if let Some(ref mut output) = self.output {
+            output.write(code, location, &result, &warnings)?;
+        }
+        match result {
+            Ok(_) => self.check(code, location, &warnings),
+            Err(ref err) => {
+                if self.output.is_none() {
+                    match location {
+                        Some(location) => print_error_at(err, code, location),
+                        None => print_error(err, code),
+                    }
+                }
+                self.summary.invalid += 1;
+                *self
+                    .summary
+                    .errors
+                    .entry((err.kind(), err.message_in(language()).to_string()))
+                    .or_insert(0) += 1;
+                self.stopped = self.args.fail_fast;
+            }
+        }
+        Ok(())
+    }
+
+    /// Report warnings of a valid code, counting it as invalid if a warning has severity error.
+    fn check(&mut self, code: &str, location: Option<&str>, warnings: &[Warning]) {
+        let mut invalid = false;
+        for warning in warnings {
+            if self.output.is_none() {
+                let label = match warning.severity {
+                    Severity::Error => "Error",
+                    Severity::Warning => "Warning",
+                    _ => "Info",
+                };
+                let message = warning.to_string_in(language());
+                match location {
+                    Some(location) => eprintln!("{}: {}: {}: {}", label, location, code, message),
+                    None => eprintln!("{}: {}: {}", label, code, message),
+                }
+            }
+            if warning.severity == Severity::Error {
+                invalid = true;
+                *self
+                    .summary
+                    .errors
+                    .entry((
+                        warning.kind.name(),
+                        language().translate(warning.kind.description()).to_string(),
+                    ))
+                    .or_insert(0) += 1;
+            }
+        }
+        if invalid {
+            self.summary.invalid += 1;
+            self.stopped = self.args.fail_fast;
+        } else {
+            self.summary.valid += 1;
+        }
+    }
+
+    /// Validate every non-empty line.
+    fn validate_lines<R: BufRead>(&mut self, reader: R, path: &str) -> Result<(), Exit> {
+        for (number, line) in reader.lines().enumerate() {
+            if self.stopped {
+                break;
+            }
+            let line = line.map_err(|err| {
+                eprintln!("Error: {}: {}", path, err);
+                Exit::Io
+            })?;
+            let code = line.trim();
+            if !code.is_empty() {
+                self.validate(code, Some(&format!("{}:{}", path, number + 1)))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn run(&mut self) -> Result<(), Exit> {
+        let args = self.args;
+        for code in &args.codes {
+            if self.stopped {
+                return Ok(());
+            }
+            if code == "-" {
+                self.validate_lines(io::stdin().lock(), "-")?;
+            } else {
+                self.validate(code, None)?;
+            }
+        }
+        for path in &args.files {
+            if self.stopped {
+                return Ok(());
+            }
+            let file = File::open(path).map_err(|err| {
+                eprintln!("Error: {}: {}", path, err);
+                Exit::Io
+            })?;
+            self.validate_lines(BufReader::new(file), path)?;
+        }
+        if args.codes.is_empty() && args.files.is_empty() {
+            self.validate_lines(io::stdin().lock(), "-")?;
+        }
+        Ok(())
+    }
+}
+
+/// Validate codes from arguments, files and standard input.
+pub fn run(args: &ValidateArgs) -> Result<(), Exit> {
+    let plausibility = args.warn.iter().fold(
+        Plausibility::new(args.output.on()),
+        |plausibility, &(kind, severity)| plausibility.with_severity(kind, severity),
+    );
+    let mut validator = Validator {
+        args,
+        plausibility,
+        summary: Summary::default(),
+        output: Output::new(
+            args.output.format,
+            args.output.on(),
+            args.output.gender_rule(),
+        ),
+        stopped: false,
+    };
+    validator.run()?;
+    if let Some(output) = validator.output.take() {
+        output.finish()?;
+    }
+    // Summary is only useful when validating a batch
+    let batch = !args.files.is_empty() || args.codes.len() != 1 || args.codes[0] == "-";
+    if batch {
+        validator.summary.print();
+    }
+    if validator.stopped || validator.summary.exceeds(args.max_invalid) {
+        Err(Exit::Invalid)
+    } else {
+        Ok(())
+    }
+}
@@ -0,0 +1,205 @@
+use std::fmt;
+
+use super::{Date, Gender, GenderRule, Language, Ssn};
+
+/// Fields claimed about a person separately from their personal identity code, e.g. in a form.
+///
+/// Fields that are `None` are not checked.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Claims {
+    pub birth_date: Option<Date>,
+    pub gender: Option<Gender>,
+    /// Age in full years on a reference date.
+    pub age: Option<(usize, Date)>,
+}
+
+/// Claimed field that contradicts the personal identity code.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Mismatch {
+    BirthDate {
+        claimed: Date,
+        actual: Date,
+    },
+    Gender {
+        claimed: Gender,
+        actual: Gender,
+    },
+    /// Actual age is `None` if the person was not born on the reference date.
+    Age {
+        claimed: usize,
+        actual: Option<usize>,
+        on: Date,
+    },
+}
+
+impl Mismatch {
+    /// Name of the mismatched field in snake case.
+    pub fn field(&self) -> &'static str {
+        match *self {
+            Mismatch::BirthDate { .. } => "birth_date",
+            Mismatch::Gender { .. } => "gender",
+            Mismatch::Age { .. } => "age",
+        }
+    }
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_string_in(Language::English))
+    }
+}
+
+impl Ssn {
+    /// Claimed fields that contradict the personal identity code, empty if all claims are consistent.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hetu::{Claims, Date, Gender, Mismatch, Ssn};
+    /// use std::convert::TryFrom;
+    ///
+    /// let ssn = Ssn::try_from("291269-2763").unwrap();
+    /// let claims = Claims {
+    ///     birth_date: Date::new(1969, 12, 29),
+    ///     gender: Some(Gender::Male),
+    ///     ..Claims::default()
+    /// };
+    /// assert_eq!(
+    ///     ssn.mismatches(&claims),
+    ///     vec![Mismatch::Gender { claimed: Gender::Male, actual: Gender::Female }]
+    /// );
+    /// ```
+    pub fn mismatches(&self, claims: &Claims) -> Vec<Mismatch> {
+        self.mismatches_by(claims, GenderRule::default())
+    }
+
+    /// Claimed fields that contradict the personal identity code, reading the gender by a rule.
+    ///
+    /// A claimed gender is not checked if the rule doesn't encode gender in the code.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hetu::{Claims, Gender, GenderRule, Ssn};
+    /// use std::convert::TryFrom;
+    ///
+    /// let ssn = Ssn::try_from("291269-2763").unwrap();
+    /// let claims = Claims {
+    ///     gender: Some(Gender::Male),
+    ///     ..Claims::default()
+    /// };
+    /// assert_eq!(ssn.mismatches_by(&claims, GenderRule::Parity).len(), 1);
+    /// assert!(ssn.mismatches_by(&claims, GenderRule::Neutral).is_empty());
+    /// ```
+    pub fn mismatches_by(&self, claims: &Claims, rule: GenderRule) -> Vec<Mismatch> {
+        let mut mismatches = Vec::new();
+        if let Some(claimed) = claims.birth_date {
+            let actual = self.birth_date();
+            if claimed != actual {
+                mismatches.push(Mismatch::BirthDate { claimed, actual });
+            }
+        }
+        if let (Some(claimed), Some(actual)) = (claims.gender, self.gender_by(rule)) {
+            if claimed != actual {
+                mismatches.push(Mismatch::Gender { claimed, actual });
+            }
+        }
+        if let Some((claimed, on)) = claims.age {
+            let actual = self.age_on(&on);
+            if actual != Some(claimed) {
+                mismatches.push(Mismatch::Age {
+                    claimed,
+                    actual,
+                    on,
+                });
+            }
+        }
+        mismatches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryFrom;
+
+    fn date(year: usize, month: usize, day: usize) -> Date {
+        Date::new(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_mismatches() {
+        let ssn = Ssn::try_from("291269-2763").unwrap();
+        assert!(ssn.mismatches(&Claims::default()).is_empty());
+        let consistent = Claims {
+            birth_date: Some(date(1969, 12, 29)),
+            gender: Some(Gender::Female),
+            age: Some((56, date(2026, 1, 1))),
+        };
+        assert!(ssn.mismatches(&consistent).is_empty());
+
+        let inconsistent = Claims {
+            birth_date: Some(date(1969, 12, 30)),
+            gender: Some(Gender::Male),
+            age: Some((55, date(2026, 1, 1))),
+        };
+        let mismatches = ssn.mismatches(&inconsistent);
+        assert_eq!(
+            mismatches.iter().map(Mismatch::field).collect::<Vec<_>>(),
+            vec!["birth_date", "gender", "age"]
+        );
+        assert_eq!(
+            mismatches[0].to_string(),
+            "Claimed birth date 1969-12-30 does not match birth date 1969-12-29"
+        );
+        assert_eq!(
+            mismatches[1].to_string_in(Language::Finnish),
+            "Ilmoitettu sukupuoli mies ei vastaa sukupuolta nainen"
+        );
+        assert_eq!(
+            mismatches[2].to_string_in(Language::Swedish),
+            "Uppgiven ålder 55 stämmer inte med åldern 56 den 2026-01-01"
+        );
+        assert_eq!(
+            mismatches[2],
+            Mismatch::Age {
+                claimed: 55,
+                actual: Some(56),
+                on: date(2026, 1, 1)
+            }
+        );
+    }
+
+    #[test]
+    fn test_mismatches_by() {
+        let ssn = Ssn::try_from("291269-2763").unwrap();
+        let claims = Claims {
+            birth_date: Some(date(1969, 12, 30)),
+            gender: Some(Gender::Male),
+            ..Claims::default()
+        };
+        let fields = |rule| {
+            ssn.mismatches_by(&claims, rule)
+                .iter()
+                .map(Mismatch::field)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(fields(GenderRule::Parity), vec!["birth_date", "gender"]);
+        assert_eq!(fields(GenderRule::Neutral), vec!["birth_date"]);
+    }
+
+    #[test]
+    fn test_mismatches_age_before_birth() {
+        let ssn = Ssn::try_from("291269-2763").unwrap();
+        let claims = Claims {
+            age: Some((0, date(1969, 1, 1))),
+            ..Claims::default()
+        };
+        let mismatches = ssn.mismatches(&claims);
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(
+            mismatches[0].to_string(),
+            "Claimed age 0 does not match, not born on 1969-01-01"
+        );
+    }
+}
@@ -0,0 +1,234 @@
+use std::convert::TryFrom;
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::{days_in_month, ParseError};
+
+/// Calendar date.
+///
+/// # Example
+///
+/// ```
+/// use hetu::Date;
+/// use std::convert::TryFrom;
+///
+/// let date = Date::try_from("2024-02-29").unwrap();
+/// assert_eq!(date, Date::new(2024, 2, 29).unwrap());
+/// assert_eq!(date.to_string(), "2024-02-29");
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    pub year: usize,
+    pub month: usize,
+    pub day: usize,
+}
+
+/// Day of the week.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl Date {
+    /// Create date, returns `None` if the date doesn't exist.
+    pub fn new(year: usize, month: usize, day: usize) -> Option<Date> {
+        if day < 1 || day > days_in_month(month, year) {
+            return None;
+        }
+        Some(Date { year, month, day })
+    }
+
+    /// Current date in UTC.
+    pub fn today() -> Date {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs());
+        Date::from_days((seconds / 86_400) as i64)
+    }
+
+    /// Date from number of days since 1970-01-01.
+    fn from_days(days: i64) -> Date {
+        // Algorithm from http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+        Date {
+            year: year.max(0) as usize,
+            month: month as usize,
+            day: day as usize,
+        }
+    }
+
+    /// Number of days since 1970-01-01.
+    fn days(&self) -> i64 {
+        // Algorithm from http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+        let year = self.year as i64 - if self.month <= 2 { 1 } else { 0 };
+        let era = year.div_euclid(400);
+        let yoe = year.rem_euclid(400);
+        let month = self.month as i64;
+        let doy =
+            (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + self.day as i64 - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146_097 + doe - 719_468
+    }
+
+    pub fn weekday(&self) -> Weekday {
+        match (self.days() + 3).rem_euclid(7) {
+            0 => Weekday::Monday,
+            1 => Weekday::Tuesday,
+            2 => Weekday::Wednesday,
+            3 => Weekday::Thursday,
+            4 => Weekday::Friday,
+            5 => Weekday::Saturday,
+            _ => Weekday::Sunday,
+        }
+    }
+
+    /// Date the given number of days later, or earlier for negative values.
+    pub fn add_days(&self, days: i64) -> Date {
+        Date::from_days(self.days() + days)
+    }
+
+    /// Same day the given number of years earlier. February 29 becomes February 28 on years that are not leap years.
+    pub fn years_before(&self, years: usize) -> Date {
+        let year = self.year.saturating_sub(years);
+        Date {
+            year,
+            month: self.month,
+            day: self.day.min(days_in_month(self.month, year)),
+        }
+    }
+
+    /// Age in full years on a date of someone born on this date, `None` if the date is before this date.
+    pub fn age_on(&self, date: &Date) -> Option<usize> {
+        if date < self {
+            return None;
+        }
+        let had_birthday = (date.month, date.day) >= (self.month, self.day);
+        Some(date.year - self.year - if had_birthday { 0 } else { 1 })
+    }
+
+    /// Parse date in ISO 8601 `YYYY-MM-DD` format.
+    fn parse(date: &str) -> Result<Date, ParseError<'_>> {
+        let bytes = date.as_bytes();
+        if bytes.len() != 10 || bytes[4] != b'-' || bytes[7] != b'-' {
+            return Err(ParseError::Syntax("Invalid date format", 0, date.len()));
+        }
+        let number = |start: usize, end: usize| {
+            bytes[start..end].iter().try_fold(0usize, |acc, b| {
+                if b.is_ascii_digit() {
+                    Some(acc * 10 + (b - b'0') as usize)
+                } else {
+                    None
+                }
+            })
+        };
+        let year = number(0, 4).ok_or(ParseError::Year("Year not integer", 0, 4))?;
+        let month = number(5, 7).ok_or(ParseError::Month("Month not integer", 5, 7))?;
+        if !(1..=12).contains(&month) {
+            return Err(ParseError::Month("Invalid month number", 5, 7));
+        }
+        let day = number(8, 10).ok_or(ParseError::Day("Day not integer", 8, 10))?;
+        Date::new(year, month, day).ok_or(ParseError::Day("Invalid day number", 8, 10))
+    }
+}
+
+impl<'a> TryFrom<&'a str> for Date {
+    type Error = ParseError<'a>;
+
+    /// Parse date in ISO 8601 `YYYY-MM-DD` format.
+    fn try_from(date: &'a str) -> Result<Self, Self::Error> {
+        Date::parse(date)
+    }
+}
+
+impl fmt::Display for Date {
+    /// Format date in ISO 8601 `YYYY-MM-DD` format.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl fmt::Display for Weekday {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match *self {
+            Weekday::Monday => "Monday",
+            Weekday::Tuesday => "Tuesday",
+            Weekday::Wednesday => "Wednesday",
+            Weekday::Thursday => "Thursday",
+            Weekday::Friday => "Friday",
+            Weekday::Saturday => "Saturday",
+            Weekday::Sunday => "Sunday",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_days_round_trip() {
+        for date in &[
+            Date::new(1970, 1, 1).unwrap(),
+            Date::new(1800, 3, 1).unwrap(),
+            Date::new(2000, 2, 29).unwrap(),
+            Date::new(2099, 12, 31).unwrap(),
+        ] {
+            assert_eq!(Date::from_days(date.days()), *date);
+        }
+        assert_eq!(Date::new(1970, 1, 1).unwrap().days(), 0);
+    }
+
+    #[test]
+    fn test_weekday() {
+        assert_eq!(Date::new(1970, 1, 1).unwrap().weekday(), Weekday::Thursday);
+        assert_eq!(Date::new(2023, 1, 1).unwrap().weekday(), Weekday::Sunday);
+        assert_eq!(Date::new(1895, 1, 1).unwrap().weekday(), Weekday::Tuesday);
+    }
+
+    #[test]
+    fn test_age_on() {
+        let birth = Date::new(2000, 2, 29).unwrap();
+        assert_eq!(birth.age_on(&Date::new(2018, 2, 28).unwrap()), Some(17));
+        assert_eq!(birth.age_on(&Date::new(2018, 3, 1).unwrap()), Some(18));
+        assert_eq!(birth.age_on(&Date::new(1999, 3, 1).unwrap()), None);
+    }
+
+    #[test]
+    fn test_years_before() {
+        assert_eq!(
+            Date::new(2024, 2, 29).unwrap().years_before(18),
+            Date::new(2006, 2, 28).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            Date::try_from("1999-12-31"),
+            Ok(Date::new(1999, 12, 31).unwrap())
+        );
+        assert_eq!(
+            Date::try_from("1999-02-29").unwrap_err(),
+            ParseError::Day("Invalid day number", 8, 10)
+        );
+        assert_eq!(
+            Date::try_from("19991231").unwrap_err(),
+            ParseError::Syntax("Invalid date format", 0, 8)
+        );
+    }
+}
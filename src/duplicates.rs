@@ -0,0 +1,375 @@
+//! Detection of probable duplicates among personal identity codes, e.g. the same person entered with a mistyped code.
+//!
+//! Codes are compared through indexes of their variants instead of pairwise, so millions of codes can be checked.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use super::{ParseError, Ssn};
+
+/// Why two codes are probably the same person, ordered from the weakest to the strongest reason.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Reason {
+    /// One character differs.
+    Substitution,
+    /// Two adjacent characters are swapped.
+    Transposition,
+    /// Same birth date and identifier with a different separator of the same century.
+    Separator,
+    /// Invalid code with its checksum corrected is the other code.
+    Checksum,
+    /// Same code, ignoring case and surrounding whitespace.
+    Identical,
+}
+
+/// Confidence that two codes are the same person.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Confidence {
+    Low,
+    Medium,
+    High,
+}
+
+impl Reason {
+    /// Every reason, from the weakest to the strongest.
+    pub fn all() -> &'static [Reason] {
+        &[
+            Reason::Substitution,
+            Reason::Transposition,
+            Reason::Separator,
+            Reason::Checksum,
+            Reason::Identical,
+        ]
+    }
+
+    /// Name of the reason in kebab case.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Reason::Substitution => "substitution",
+            Reason::Transposition => "transposition",
+            Reason::Separator => "separator",
+            Reason::Checksum => "checksum",
+            Reason::Identical => "identical",
+        }
+    }
+
+    /// Confidence of the reason.
+    pub fn confidence(&self) -> Confidence {
+        match *self {
+            Reason::Substitution => Confidence::Low,
+            Reason::Transposition => Confidence::Medium,
+            Reason::Separator | Reason::Checksum | Reason::Identical => Confidence::High,
+        }
+    }
+}
+
+impl fmt::Display for Reason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl Confidence {
+    /// Name of the confidence in lower case.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Confidence::Low => "low",
+            Confidence::Medium => "medium",
+            Confidence::High => "high",
+        }
+    }
+}
+
+impl fmt::Display for Confidence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Two codes that are probably the same person, by their indexes in the searched codes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Duplicate {
+    /// Index of the earlier code.
+    pub first: usize,
+    /// Index of the later code.
+    pub second: usize,
+    pub reason: Reason,
+}
+
+/// Codes connected by probable duplicates.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Group {
+    /// Indexes of the codes in ascending order.
+    pub codes: Vec<usize>,
+    /// Duplicates between the codes.
+    pub duplicates: Vec<Duplicate>,
+}
+
+/// Find probable duplicates among codes, valid or not.
+///
+/// Every pair of codes is reported once with its strongest reason. Identical copies of a code are reported as
+/// duplicates of its first occurrence only, and the other reasons are reported between first occurrences.
+///
+/// # Example
+///
+/// ```
+/// use hetu::duplicates::{self, Reason};
+///
+/// let codes = ["291269-2763", "291269Y2763", "292169-2763", "291269-2764", "010594Y123W"];
+/// let found: Vec<_> = duplicates::find(&codes)
+///     .iter()
+///     .map(|duplicate| (duplicate.first, duplicate.second, duplicate.reason))
+///     .collect();
+/// assert_eq!(
+///     found,
+///     vec![
+///         (0, 1, Reason::Separator),
+///         (0, 2, Reason::Transposition),
+///         (0, 3, Reason::Checksum),
+///     ]
+/// );
+/// ```
+pub fn find<S: AsRef<str>>(codes: &[S]) -> Vec<Duplicate> {
+    let mut found: HashMap<(usize, usize), Reason> = HashMap::new();
+    let mut add = |a: usize, b: usize, reason: Reason| {
+        let key = (a.min(b), a.max(b));
+        let strongest = found.entry(key).or_insert(reason);
+        *strongest = (*strongest).max(reason);
+    };
+
+    // First occurrence of every distinct code
+    let mut first: HashMap<Vec<char>, usize> = HashMap::new();
+    let mut distinct: Vec<(usize, Vec<char>)> = Vec::new();
+    for (i, code) in codes.iter().enumerate() {
+        let chars: Vec<char> = code.as_ref().trim().to_uppercase().chars().collect();
+        match first.get(&chars) {
+            Some(&original) => add(original, i, Reason::Identical),
+            None => {
+                first.insert(chars.clone(), i);
+                distinct.push((i, chars));
+            }
+        }
+    }
+
+    // Century of valid codes, and checksum-corrected invalid codes that collide with a valid code
+    let mut centuries: HashMap<usize, usize> = HashMap::new();
+    for &(i, ref chars) in &distinct {
+        match Ssn::parse(&chars.iter().collect::<String>()) {
+            Ok(ssn) => {
+                centuries.insert(i, ssn.year / 100);
+            }
+            Err(ParseError::Checksum(_, _, _, expected)) => {
+                let mut corrected = chars.clone();
+                corrected[10] = expected;
+                if let Some(&valid) = first.get(&corrected) {
+                    add(valid, i, Reason::Checksum);
+                }
+            }
+            Err(_) => {}
+        }
+    }
+
+    // Codes that are equal with one position masked out, or with two adjacent characters in order, are next to each
+    // other when sorted by that variant, so one sort per position finds them without comparing every pair
+    let longest = distinct
+        .iter()
+        .map(|(_, chars)| chars.len())
+        .max()
+        .unwrap_or(0);
+    for position in 0..longest {
+        for &transposed in &[false, true] {
+            let mut order: Vec<(u64, usize)> = distinct
+                .iter()
+                .enumerate()
+                .filter(|(_, (_, chars))| {
+                    position < chars.len()
+                        && (!transposed || (position > 0 && chars[position - 1] != chars[position]))
+                })
+                .map(|(k, (_, chars))| (hash(variant(chars, position, transposed)), k))
+                .collect();
+            order.sort_unstable();
+            for run in order.chunk_by(|a, b| a.0 == b.0) {
+                for (n, &(_, k)) in run.iter().enumerate() {
+                    for &(_, l) in &run[n + 1..] {
+                        let (a, ref chars_a) = distinct[k];
+                        let (b, ref chars_b) = distinct[l];
+                        if !variant(chars_a, position, transposed)
+                            .eq(variant(chars_b, position, transposed))
+                        {
+                            // Different variants with the same hash
+                            continue;
+                        }
+                        // Valid codes that differ only in the separator have the same birth date and identifier
+                        let century = centuries.get(&a);
+                        let reason = if transposed {
+                            Reason::Transposition
+                        } else if position == 6 && century.is_some() && century == centuries.get(&b)
+                        {
+                            Reason::Separator
+                        } else {
+                            Reason::Substitution
+                        };
+                        add(a, b, reason);
+                    }
+                }
+            }
+        }
+    }
+
+    let mut duplicates: Vec<Duplicate> = found
+        .into_iter()
+        .map(|((first, second), reason)| Duplicate {
+            first,
+            second,
+            reason,
+        })
+        .collect();
+    duplicates.sort_by_key(|duplicate| (duplicate.first, duplicate.second));
+    duplicates
+}
+
+/// Group codes connected by probable duplicates, in the order of their first code.
+///
+/// # Example
+///
+/// ```
+/// use hetu::duplicates;
+///
+/// let codes = ["291269-2763", "010594Y123W", "291269-2764", "010594Y123W"];
+/// let groups = duplicates::groups(&codes);
+/// assert_eq!(groups.len(), 2);
+/// assert_eq!(groups[0].codes, vec![0, 2]);
+/// assert_eq!(groups[1].codes, vec![1, 3]);
+/// ```
+pub fn groups<S: AsRef<str>>(codes: &[S]) -> Vec<Group> {
+    let duplicates = find(codes);
+    let mut parents: Vec<usize> = (0..codes.len()).collect();
+    fn root(parents: &mut [usize], mut i: usize) -> usize {
+        while parents[i] != i {
+            parents[i] = parents[parents[i]];
+            i = parents[i];
+        }
+        i
+    }
+    for duplicate in &duplicates {
+        let a = root(&mut parents, duplicate.first);
+        let b = root(&mut parents, duplicate.second);
+        parents[a.max(b)] = a.min(b);
+    }
+    let mut groups: Vec<Group> = Vec::new();
+    let mut group_of: HashMap<usize, usize> = HashMap::new();
+    for duplicate in duplicates {
+        let root = root(&mut parents, duplicate.first);
+        let index = *group_of.entry(root).or_insert_with(|| {
+            groups.push(Group {
+                codes: Vec::new(),
+                duplicates: Vec::new(),
+            });
+            groups.len() - 1
+        });
+        let group = &mut groups[index];
+        group.codes.push(duplicate.first);
+        group.codes.push(duplicate.second);
+        group.duplicates.push(duplicate);
+    }
+    for group in &mut groups {
+        group.codes.sort_unstable();
+        group.codes.dedup();
+    }
+    groups.sort_by_key(|group| group.codes[0]);
+    groups
+}
+
+/// Hash of characters, equal for equal characters.
+fn hash<I: Iterator<Item = char>>(chars: I) -> u64 {
+    chars.fold(0xcbf2_9ce4_8422_2325, |hash, c| {
+        (hash ^ c as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Characters of a code with a position masked out, or with the characters before and at the position in order.
+fn variant(chars: &[char], position: usize, transposed: bool) -> impl Iterator<Item = char> + '_ {
+    let (low, high) = if transposed {
+        let (a, b) = (chars[position - 1], chars[position]);
+        (a.min(b), a.max(b))
+    } else {
+        ('\0', '\0')
+    };
+    chars.iter().enumerate().map(move |(i, &c)| match i {
+        _ if !transposed && i == position => '\0',
+        _ if transposed && i == position - 1 => low,
+        _ if transposed && i == position => high,
+        _ => c,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reasons(codes: &[&str]) -> Vec<(usize, usize, Reason)> {
+        find(codes)
+            .iter()
+            .map(|duplicate| (duplicate.first, duplicate.second, duplicate.reason))
+            .collect()
+    }
+
+    #[test]
+    fn test_find() {
+        assert_eq!(
+            reasons(&["291269-2763", " 291269-2763 ", "291269-2863"]),
+            vec![(0, 1, Reason::Identical), (0, 2, Reason::Substitution)]
+        );
+        assert_eq!(
+            reasons(&["010594Y123W", "010594-123W", "010594A123W"]),
+            vec![
+                (0, 1, Reason::Separator),
+                (0, 2, Reason::Substitution),
+                (1, 2, Reason::Substitution),
+            ]
+        );
+        assert_eq!(
+            reasons(&["291269-2763", "219269-2763", "291296-2763"]),
+            vec![(0, 1, Reason::Transposition), (0, 2, Reason::Transposition)]
+        );
+        assert_eq!(
+            reasons(&["291269-2764", "291269-2763"]),
+            vec![(0, 1, Reason::Checksum)]
+        );
+        assert_eq!(reasons(&["291269-2763", "291269-2736"]).len(), 1);
+        assert!(reasons(&["291269-2763", "010594Y123W"]).is_empty());
+        assert!(reasons(&["291269-2763", "291269-276"]).is_empty());
+    }
+
+    #[test]
+    fn test_normalized() {
+        assert_eq!(
+            reasons(&["010594a123w", "010594B123W"]),
+            vec![(0, 1, Reason::Separator)]
+        );
+        assert_eq!(
+            reasons(&["291269-2763", "291269-2773"]),
+            vec![(0, 1, Reason::Substitution)]
+        );
+        assert_eq!(Reason::Checksum.confidence(), Confidence::High);
+        assert!(Reason::Substitution.confidence() < Reason::Transposition.confidence());
+    }
+
+    #[test]
+    fn test_groups() {
+        let codes = [
+            "291269-2763",
+            "010594Y123W",
+            "291269-2764",
+            "xyz",
+            "291269-2763",
+            "010594-123W",
+        ];
+        let groups = groups(&codes);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].codes, vec![0, 2, 4]);
+        assert_eq!(groups[0].duplicates.len(), 2);
+        assert_eq!(groups[1].codes, vec![1, 5]);
+        assert_eq!(groups[1].duplicates[0].reason, Reason::Separator);
+    }
+}
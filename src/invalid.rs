@@ -0,0 +1,276 @@
+use rand::Rng;
+use std::convert::TryFrom;
+use std::fmt;
+
+use super::{checksum, is_leap_year, ParseError, Ssn, SsnPattern, CHECKSUM_TABLE};
+
+/// Class of failure in a deliberately invalid personal identity code.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum InvalidKind {
+    /// Checksum character doesn't match the date and identifier.
+    WrongChecksum,
+    /// Birth date is February 30.
+    February30,
+    /// Birth date is February 29 on a year that is not a leap year.
+    February29NonLeapYear,
+    /// Birth month is 13.
+    Month13,
+    /// Birth day is 00.
+    Day00,
+    /// Identifier is 000.
+    Identifier000,
+    /// Identifier is 001.
+    Identifier001,
+    /// Separator is not one of the century separator characters.
+    InvalidSeparator,
+    /// Code is one character too short or too long.
+    WrongLength,
+    /// Birth date contains a non-digit character.
+    NonDigitDate,
+}
+
+impl InvalidKind {
+    /// Every failure class.
+    pub fn all() -> &'static [InvalidKind] {
+        &[
+            InvalidKind::WrongChecksum,
+            InvalidKind::February30,
+            InvalidKind::February29NonLeapYear,
+            InvalidKind::Month13,
+            InvalidKind::Day00,
+            InvalidKind::Identifier000,
+            InvalidKind::Identifier001,
+            InvalidKind::InvalidSeparator,
+            InvalidKind::WrongLength,
+            InvalidKind::NonDigitDate,
+        ]
+    }
+
+    /// Name of the failure class in kebab case.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            InvalidKind::WrongChecksum => "wrong-checksum",
+            InvalidKind::February30 => "february-30",
+            InvalidKind::February29NonLeapYear => "february-29-non-leap-year",
+            InvalidKind::Month13 => "month-13",
+            InvalidKind::Day00 => "day-00",
+            InvalidKind::Identifier000 => "identifier-000",
+            InvalidKind::Identifier001 => "identifier-001",
+            InvalidKind::InvalidSeparator => "invalid-separator",
+            InvalidKind::WrongLength => "wrong-length",
+            InvalidKind::NonDigitDate => "non-digit-date",
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a str> for InvalidKind {
+    type Error = ();
+
+    /// Parse failure class from its name.
+    fn try_from(name: &'a str) -> Result<Self, Self::Error> {
+        InvalidKind::all()
+            .iter()
+            .find(|kind| kind.name() == name)
+            .copied()
+            .ok_or(())
+    }
+}
+
+impl fmt::Display for InvalidKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Deliberately invalid personal identity code for negative testing.
+///
+/// # Example
+///
+/// ```
+/// use hetu::{InvalidKind, Ssn};
+/// use std::convert::TryFrom;
+///
+/// let invalid = Ssn::generate_invalid(InvalidKind::February30);
+/// assert!(Ssn::try_from(invalid.base.as_str()).is_ok());
+/// assert_eq!(Ssn::try_from(invalid.code.as_str()), Err(invalid.error));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct InvalidSsn {
+    /// Invalid personal identity code.
+    pub code: String,
+    /// Valid personal identity code the invalid code was produced from.
+    pub base: String,
+    pub kind: InvalidKind,
+    /// Error that parsing the invalid code is expected to return.
+    pub error: ParseError<'static>,
+}
+
+/// Format personal identity code from fields, without validating them.
+fn format(day: usize, month: usize, year: usize, separator: char, identifier: usize) -> String {
+    format!(
+        "{:02}{:02}{:02}{}{:03}{}",
+        day,
+        month,
+        year % 100,
+        separator,
+        identifier,
+        checksum(day, month, year, identifier)
+    )
+}
+
+impl Ssn {
+    /// Generate invalid personal identity code of a failure class.
+    ///
+    /// The code is produced from a random valid personal identity code by changing only the parts needed for
+    /// the failure. When possible, the checksum is recalculated so that the code fails only for the intended reason.
+    pub fn generate_invalid(kind: InvalidKind) -> InvalidSsn {
+        let mut rng = rand::thread_rng();
+        let base = match kind {
+            InvalidKind::February30 | InvalidKind::February29NonLeapYear => loop {
+                // Pattern is known to be valid and to match codes
+                let pattern = SsnPattern::try_from("2802???????").unwrap_or_default();
+                let code = Ssn::generate_by_pattern(&pattern).unwrap_or_else(|_| Ssn::generate());
+                match Ssn::try_from(code.as_str()) {
+                    Ok(ssn) if !is_leap_year(ssn.year) && ssn.day == 28 => break ssn,
+                    _ => continue,
+                }
+            },
+            _ => loop {
+                if let Ok(ssn) = Ssn::try_from(Ssn::generate().as_str()) {
+                    break ssn;
+                }
+            },
+        };
+        let (day, month, year, separator, identifier) = (
+            base.day,
+            base.month,
+            base.year,
+            base.separator,
+            base.identifier,
+        );
+        let (code, error) = match kind {
+            InvalidKind::WrongChecksum => {
+                let expected = base.checksum();
+                let wrong = loop {
+                    let c = *rng.choose(&CHECKSUM_TABLE).unwrap_or(&'0');
+                    if c != expected {
+                        break c;
+                    }
+                };
+                let mut code = base.to_string();
+                code.pop();
+                code.push(wrong);
+                (
+                    code,
+                    ParseError::Checksum("Incorrect checksum", 10, 11, expected),
+                )
+            }
+            InvalidKind::February30 => (
+                format(30, 2, year, separator, identifier),
+                ParseError::Day("Invalid day number", 0, 2),
+            ),
+            InvalidKind::February29NonLeapYear => (
+                format(29, 2, year, separator, identifier),
+                ParseError::Day("Invalid day number", 0, 2),
+            ),
+            InvalidKind::Month13 => (
+                format(day, 13, year, separator, identifier),
+                ParseError::Month("Invalid month number", 2, 4),
+            ),
+            InvalidKind::Day00 => (
+                format(0, month, year, separator, identifier),
+                ParseError::Day("Invalid day number", 0, 2),
+            ),
+            InvalidKind::Identifier000 => (
+                format(day, month, year, separator, 0),
+                ParseError::Identifier("Invalid identifier number", 10, 11),
+            ),
+            InvalidKind::Identifier001 => (
+                format(day, month, year, separator, 1),
+                ParseError::Identifier("Invalid identifier number", 10, 11),
+            ),
+            InvalidKind::InvalidSeparator => {
+                let invalid = *rng
+                    .choose(&['_', '/', '*', '.', ' ', 'Z', 'a', '0'])
+                    .unwrap_or(&'_');
+                let code = base
+                    .to_string()
+                    .chars()
+                    .enumerate()
+                    .map(|(i, c)| if i == 6 { invalid } else { c })
+                    .collect();
+                (code, ParseError::Syntax("Invalid separator", 6, 7))
+            }
+            InvalidKind::WrongLength => {
+                let mut code = base.to_string();
+                if rng.gen() {
+                    code.pop();
+                } else {
+                    code.push(*rng.choose(&CHECKSUM_TABLE).unwrap_or(&'0'));
+                }
+                let len = code.len();
+                (code, ParseError::Syntax("Invalid length", 0, len))
+            }
+            InvalidKind::NonDigitDate => {
+                let index = rng.gen_range(0, 6);
+                let letter = *rng.choose(&['O', 'I', 'l', 'S', 'B', 'x']).unwrap_or(&'O');
+                let code = base
+                    .to_string()
+                    .chars()
+                    .enumerate()
+                    .map(|(i, c)| if i == index { letter } else { c })
+                    .collect();
+                (code, ParseError::Syntax("Date not integer", 0, 6))
+            }
+        };
+        InvalidSsn {
+            code,
+            base: base.to_string(),
+            kind,
+            error,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_invalid() {
+        for kind in InvalidKind::all() {
+            for _ in 0..100 {
+                let invalid = Ssn::generate_invalid(*kind);
+                assert!(
+                    Ssn::try_from(invalid.base.as_str()).is_ok(),
+                    "{}: invalid base {}",
+                    kind,
+                    invalid.base
+                );
+                assert_eq!(
+                    Ssn::try_from(invalid.code.as_str()),
+                    Err(invalid.error),
+                    "{}: {}",
+                    kind,
+                    invalid.code
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_generate_invalid_keeps_base() {
+        let invalid = Ssn::generate_invalid(InvalidKind::Month13);
+        assert_eq!(invalid.code[..2], invalid.base[..2]);
+        assert_eq!(&invalid.code[2..4], "13");
+        assert_eq!(invalid.code[4..10], invalid.base[4..10]);
+    }
+
+    #[test]
+    fn test_kind_name() {
+        for kind in InvalidKind::all() {
+            assert_eq!(InvalidKind::try_from(kind.name()), Ok(*kind));
+        }
+        assert_eq!(InvalidKind::try_from("february-31"), Err(()));
+    }
+}
@@ -1,11 +1,42 @@
+#[cfg(feature = "codespan-reporting")]
+extern crate codespan_reporting;
 extern crate core;
+#[cfg(feature = "miette")]
+extern crate miette;
 extern crate rand;
 
-use rand::{Rng, ThreadRng};
+use rand::Rng;
 use std::convert::TryFrom;
 use std::error;
 use std::fmt;
 
+mod allocator;
+mod consistency;
+mod date;
+pub mod duplicates;
+mod invalid;
+mod locale;
+mod names;
+mod people;
+mod plausibility;
+mod profile;
+mod query;
+mod reform;
+#[cfg(any(feature = "miette", feature = "codespan-reporting"))]
+mod report;
+pub mod scan;
+
+pub use allocator::{AllocationError, Allocator, SnapshotError};
+pub use consistency::{Claims, Mismatch};
+pub use date::{Date, Weekday};
+pub use invalid::{InvalidKind, InvalidSsn};
+pub use locale::Language;
+pub use people::{AgeDistribution, People, Person};
+pub use plausibility::{Plausibility, Severity, Warning, WarningKind};
+pub use profile::Profile;
+pub use query::{Constraints, QueryError};
+pub use reform::{GenderReform, GenderRule};
+
 /// The personal identity code.
 ///
 /// # Example
@@ -30,405 +61,225 @@ pub struct Ssn {
     pub day: usize,
     pub month: usize,
     pub year: usize,
+    /// Gender by the parity of the identifier, see `gender_by` for codes issued after a gender reform.
     pub gender: Gender,
-}
-
-fn century_range(sep: &Option<char>) -> Vec<usize> {
-    match sep {
-        Some(c) => match from_separator(c) {
-            Ok(v) => vec![v],
-            Err(_) => panic!("Unsupported separator in pattern"),
-        },
-        None => vec![1800usize, 1900usize, 2000usize],
-    }
+    separator: char,
+    identifier: usize,
 }
 
 static SEPARATORS: [char; 13] = [
     '+', '-', 'Y', 'X', 'W', 'V', 'U', 'A', 'B', 'C', 'D', 'E', 'F',
 ];
 
-fn separator_range(sep: &Option<char>) -> Vec<char> {
-    match sep {
-        Some(c) => vec![*c],
-        None => SEPARATORS.to_vec(),
-    }
+/// Candidate values for each field of personal identity codes that match a pattern.
+#[derive(Debug)]
+struct Space {
+    years: Vec<(usize, char)>,
+    months: Vec<usize>,
+    days: Vec<usize>,
+    identifiers: Vec<usize>,
+    check: Option<char>,
+    born: Option<(Date, Date)>,
 }
 
-fn decade_range(y1: &Option<u8>, sep: &Option<char>) -> Vec<usize> {
-    match (y1, sep) {
-        (Some(v), _) => vec![*v as usize],
-        (None, Some('+')) => (5usize..=9usize).collect(),
-        (None, _) => (0usize..=9usize).collect(),
-    }
-}
+impl Space {
+    fn new(pattern: &SsnPattern, born: Option<(Date, Date)>) -> Result<Space, GenerateError> {
+        pattern.validate().map_err(|_| GenerateError)?;
 
-fn y2_range(rng: &mut ThreadRng, y2: &Option<u8>) -> Vec<usize> {
-    match y2 {
-        Some(v) => vec![*v as usize],
-        None => {
-            let mut range: Vec<usize> = (0usize..=9usize).collect();
-            rng.shuffle(&mut range);
-            range
+        let mut years = Vec::new();
+        for sep in pattern.sep.iter() {
+            let century = from_separator(&sep).map_err(|_| GenerateError)?;
+            for y1 in pattern.y1.iter() {
+                for y2 in pattern.y2.iter() {
+                    years.push((century + y1 as usize * 10 + y2 as usize, sep));
+                }
+            }
         }
-    }
-}
-
-fn m_range(m1: &Option<u8>, m2: &Option<u8>) -> Vec<usize> {
-    match (m1, m2) {
-        (Some(ref m1), Some(ref m2)) => {
-            let m = (m1 * 10 + m2) as usize;
-            if !(1..=12).contains(&m) {
-                panic!("Unsupported month {} in pattern", &m);
-            };
-            vec![m]
+        if let Some((earliest, latest)) = born {
+            years.retain(|&(year, _)| earliest.year <= year && year <= latest.year);
+        }
+        // Unless the pattern explicitly sets the year to be before 1850, don't generate years before 1850.
+        if years.iter().any(|&(year, _)| year >= 1850) {
+            years.retain(|&(year, _)| year >= 1850);
         }
-        (Some(ref m1), None) => ((if *m1 == 0 { 1 } else { 0 })..(if *m1 == 0 { 10 } else { 3 }))
-            .map(|m2| (m1 * 10 + m2) as usize)
-            .collect(),
-        (None, Some(ref m2)) => ((if *m2 == 0 { 1 } else { 0 })..2)
-            .map(|m1| (m1 * 10 + m2) as usize)
-            .collect(),
-        (None, None) => (1usize..13usize).collect(),
-    }
-}
 
-fn d_range(d1: &Option<u8>, d2: &Option<u8>) -> Vec<usize> {
-    match (d1, d2) {
-        (Some(ref d1), Some(ref d2)) => {
-            let d = (d1 * 10 + d2) as usize;
-            if d < 1 {
-                panic!("Unsupported day {} in pattern", &d);
-            };
-            vec![d]
+        let mut months = Vec::new();
+        for m1 in pattern.m1.iter() {
+            for m2 in pattern.m2.iter() {
+                months.push(m1 as usize * 10 + m2 as usize);
+            }
         }
-        (Some(ref d1), None) => {
-            // if *d1 < 1 || *d1 as usize > days_in_month / 10 {
-            //     return Err(GenerateError);
-            // };
-            ((if *d1 as usize == 0 { 1 } else { 0 })..=9)
-                .map(|d2| *d1 as usize * 10 + d2)
-                .collect()
+        months.retain(|m| (1..=12).contains(m));
+
+        let mut days = Vec::new();
+        for d1 in pattern.d1.iter() {
+            for d2 in pattern.d2.iter() {
+                days.push(d1 as usize * 10 + d2 as usize);
+            }
         }
-        (None, Some(ref d2)) => ((if *d2 as usize == 0 { 1 } else { 0 })..4)
-            .map(|d1| d1 * 10 + *d2 as usize)
-            .collect(),
-        (None, None) =>
-        // (1..(days_in_month + 1)).collect()
-        {
-            (1..=31).collect()
+        days.retain(|d| (1..=31).contains(d));
+
+        // Temporary identifiers in range of 900-999 are only generated when explicitly requested.
+        let i1s = if pattern.i1.is_any() {
+            Digits::range(0, 8)
+        } else {
+            pattern.i1
+        };
+        let mut identifiers = Vec::new();
+        for i1 in i1s.iter() {
+            for i2 in pattern.i2.iter() {
+                for i3 in pattern.i3.iter() {
+                    identifiers.push(i1 as usize * 100 + i2 as usize * 10 + i3 as usize);
+                }
+            }
         }
+        identifiers.retain(|i| *i >= 2);
+
+        Ok(Space {
+            years,
+            months,
+            days,
+            identifiers,
+            check: pattern.check,
+            born,
+        })
     }
-}
 
-pub fn generate_by_pattern_with_any_checksum(
-    pattern: &SsnPattern,
-) -> Result<String, GenerateError> {
-    let mut rng = rand::thread_rng();
+    fn shuffle<R: Rng>(&mut self, rng: &mut R) {
+        rng.shuffle(&mut self.years);
+        rng.shuffle(&mut self.months);
+        rng.shuffle(&mut self.days);
+        rng.shuffle(&mut self.identifiers);
+    }
 
-    let century = match (pattern.sep, pattern.y1) {
-        (Some(c), _) => match from_separator(&c) {
-            Ok(v) => v,
-            Err(_) => return Err(GenerateError),
-        },
-        // Unless the pattern explicitly sets the year to be before 1850, don't generate years before 1850.
-        (None, Some(y1)) if y1 < 6 => 1800 + rng.gen_range(1, 3) * 100,
-        _ => 1800 + rng.gen_range(0, 3) * 100,
-    };
-    let separator: char = match pattern.sep {
-        Some(s) => s,
-        None => match century {
-            1800 => '+',
-            1900 => *rng.choose(&['-', 'Y', 'X', 'W', 'V', 'U']).unwrap(),
-            2000 => *rng.choose(&['A', 'B', 'C', 'D', 'E', 'F']).unwrap(),
-            _ => return Err(GenerateError),
-        },
-    };
-    // Unless the pattern explicitly sets the year to be before 1850, don't generate years before 1850.
-    let decade = pattern
-        .y1
-        .unwrap_or_else(|| rng.gen_range(if century == 1800 { 5 } else { 0 }, 10))
-        as usize;
-    let y2 = pattern.y2.unwrap_or_else(|| rng.gen_range(0, 10)) as usize;
-    let year = century + decade * 10 + y2;
-
-    // Month generation needs to take days into consideration to handle leap years
-    let month: usize = match (pattern.m1, pattern.m2, pattern.d1, pattern.d2) {
-        (Some(ref m1), Some(ref m2), _, _) => (m1 * 10 + m2) as usize,
-
-        (Some(0), None, Some(3), Some(1)) => *rng.choose(&[1, 3, 5, 7, 8]).unwrap(),
-        (Some(0), None, Some(3), Some(0)) => *rng.choose(&[1, 3, 4, 5, 6, 7, 8, 9]).unwrap(),
-        (Some(0), None, Some(2), Some(9)) if !is_leap_year(year) => {
-            *rng.choose(&[1, 3, 4, 5, 6, 7, 8, 9]).unwrap()
-        }
-        (Some(0), None, _, _) => rng.gen_range(1, 10),
-        // FIXME: handle month 30 days
-        (Some(1), None, _, _) => rng.gen_range(10, 13),
-
-        (None, Some(0), _, _) => 10,
-        (None, Some(ref m2), _, _) => {
-            let m1 = rng.gen_range(0, 2);
-            (m1 * 10 + m2) as usize
-        }
+    /// Number of field combinations, including ones that are not valid dates or have a wrong checksum.
+    fn len(&self) -> usize {
+        self.years.len() * self.months.len() * self.days.len() * self.identifiers.len()
+    }
 
-        (None, None, Some(3), Some(1)) => *rng.choose(&[1, 3, 5, 7, 8, 10, 12]).unwrap(),
-        (None, None, Some(3), Some(0)) => {
-            *rng.choose(&[1, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]).unwrap()
-        }
-        (None, None, Some(2), Some(9)) if !is_leap_year(year) => {
-            *rng.choose(&[1, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]).unwrap()
-        }
+    /// Test whether date is inside the birth date range.
+    fn is_born(&self, year: usize, month: usize, day: usize) -> bool {
+        self.born.is_none_or(|(earliest, latest)| {
+            let date = Date { year, month, day };
+            earliest <= date && date <= latest
+        })
+    }
 
-        _ => rng.gen_range(1, 13),
-    };
-
-    let days_in_month = days_in_month(month, year);
-    let day: usize = match (pattern.d1, pattern.d2) {
-        (Some(ref d1), Some(ref d2)) => {
-            let d = (d1 * 10 + d2) as usize;
-            if d < 1 || d > days_in_month {
-                // XXX: This is a case where the pattern is e.g. 2902??????? which means the year
-                // must be a leap year. Currently, this corner case is not supported.
-                return Err(GenerateError);
-            };
-            d
+    /// Number of valid personal identity codes in the space.
+    fn count(&self) -> usize {
+        let mut residues = [0usize; 31];
+        for identifier in &self.identifiers {
+            residues[identifier % 31] += 1;
         }
-
-        (Some(3), None) if days_in_month == 31 => 30 + rng.gen_range(0, 2),
-        (Some(3), None) if days_in_month == 30 => 30,
-        (Some(2), None) if month == 2 && is_leap_year(year) => 20 + rng.gen_range(0, 10),
-        (Some(2), None) if month == 2 && !is_leap_year(year) => 20 + rng.gen_range(0, 9),
-        (Some(ref d1), None) => {
-            if *d1 as usize > days_in_month / 10 {
-                return Err(GenerateError);
-            };
-            let d2 = rng.gen_range(if *d1 as usize == 0 { 1 } else { 0 }, 10);
-            *d1 as usize * 10 + d2
+        let target = self
+            .check
+            .and_then(|c| CHECKSUM_TABLE.iter().position(|t| *t == c));
+        let mut count = 0;
+        for &(year, _) in &self.years {
+            for &month in &self.months {
+                let days_in_this_month = days_in_month(month, year);
+                for &day in self
+                    .days
+                    .iter()
+                    .filter(|d| **d <= days_in_this_month && self.is_born(year, month, **d))
+                {
+                    count += match target {
+                        Some(target) => {
+                            let date =
+                                (day * 10_000_000 + month * 100_000 + (year % 100) * 1_000) % 31;
+                            residues[(target + 31 - date) % 31]
+                        }
+                        None => self.identifiers.len(),
+                    };
+                }
+            }
         }
-        (None, Some(ref d2)) => {
-            let d1 = rng.gen_range(
-                if *d2 as usize == 0 { 1 } else { 0 },
-                if days_in_month % 10 == 3 { 4 } else { 3 },
-            ) as usize;
-            d1 * 10 + *d2 as usize
+        count
+    }
+
+    /// Personal identity code for a field combination, if the combination is valid.
+    fn get(&self, offset: usize) -> Option<String> {
+        let mut rest = offset;
+        let day = self.days[rest % self.days.len()];
+        rest /= self.days.len();
+        let month = self.months[rest % self.months.len()];
+        rest /= self.months.len();
+        let (year, separator) = self.years[rest % self.years.len()];
+        rest /= self.years.len();
+        let identifier = self.identifiers[rest % self.identifiers.len()];
+
+        if day > days_in_month(month, year) || !self.is_born(year, month, day) {
+            return None;
         }
-        (None, None) => rng.gen_range(1, days_in_month + 1),
-    };
-
-    let i1 = pattern.i1.unwrap_or_else(|| rng.gen_range(0, 9)) as usize;
-    let i2 = pattern.i2.unwrap_or_else(|| rng.gen_range(0, 10)) as usize;
-    let i3 = pattern
-        .i3
-        .unwrap_or_else(|| rng.gen_range(if i1 == 0 && i2 == 0 { 2 } else { 0 }, 10))
-        as usize;
-    let identifier = i1 * 100 + i2 * 10 + i3;
-    if identifier < 2 {
-        return Err(GenerateError);
+        let checksum = checksum(day, month, year, identifier);
+        if self.check.is_some_and(|c| c != checksum) {
+            return None;
+        }
+        Some(format!(
+            "{:02}{:02}{:02}{}{:03}{}",
+            day,
+            month,
+            year % 100,
+            separator,
+            identifier,
+            checksum
+        ))
     }
-    let nums = day * 10_000_000 + month * 100_000 + (year % 100) * 1_000 + identifier;
-    let checksum = CHECKSUM_TABLE[nums % 31];
-
-    Ok(format!(
-        "{:02}{:02}{:02}{}{:03}{}",
-        day,
-        month,
-        year % 100,
-        separator,
-        identifier,
-        checksum
-    ))
 }
 
+/// Generate personal identity code with matching fields and any checksum character.
+pub fn generate_by_pattern_with_any_checksum(
+    pattern: &SsnPattern,
+) -> Result<String, GenerateError> {
+    Ssn::generate_by_pattern(pattern)
+}
+
+/// Generate personal identity code with matching fields and a fixed checksum character.
 pub fn generate_by_pattern_with_fixed_checksum(
     pattern: &SsnPattern,
 ) -> Result<String, GenerateError> {
-    let mut rng = rand::thread_rng();
-    let mut separators = separator_range(&pattern.sep);
-    rng.shuffle(&mut separators);
-    let mut decades = decade_range(&pattern.y1, &pattern.sep);
-    rng.shuffle(&mut decades);
-    let mut y2s = y2_range(&mut rng, &pattern.y2);
-    rng.shuffle(&mut y2s);
-    let mut months = m_range(&pattern.m1, &pattern.m2);
-    rng.shuffle(&mut months);
-    let mut days = d_range(&pattern.d1, &pattern.d2);
-    rng.shuffle(&mut days);
-    let mut i1s = pattern
-        .i1
-        .map(|v| vec![v as usize])
-        .unwrap_or_else(|| (0usize..=8usize).collect());
-    rng.shuffle(&mut i1s);
-    let mut i2s = pattern
-        .i2
-        .map(|v| vec![v as usize])
-        .unwrap_or_else(|| (0usize..=9usize).collect());
-    rng.shuffle(&mut i2s);
-    let mut i3s = pattern
-        .i3
-        .map(|v| vec![v as usize])
-        // .unwrap_or_else(|| ((if i1 == 0 && i2 == 0 { 2usize } else { 0usize })..10usize)
-        .unwrap_or_else(|| (0usize..=9usize).collect());
-    rng.shuffle(&mut i3s);
-
-    for separator in &separators {
-        let century = from_separator(separator).unwrap();
-        for decade in &decades {
-            for y2 in &y2s {
-                let year = century + decade * 10 + y2;
-                // Unless the pattern explicitly sets the year to be before 1850, don't generate years before 1850.
-                println!("year: {}", year);
-                if year < 1850 && !(&pattern.sep == &Some('+') && &pattern.y1.unwrap_or(6u8) <= &5)
-                {
-                    continue;
-                }
-                for month in &months {
-                    let days_in_this_month = days_in_month(*month, year);
-                    for day in days.iter().filter(|d| d <= &&days_in_this_month) {
-                        for i1 in &i1s {
-                            for i2 in &i2s {
-                                for i3 in &i3s {
-                                    let identifier = i1 * 100 + i2 * 10 + i3;
-                                    if identifier < 2 {
-                                        continue;
-                                    }
-                                    let nums = day * 10_000_000
-                                        + month * 100_000
-                                        + (year % 100) * 1_000
-                                        + identifier;
-                                    let exp_checksum = CHECKSUM_TABLE[nums % 31];
-                                    let checksum = &pattern.check.unwrap();
-                                    if exp_checksum != *checksum {
-                                        // println!("{} != {} was not valid guess", exp_checksum, checksum);
-                                        continue;
-                                    }
-                                    return Ok(format!(
-                                        "{:02}{:02}{:02}{}{:03}{}",
-                                        day,
-                                        month,
-                                        year % 100,
-                                        separator,
-                                        identifier,
-                                        checksum
-                                    ));
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
+    if pattern.check.is_none() {
+        return Err(GenerateError);
     }
-    Err(GenerateError)
+    Ssn::generate_by_pattern(pattern)
 }
 
+/// Iterator over personal identity codes that match a pattern.
+///
+/// Codes are returned in random order and the iterator starts over after every matching code has been
+/// returned. Iteration ends if the pattern doesn't match any valid personal identity code.
 #[derive(Debug)]
 struct SsnIterator {
-    all: Vec<usize>,
-    bases: [usize; 8],
-    offsets: [usize; 8],
-    separators: Vec<char>,
-    check: Option<char>,
+    space: Space,
     offset: usize,
+    /// Step between visited field combinations, coprime with the space size so that every combination is visited.
+    stride: usize,
+    found: bool,
 }
 
 impl SsnIterator {
-    const OFFSET_CENTURY: usize = 0;
-    const OFFSET_DECADE: usize = 1;
-    const OFFSET_Y2: usize = 2;
-    const OFFSET_MOTH: usize = 3;
-    const OFFSET_DAY: usize = 4;
-    const OFFSET_I1: usize = 5;
-    const OFFSET_I2: usize = 6;
-    const OFFSET_I3: usize = 7;
-
-    pub fn new(pattern: &SsnPattern) -> SsnIterator {
-        let mut rng = rand::thread_rng();
-
-        let mut centuries = century_range(&pattern.sep);
-        rng.shuffle(&mut centuries);
-        let mut separators = separator_range(&pattern.sep);
-        rng.shuffle(&mut separators);
-        let mut decades = decade_range(&pattern.y1, &pattern.sep);
-        rng.shuffle(&mut decades);
-        let mut y2s = y2_range(&mut rng, &pattern.y2);
-        rng.shuffle(&mut y2s);
-        let mut months = m_range(&pattern.m1, &pattern.m2);
-        rng.shuffle(&mut months);
-        let mut days = d_range(&pattern.d1, &pattern.d2);
-        rng.shuffle(&mut days);
-        let mut i1s = pattern
-            .i1
-            .map(|v| vec![v as usize])
-            .unwrap_or_else(|| (0usize..=8usize).collect());
-        rng.shuffle(&mut i1s);
-        let mut i2s = pattern
-            .i2
-            .map(|v| vec![v as usize])
-            .unwrap_or_else(|| (0usize..=9usize).collect());
-        rng.shuffle(&mut i2s);
-        let mut i3s = pattern
-            .i3
-            .map(|v| vec![v as usize])
-            .unwrap_or_else(|| (0usize..=9usize).collect());
-        rng.shuffle(&mut i3s);
-
-        let all: Vec<usize> = vec![];
-
-        let mut res = SsnIterator {
-            all,
-            bases: [
-                centuries.len(),
-                centuries.len() * decades.len(),
-                centuries.len() * decades.len() * y2s.len(),
-                centuries.len() * decades.len() * y2s.len() * months.len(),
-                centuries.len() * decades.len() * y2s.len() * months.len() * days.len(),
-                centuries.len() * decades.len() * y2s.len() * months.len() * days.len() * i1s.len(),
-                centuries.len()
-                    * decades.len()
-                    * y2s.len()
-                    * months.len()
-                    * days.len()
-                    * i1s.len()
-                    * i2s.len(),
-                centuries.len()
-                    * decades.len()
-                    * y2s.len()
-                    * months.len()
-                    * days.len()
-                    * i1s.len()
-                    * i2s.len()
-                    * i3s.len(),
-            ],
-            offsets: [
-                0,
-                centuries.len(),
-                centuries.len() + decades.len(),
-                centuries.len() + decades.len() + y2s.len(),
-                centuries.len() + decades.len() + y2s.len() + months.len(),
-                centuries.len() + decades.len() + y2s.len() + months.len() + days.len(),
-                centuries.len() + decades.len() + y2s.len() + months.len() + days.len() + i1s.len(),
-                centuries.len()
-                    + decades.len()
-                    + y2s.len()
-                    + months.len()
-                    + days.len()
-                    + i1s.len()
-                    + i2s.len(),
-            ],
-            separators,
-            check: pattern.check,
-            offset: usize::MAX,
+    fn new<R: Rng>(constraints: &Constraints, rng: &mut R) -> Result<SsnIterator, GenerateError> {
+        let mut space = Space::new(&constraints.pattern, constraints.born())?;
+        space.shuffle(rng);
+        // Consecutive field combinations only differ by day, so step over the space to spread out birth dates and
+        // identifiers of consecutive codes.
+        let len = space.len();
+        let stride = if len > 1 {
+            loop {
+                let stride = rng.gen_range(1, len);
+                if gcd(stride, len) == 1 {
+                    break stride;
+                }
+            }
+        } else {
+            1
         };
-
-        res.all.append(&mut centuries);
-        res.all.append(&mut decades);
-        res.all.append(&mut y2s);
-        res.all.append(&mut months);
-        res.all.append(&mut days);
-        res.all.append(&mut i1s);
-        res.all.append(&mut i2s);
-        res.all.append(&mut i3s);
-
-        res
+        Ok(SsnIterator {
+            space,
+            offset: 0,
+            stride,
+            found: false,
+        })
     }
 }
 
@@ -436,87 +287,33 @@ impl Iterator for SsnIterator {
     type Item = String;
 
     fn next(&mut self) -> Option<Self::Item> {
+        let len = self.space.len();
+        if len == 0 {
+            return None;
+        }
         loop {
-            if self.offset == usize::MAX {
+            if self.offset == len {
+                if !self.found {
+                    return None;
+                }
                 self.offset = 0;
-            } else {
-                self.offset += 1;
-            }
-
-            // year
-            let century_index = (self.offset % self.bases[SsnIterator::OFFSET_CENTURY])
-                + self.offsets[SsnIterator::OFFSET_CENTURY];
-            let century = self.all[century_index];
-            let decade_index = (self.offset % self.bases[SsnIterator::OFFSET_DECADE])
-                / self.bases[SsnIterator::OFFSET_CENTURY]
-                + self.offsets[SsnIterator::OFFSET_DECADE];
-            let decade = self.all[decade_index];
-            let y2_index = (self.offset % self.bases[SsnIterator::OFFSET_Y2])
-                / self.bases[SsnIterator::OFFSET_DECADE]
-                + self.offsets[SsnIterator::OFFSET_Y2];
-            let y2 = self.all[y2_index];
-            // month
-            let month_index = (self.offset % self.bases[SsnIterator::OFFSET_MOTH])
-                / self.bases[SsnIterator::OFFSET_Y2]
-                + self.offsets[SsnIterator::OFFSET_MOTH];
-            let month = self.all[month_index];
-            // day
-            let day_index = (self.offset % self.bases[SsnIterator::OFFSET_DAY])
-                / self.bases[SsnIterator::OFFSET_MOTH]
-                + self.offsets[SsnIterator::OFFSET_DAY];
-            let day = self.all[day_index];
-            let year = century + decade * 10 + y2;
-            let separator = if self.separators.len() == 1 {
-                self.separators.first().unwrap()
-            } else {
-                self.separators
-                    .get(self.offset % self.separators.len())
-                    .unwrap()
-            };
-            let days_in_this_month = days_in_month(month, year);
-            if day >= days_in_this_month {
-                continue;
             }
-            // identifier
-            let i1_index = (self.offset % self.bases[SsnIterator::OFFSET_I1])
-                / self.bases[SsnIterator::OFFSET_DAY]
-                + self.offsets[SsnIterator::OFFSET_I1];
-            let i1 = self.all[i1_index];
-            let i2_index = (self.offset % self.bases[SsnIterator::OFFSET_I2])
-                / self.bases[SsnIterator::OFFSET_I1]
-                + self.offsets[SsnIterator::OFFSET_I2];
-            let i2 = self.all[i2_index];
-            let i3_index = (self.offset % self.bases[SsnIterator::OFFSET_I3])
-                / self.bases[SsnIterator::OFFSET_I2]
-                + self.offsets[SsnIterator::OFFSET_I3];
-            let i3 = self.all[i3_index];
-            let identifier = i1 * 100 + i2 * 10 + i3;
-            if identifier < 2 {
-                continue;
+            let offset = (self.offset as u128 * self.stride as u128 % len as u128) as usize;
+            self.offset += 1;
+            if let Some(ssn) = self.space.get(offset) {
+                self.found = true;
+                return Some(ssn);
             }
-            // checksum
-            let nums = day * 10_000_000 + month * 100_000 + (year % 100) * 1_000 + identifier;
-            let exp_checksum = CHECKSUM_TABLE[nums % 31];
-            let checksum = match self.check {
-                Some(c) => {
-                    if exp_checksum != c {
-                        continue;
-                    }
-                    c
-                }
-                None => exp_checksum,
-            };
-            return Some(format!(
-                "{:02}{:02}{:02}{}{:03}{}",
-                day,
-                month,
-                year % 100,
-                separator,
-                identifier,
-                checksum
-            ));
         }
-        // None
+    }
+}
+
+/// Greatest common divisor.
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
     }
 }
 
@@ -529,20 +326,32 @@ impl<'a> TryFrom<&'a str> for Ssn {
     }
 }
 
+/// Parse ASCII digits into a number.
+fn parse_digits(bytes: &[u8]) -> Option<usize> {
+    bytes.iter().try_fold(0usize, |acc, b| {
+        if b.is_ascii_digit() {
+            Some(acc * 10 + (b - b'0') as usize)
+        } else {
+            None
+        }
+    })
+}
+
 impl Ssn {
     /// Parse personal identity code.
-    fn parse(ssn: &str) -> Result<Ssn, ParseError> {
+    fn parse(ssn: &str) -> Result<Ssn, ParseError<'_>> {
         if ssn.len() != 11 {
             return Err(ParseError::Syntax("Invalid length", 0, ssn.len()));
         }
-        let chars: Vec<char> = ssn.chars().collect();
+        // Multibyte characters are never valid, so checking single bytes avoids slicing inside a character.
+        let bytes = ssn.as_bytes();
 
-        let separator = chars[6];
+        let separator = bytes[6] as char;
         from_separator(&separator)?;
 
-        let date: usize = match ssn[0..6].parse::<usize>() {
-            Ok(n) => n,
-            Err(_) => return Err(ParseError::Syntax("Date not integer", 0, 6)),
+        let date: usize = match parse_digits(&bytes[0..6]) {
+            Some(n) => n,
+            None => return Err(ParseError::Syntax("Date not integer", 0, 6)),
         };
 
         let month = date % 10_000 / 100;
@@ -562,20 +371,20 @@ impl Ssn {
             return Err(ParseError::Day("Invalid day number", 0, 2));
         }
 
-        let identifier: usize = match ssn[7..10].parse::<usize>() {
-            Ok(n) => n,
-            Err(_) => return Err(ParseError::Identifier("Invalid identifier", 7, 10)),
+        let identifier: usize = match parse_digits(&bytes[7..10]) {
+            Some(n) => n,
+            None => return Err(ParseError::Identifier("Invalid identifier", 7, 10)),
         };
-        if !(2..=899).contains(&identifier) {
+        if !(2..=999).contains(&identifier) {
             return Err(ParseError::Identifier("Invalid identifier number", 10, 11));
         }
 
-        let checksum = checksum(ssn);
-        if checksum != chars[10] {
+        let checksum = checksum(day, month, year, identifier);
+        if checksum != bytes[10] as char {
             return Err(ParseError::Checksum("Incorrect checksum", 10, 11, checksum));
         }
 
-        let gender: Gender = if identifier % 2 == 0 {
+        let gender: Gender = if identifier.is_multiple_of(2) {
             Gender::Female
         } else {
             Gender::Male
@@ -586,24 +395,85 @@ impl Ssn {
             month,
             year,
             gender,
+            separator,
+            identifier,
         })
     }
 
+    /// Birth date.
+    pub fn birth_date(&self) -> Date {
+        Date {
+            year: self.year,
+            month: self.month,
+            day: self.day,
+        }
+    }
+
+    /// Age in full years on a date, `None` if the date is before the birth date.
+    pub fn age_on(&self, date: &Date) -> Option<usize> {
+        self.birth_date().age_on(date)
+    }
+
+    /// Test whether the personal identity code is a temporary one with identifier in range of 900-999.
+    pub fn is_temporary(&self) -> bool {
+        self.identifier >= 900
+    }
+
+    /// Separator character between the birth date and the identifier, e.g. `-` or `A`.
+    pub fn separator(&self) -> char {
+        self.separator
+    }
+
+    /// Individual number of the personal identity code, 2-899 or 900-999 for temporary codes.
+    pub fn identifier(&self) -> usize {
+        self.identifier
+    }
+
+    /// Checksum character of the personal identity code.
+    pub fn checksum(&self) -> char {
+        checksum(self.day, self.month, self.year, self.identifier)
+    }
+
+    /// Nine digit number of birth date and identifier that the checksum is calculated from.
+    ///
+    /// The checksum is the character in `CHECKSUM_TABLE` at the remainder of the number divided by 31.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hetu::{Ssn, CHECKSUM_TABLE};
+    /// use std::convert::TryFrom;
+    ///
+    /// let ssn = Ssn::try_from("291269-2763").unwrap();
+    /// assert_eq!(ssn.checksum_number(), 291_269_276);
+    /// assert_eq!(CHECKSUM_TABLE[ssn.checksum_number() % 31], ssn.checksum());
+    /// ```
+    pub fn checksum_number(&self) -> usize {
+        checksum_number(self.day, self.month, self.year, self.identifier)
+    }
+
     /// Generate random personal identity code.
     ///
     /// Temporary personal identity code with identifier range of 900-999 will never be created. To generate a
     /// temporary personal identity code, use `Ssn::generate_by_pattern(pattern)` with pattern that explicity has '9' as the
     /// first character of the identifier part.
     pub fn generate() -> String {
+        Ssn::generate_with_profile(Profile::Modern)
+    }
+
+    /// Generate random personal identity code with a separator of a compatibility profile.
+    ///
+    /// Like `Ssn::generate()`, temporary personal identity codes are never created.
+    pub fn generate_with_profile(profile: Profile) -> String {
         let mut rng = rand::thread_rng();
 
         let year = rng.gen_range(1890, 2016);
         let month = rng.gen_range(1, 13);
         let day = rng.gen_range(1, days_in_month(month, year) + 1);
-        let separator = to_separator(year, &mut rng).unwrap();
+        let separator = to_separator(year, profile, &mut rng)
+            .expect("every profile has separators for the 1800s, 1900s and 2000s");
         let identifier = rng.gen_range(2, 900);
-        let nums = day * 10_000_000 + month * 100_000 + (year % 100) * 1_000 + identifier;
-        let checksum = CHECKSUM_TABLE[nums % 31];
+        let checksum = checksum(day, month, year, identifier);
         format!(
             "{:02}{:02}{:02}{}{:03}{}",
             day,
@@ -616,16 +486,89 @@ impl Ssn {
     }
 
     /// Generate personal identity code with matching fields.
+    ///
+    /// Returns an error if the pattern is invalid or no valid personal identity code matches it.
     pub fn generate_by_pattern(pattern: &SsnPattern) -> Result<String, GenerateError> {
-        match &pattern.check {
-            Some(_) => generate_by_pattern_with_fixed_checksum(pattern),
-            None => generate_by_pattern_with_any_checksum(pattern),
-        }
+        Ssn::generate_by_constraints(&Constraints::from(*pattern))
+    }
+
+    /// Generate personal identity code that matches constraints.
+    ///
+    /// Returns an error if the constraints are invalid or no valid personal identity code matches them.
+    pub fn generate_by_constraints(constraints: &Constraints) -> Result<String, GenerateError> {
+        let mut rng = rand::thread_rng();
+        SsnIterator::new(constraints, &mut rng)?
+            .next()
+            .ok_or(GenerateError)
+    }
+
+    /// Number of distinct personal identity codes that can be generated with matching fields.
+    ///
+    /// Returns an error if the pattern is invalid.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hetu::{Ssn, SsnPattern};
+    /// use std::convert::TryFrom;
+    ///
+    /// let pattern = SsnPattern::try_from("[0-1]10197-100?").unwrap();
+    /// assert_eq!(Ssn::count(&pattern), Ok(2));
+    /// ```
+    pub fn count(pattern: &SsnPattern) -> Result<usize, GenerateError> {
+        Ssn::count_by_constraints(&Constraints::from(*pattern))
+    }
+
+    /// Number of distinct personal identity codes that can be generated matching constraints.
+    pub fn count_by_constraints(constraints: &Constraints) -> Result<usize, GenerateError> {
+        Ok(Space::new(&constraints.pattern, constraints.born())?.count())
     }
 
     /// Iterator for generated personal identity code with matching fields.
-    pub fn iter<'a>(pattern: &SsnPattern) -> impl Iterator<Item = String> + 'a {
-        SsnIterator::new(pattern)
+    ///
+    /// Returns an error if the pattern is invalid.
+    pub fn iter<'a>(
+        pattern: &SsnPattern,
+    ) -> Result<impl Iterator<Item = String> + 'a, GenerateError> {
+        Ssn::iter_by_constraints(&Constraints::from(*pattern))
+    }
+
+    /// Iterator for generated personal identity code that match constraints.
+    ///
+    /// Returns an error if the constraints are invalid.
+    pub fn iter_by_constraints<'a>(
+        constraints: &Constraints,
+    ) -> Result<impl Iterator<Item = String> + 'a, GenerateError> {
+        let mut rng = rand::thread_rng();
+        SsnIterator::new(constraints, &mut rng)
+    }
+
+    /// Iterator for generated personal identity code that match constraints, in an order given by a random number
+    /// generator.
+    ///
+    /// A seeded random number generator returns the same codes in the same order on every run.
+    ///
+    /// Returns an error if the constraints are invalid.
+    pub fn iter_by_constraints_with_rng<'a, R: Rng>(
+        constraints: &Constraints,
+        rng: &mut R,
+    ) -> Result<impl Iterator<Item = String> + 'a, GenerateError> {
+        SsnIterator::new(constraints, rng)
+    }
+}
+
+impl fmt::Display for Ssn {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:02}{:02}{:02}{}{:03}{}",
+            self.day,
+            self.month,
+            self.year % 100,
+            self.separator,
+            self.identifier,
+            self.checksum()
+        )
     }
 }
 
@@ -639,19 +582,330 @@ fn from_separator<'a>(separator: &char) -> Result<usize, ParseError<'a>> {
     }
 }
 
-/** Get separator character for year. */
-fn to_separator(year: usize, rng: &mut ThreadRng) -> Result<char, GenerateError> {
-    match year / 100 {
-        18 => Ok('+'),
-        19 => rng
-            .choose(&['-', 'Y', 'X', 'W', 'V', 'U'])
-            .ok_or(GenerateError)
-            .copied(),
-        20 => rng
-            .choose(&['A', 'B', 'C', 'D', 'E', 'F'])
-            .ok_or(GenerateError)
-            .copied(),
-        _ => Err(GenerateError),
+/** Get separator character of a profile for year. */
+fn to_separator<R: Rng>(year: usize, profile: Profile, rng: &mut R) -> Result<char, GenerateError> {
+    let separators: Vec<char> = Separators::century(year / 100 * 100)
+        .intersection(profile.separators())
+        .iter()
+        .collect();
+    rng.choose(&separators).ok_or(GenerateError).copied()
+}
+
+/// Set of allowed digits in a single position of a pattern.
+///
+/// # Example
+///
+/// ```
+/// use hetu::Digits;
+///
+/// let digits = Digits::range(0, 2);
+/// assert!(digits.contains(1));
+/// assert!(!digits.contains(3));
+/// assert_eq!(digits.to_string(), "[0-2]");
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Digits(u16);
+
+impl Digits {
+    const ALL: u16 = 0b11_1111_1111;
+
+    /// Any digit, written as '?' in a pattern.
+    pub fn any() -> Digits {
+        Digits(Digits::ALL)
+    }
+
+    /// No digit.
+    pub fn empty() -> Digits {
+        Digits(0)
+    }
+
+    /// Single digit. Values over 9 result in an empty set.
+    pub fn digit(digit: u8) -> Digits {
+        Digits::range(digit, digit)
+    }
+
+    /// Inclusive range of digits. Values over 9 are ignored.
+    pub fn range(start: u8, end: u8) -> Digits {
+        Digits(
+            (start..=end.min(9))
+                .filter(|d| *d <= 9)
+                .fold(0, |acc, d| acc | 1 << d),
+        )
+    }
+
+    /// Even digits, written as 'f' for female in the last identifier digit of a pattern.
+    pub fn even() -> Digits {
+        Digits(0b01_0101_0101)
+    }
+
+    /// Odd digits, written as 'm' for male in the last identifier digit of a pattern.
+    pub fn odd() -> Digits {
+        Digits(0b10_1010_1010)
+    }
+
+    /// Digits in either set.
+    pub fn union(self, other: Digits) -> Digits {
+        Digits(self.0 | other.0)
+    }
+
+    /// Digits in both sets.
+    pub fn intersection(self, other: Digits) -> Digits {
+        Digits(self.0 & other.0)
+    }
+
+    /// Digits that are not in the set.
+    pub fn complement(self) -> Digits {
+        Digits(!self.0 & Digits::ALL)
+    }
+
+    pub fn contains(&self, digit: u8) -> bool {
+        digit <= 9 && self.0 & 1 << digit != 0
+    }
+
+    pub fn is_any(&self) -> bool {
+        self.0 == Digits::ALL
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// The digit if the set contains exactly one digit.
+    pub fn single(&self) -> Option<u8> {
+        if self.0.count_ones() == 1 {
+            Some(self.0.trailing_zeros() as u8)
+        } else {
+            None
+        }
+    }
+
+    /// Digits in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = u8> {
+        let bits = self.0;
+        (0u8..=9u8).filter(move |d| bits & 1 << d != 0)
+    }
+
+    /// Parse digit class contents, e.g. `0-2` or `^9`.
+    fn parse_class(class: &str) -> Option<Digits> {
+        let (negated, class) = match class.strip_prefix('^') {
+            Some(rest) => (true, rest),
+            None => (false, class),
+        };
+        let chars: Vec<char> = class.chars().collect();
+        let digit = |c: char| c.to_digit(10).map(|d| d as u8);
+        let mut digits = Digits::empty();
+        let mut i = 0;
+        while i < chars.len() {
+            let start = digit(chars[i])?;
+            if chars.get(i + 1) == Some(&'-') {
+                let end = digit(*chars.get(i + 2)?)?;
+                if end < start {
+                    return None;
+                }
+                digits = digits.union(Digits::range(start, end));
+                i += 3;
+            } else {
+                digits = digits.union(Digits::digit(start));
+                i += 1;
+            }
+        }
+        if digits.is_empty() {
+            return None;
+        }
+        Some(if negated { digits.complement() } else { digits })
+    }
+}
+
+impl Default for Digits {
+    fn default() -> Digits {
+        Digits::any()
+    }
+}
+
+impl fmt::Display for Digits {
+    /// Format digits as a pattern token.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_any() {
+            return write!(f, "?");
+        }
+        if let Some(digit) = self.single() {
+            return write!(f, "{}", digit);
+        }
+        write!(f, "[")?;
+        let digits: Vec<u8> = self.iter().collect();
+        let mut i = 0;
+        while i < digits.len() {
+            let mut end = i;
+            while end + 1 < digits.len() && digits[end + 1] == digits[end] + 1 {
+                end += 1;
+            }
+            if end - i >= 2 {
+                write!(f, "{}-{}", digits[i], digits[end])?;
+            } else {
+                for digit in &digits[i..=end] {
+                    write!(f, "{}", digit)?;
+                }
+            }
+            i = end + 1;
+        }
+        write!(f, "]")
+    }
+}
+
+/// Set of allowed separator characters in a pattern.
+///
+/// # Example
+///
+/// ```
+/// use hetu::Separators;
+///
+/// let separators = Separators::century(1900);
+/// assert!(separators.contains('Y'));
+/// assert!(!separators.contains('A'));
+/// assert_eq!(separators.to_string(), "{19}");
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Separators(u16);
+
+impl Separators {
+    const ALL: u16 = 0b1_1111_1111_1111;
+
+    /// Any separator, written as '?' in a pattern.
+    pub fn any() -> Separators {
+        Separators(Separators::ALL)
+    }
+
+    /// No separator.
+    pub fn empty() -> Separators {
+        Separators(0)
+    }
+
+    /// Single separator character. Invalid separator characters result in an empty set.
+    pub fn separator(separator: char) -> Separators {
+        Separators(
+            SEPARATORS
+                .iter()
+                .position(|s| *s == separator)
+                .map_or(0, |i| 1 << i),
+        )
+    }
+
+    /// Separators for a century, written as `{18}`, `{19}` or `{20}` in a pattern.
+    pub fn century(century: usize) -> Separators {
+        SEPARATORS
+            .iter()
+            .filter(|s| from_separator(s).ok() == Some(century))
+            .fold(Separators::empty(), |acc, s| {
+                acc.union(Separators::separator(*s))
+            })
+    }
+
+    /// Separators used before 2023: `+`, `-` and `A`.
+    pub fn legacy() -> Separators {
+        ['+', '-', 'A'].iter().fold(Separators::empty(), |acc, s| {
+            acc.union(Separators::separator(*s))
+        })
+    }
+
+    /// Separator letters introduced in 2023: `Y`, `X`, `W`, `V`, `U`, `B`, `C`, `D`, `E` and `F`.
+    pub fn modern() -> Separators {
+        Separators(Separators::legacy().0 ^ Separators::ALL)
+    }
+
+    /// Separators in either set.
+    pub fn union(self, other: Separators) -> Separators {
+        Separators(self.0 | other.0)
+    }
+
+    /// Separators in both sets.
+    pub fn intersection(self, other: Separators) -> Separators {
+        Separators(self.0 & other.0)
+    }
+
+    pub fn contains(&self, separator: char) -> bool {
+        self.0 & Separators::separator(separator).0 != 0
+    }
+
+    pub fn is_any(&self) -> bool {
+        self.0 == Separators::ALL
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// The separator if the set contains exactly one separator.
+    pub fn single(&self) -> Option<char> {
+        if self.0.count_ones() == 1 {
+            Some(SEPARATORS[self.0.trailing_zeros() as usize])
+        } else {
+            None
+        }
+    }
+
+    /// Separators in the order of `SEPARATORS`.
+    pub fn iter(&self) -> impl Iterator<Item = char> {
+        let bits = self.0;
+        SEPARATORS
+            .iter()
+            .enumerate()
+            .filter(move |&(i, _)| bits & 1 << i != 0)
+            .map(|(_, s)| *s)
+    }
+
+    /// Parse separator family name, e.g. `19`.
+    fn parse_family(family: &str) -> Option<Separators> {
+        let separators = match family {
+            "18" => Separators::century(1800),
+            "19" => Separators::century(1900),
+            "20" => Separators::century(2000),
+            _ => return None,
+        };
+        Some(separators)
+    }
+
+    /// Parse separator class contents, e.g. `-Y`.
+    fn parse_class(class: &str) -> Option<Separators> {
+        let mut separators = Separators::empty();
+        for c in class.chars() {
+            let separator = Separators::separator(c);
+            if separator.is_empty() {
+                return None;
+            }
+            separators = separators.union(separator);
+        }
+        if separators.is_empty() {
+            return None;
+        }
+        Some(separators)
+    }
+}
+
+impl Default for Separators {
+    fn default() -> Separators {
+        Separators::any()
+    }
+}
+
+impl fmt::Display for Separators {
+    /// Format separators as a pattern token.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_any() {
+            return write!(f, "?");
+        }
+        if let Some(separator) = self.single() {
+            return write!(f, "{}", separator);
+        }
+        for century in &[1800, 1900, 2000] {
+            if *self == Separators::century(*century) {
+                return write!(f, "{{{}}}", century / 100);
+            }
+        }
+        write!(f, "[")?;
+        for separator in self.iter() {
+            write!(f, "{}", separator)?;
+        }
+        write!(f, "]")
     }
 }
 
@@ -667,37 +921,20 @@ fn to_separator(year: usize, rng: &mut ThreadRng) -> Result<char, GenerateError>
 /// let pattern = SsnPattern::try_from("141286-245?").unwrap();
 /// Ssn::generate_by_pattern(&pattern);
 /// ```
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
 pub struct SsnPattern {
-    pub d1: Option<u8>,
-    pub d2: Option<u8>,
-    pub m1: Option<u8>,
-    pub m2: Option<u8>,
-    pub y1: Option<u8>,
-    pub y2: Option<u8>,
-    pub sep: Option<char>,
-    pub i1: Option<u8>,
-    pub i2: Option<u8>,
-    pub i3: Option<u8>,
+    pub d1: Digits,
+    pub d2: Digits,
+    pub m1: Digits,
+    pub m2: Digits,
+    pub y1: Digits,
+    pub y2: Digits,
+    pub sep: Separators,
+    pub i1: Digits,
+    pub i2: Digits,
+    pub i3: Digits,
     pub check: Option<char>,
 }
-impl Default for SsnPattern {
-    fn default() -> SsnPattern {
-        SsnPattern {
-            d1: None,
-            d2: None,
-            m1: None,
-            m2: None,
-            y1: None,
-            y2: None,
-            sep: None,
-            i1: None,
-            i2: None,
-            i3: None,
-            check: None,
-        }
-    }
-}
 
 impl<'a> TryFrom<&'a str> for SsnPattern {
     type Error = ParseError<'a>;
@@ -709,6 +946,19 @@ impl<'a> TryFrom<&'a str> for SsnPattern {
 }
 
 impl SsnPattern {
+    /// Create personal identity code pattern from fields, `None` being a wildcard.
+    ///
+    /// Fields are validated the same way as when parsing a pattern from a string.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hetu::SsnPattern;
+    ///
+    /// assert!(SsnPattern::new(None, None, Some(1), Some(2), None, None, None, None, None, None, None).is_ok());
+    /// assert!(SsnPattern::new(None, None, Some(1), Some(9), None, None, None, None, None, None, None).is_err());
+    /// ```
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         d1: Option<u8>,
         d2: Option<u8>,
@@ -721,31 +971,142 @@ impl SsnPattern {
         i2: Option<u8>,
         i3: Option<u8>,
         check: Option<char>,
-    ) -> SsnPattern {
-        SsnPattern {
-            d1,
-            d2,
-            m1,
-            m2,
-            y1,
-            y2,
+    ) -> Result<SsnPattern, ParseError<'static>> {
+        let digit = |digit: Option<u8>, index: usize| match digit {
+            None => Ok(Digits::any()),
+            Some(d) if d <= 9 => Ok(Digits::digit(d)),
+            Some(_) => Err(ParseError::Syntax("Date not integer", index, index + 1)),
+        };
+        let sep = match sep {
+            None => Separators::any(),
+            Some(sep) if SEPARATORS.contains(&sep) => Separators::separator(sep),
+            Some(_) => {
+                return Err(ParseError::Syntax("Invalid separator character", 6, 7));
+            }
+        };
+        let pattern = SsnPattern {
+            d1: digit(d1, 0)?,
+            d2: digit(d2, 1)?,
+            m1: digit(m1, 2)?,
+            m2: digit(m2, 3)?,
+            y1: digit(y1, 4)?,
+            y2: digit(y2, 5)?,
             sep,
-            i1,
-            i2,
-            i3,
+            i1: digit(i1, 7)?,
+            i2: digit(i2, 8)?,
+            i3: digit(i3, 9)?,
             check,
-        }
+        };
+        pattern.validate()?;
+        Ok(pattern)
     }
-    fn parse_char(chars: &str, index: usize) -> Result<Option<u8>, ParseError> {
-        let c = chars.chars().nth(index).unwrap();
-        if c == '?' {
-            Ok(None)
-        } else {
-            Ok(Some(match chars[index..=index].parse::<u8>() {
-                Ok(n) => n,
-                Err(_) => return Err(ParseError::Syntax("Date not integer", index, index + 1)),
-            }))
+
+    /// Test whether personal identity code matches every fixed position of the pattern, including the
+    /// separator and checksum characters.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hetu::{Ssn, SsnPattern};
+    /// use std::convert::TryFrom;
+    ///
+    /// let pattern = SsnPattern::try_from("????9?-8???").unwrap();
+    /// assert!(pattern.matches(&Ssn::try_from("010195-800S").unwrap()));
+    /// assert!(!pattern.matches(&Ssn::try_from("010185-8006").unwrap()));
+    /// ```
+    pub fn matches(&self, ssn: &Ssn) -> bool {
+        let year = ssn.year % 100;
+        let digits = [
+            (self.d1, ssn.day / 10),
+            (self.d2, ssn.day % 10),
+            (self.m1, ssn.month / 10),
+            (self.m2, ssn.month % 10),
+            (self.y1, year / 10),
+            (self.y2, year % 10),
+            (self.i1, ssn.identifier / 100),
+            (self.i2, ssn.identifier / 10 % 10),
+            (self.i3, ssn.identifier % 10),
+        ];
+        digits
+            .iter()
+            .all(|&(digits, d)| d <= 9 && digits.contains(d as u8))
+            && self.sep.contains(ssn.separator)
+            && self.check.is_none_or(|c| c == ssn.checksum())
+    }
+
+    /// Pattern that matches codes matched by both patterns, `None` if no code can match both.
+    pub fn intersection(&self, other: &SsnPattern) -> Option<SsnPattern> {
+        let check = match (self.check, other.check) {
+            (Some(a), Some(b)) if a != b => return None,
+            (a, b) => a.or(b),
+        };
+        let pattern = SsnPattern {
+            d1: self.d1.intersection(other.d1),
+            d2: self.d2.intersection(other.d2),
+            m1: self.m1.intersection(other.m1),
+            m2: self.m2.intersection(other.m2),
+            y1: self.y1.intersection(other.y1),
+            y2: self.y2.intersection(other.y2),
+            sep: self.sep.intersection(other.sep),
+            i1: self.i1.intersection(other.i1),
+            i2: self.i2.intersection(other.i2),
+            i3: self.i3.intersection(other.i3),
+            check,
+        };
+        pattern.validate().ok().map(|_| pattern)
+    }
+
+    /// Test whether string is a valid personal identity code that matches the pattern.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hetu::SsnPattern;
+    /// use std::convert::TryFrom;
+    ///
+    /// let pattern = SsnPattern::try_from("??????-????").unwrap();
+    /// assert!(pattern.matches_str("010197-100P"));
+    /// assert!(!pattern.matches_str("010114A173M"));
+    /// assert!(!pattern.matches_str("010197-100X"));
+    /// ```
+    pub fn matches_str(&self, ssn: &str) -> bool {
+        Ssn::try_from(ssn).is_ok_and(|ssn| self.matches(&ssn))
+    }
+
+    /// Split pattern into tokens, returning the byte range of each token.
+    fn tokenize(p: &str) -> Result<Vec<(usize, usize)>, ParseError<'_>> {
+        let mut tokens = Vec::with_capacity(11);
+        let mut chars = p.char_indices().peekable();
+        while let Some((start, c)) = chars.next() {
+            let close = match c {
+                '[' => Some(']'),
+                '{' => Some('}'),
+                _ => None,
+            };
+            let end = match close {
+                Some(close) => match chars.by_ref().find(|&(_, c)| c == close) {
+                    Some((end, _)) => end + 1,
+                    None => return Err(ParseError::Syntax("Unclosed class", start, p.len())),
+                },
+                None => start + c.len_utf8(),
+            };
+            tokens.push((start, end));
         }
+        Ok(tokens)
+    }
+
+    fn parse_digits(p: &str, (start, end): (usize, usize)) -> Result<Digits, ParseError<'_>> {
+        let token = &p[start..end];
+        let digits = match token {
+            "?" => Some(Digits::any()),
+            _ if token.starts_with('[') => Digits::parse_class(&token[1..token.len() - 1]),
+            _ => token
+                .parse::<u8>()
+                .ok()
+                .filter(|d| token.len() == 1 && *d <= 9)
+                .map(Digits::digit),
+        };
+        digits.ok_or(ParseError::Syntax("Date not integer", start, end))
     }
 
     /// Parse personal identity code pattern from a string.
@@ -753,6 +1114,11 @@ impl SsnPattern {
     /// A character in the pattern string is either the desired character or a wildcard denoted by
     /// a '?' character. Any character in the pattern can be a wildcard.
     ///
+    /// Digit positions also accept a bracket class of digits and digit ranges, e.g. `[0-2]`, `[13579]` or `[^9]`.
+    /// The last identifier digit accepts 'f' for even (female) and 'm' for odd (male) digits. The separator accepts
+    /// a bracket class of separator characters, e.g. `[-A]`, or a century family of separators: `{18}`, `{19}` or
+    /// `{20}`.
+    ///
     /// # Example
     ///
     /// ```
@@ -763,92 +1129,160 @@ impl SsnPattern {
     /// SsnPattern::try_from("??????-????");
     /// // all other characters are fixed except the checksum
     /// SsnPattern::try_from("141286-245?");
+    /// // born on the first 19 days of a month in 1990s, female
+    /// SsnPattern::try_from("[0-1]???9?{19}??f?");
     /// ```
-    fn parse(p: &str) -> Result<SsnPattern, ParseError> {
-        if p.len() != 11 {
+    fn parse(p: &str) -> Result<SsnPattern, ParseError<'_>> {
+        let tokens = SsnPattern::tokenize(p)?;
+        if tokens.len() != 11 {
             return Err(ParseError::Syntax("Invalid length", 0, p.len()));
         }
-        let d1: Option<u8> = SsnPattern::parse_char(p, 0)?;
-        let d2: Option<u8> = SsnPattern::parse_char(p, 1)?;
-        let m1: Option<u8> = SsnPattern::parse_char(p, 2)?;
-        let m2: Option<u8> = SsnPattern::parse_char(p, 3)?;
-        let y1: Option<u8> = SsnPattern::parse_char(p, 4)?;
-        let y2: Option<u8> = SsnPattern::parse_char(p, 5)?;
-        let sep: Option<char> = match p.chars().nth(6).unwrap() {
-            '?' => None,
-            sep @ '+' => Some(sep),
-            sep @ '-' | sep @ 'Y' | sep @ 'X' | sep @ 'W' | sep @ 'V' | sep @ 'U' => Some(sep),
-            sep @ 'A' | sep @ 'B' | sep @ 'C' | sep @ 'D' | sep @ 'E' | sep @ 'F' => Some(sep),
-            _ => {
-                return Err(ParseError::Syntax("Invalid separator character", 6, 7));
-            }
+        let i3 = match &p[tokens[9].0..tokens[9].1] {
+            "f" => Digits::even(),
+            "m" => Digits::odd(),
+            _ => SsnPattern::parse_digits(p, tokens[9])?,
         };
-        let i1: Option<u8> = SsnPattern::parse_char(p, 7)?;
-        let i2: Option<u8> = SsnPattern::parse_char(p, 8)?;
-        let i3: Option<u8> = SsnPattern::parse_char(p, 9)?;
-        let check: Option<char> = match p.chars().nth(10).unwrap() {
-            '?' => None,
-            sep if CHECKSUM_TABLE.contains(&sep) => Some(sep),
-            _ => {
-                return Err(ParseError::Syntax("Invalid checksum character", 10, 11));
-            }
+        let (start, end) = tokens[6];
+        let sep = match &p[start..end] {
+            "?" => Some(Separators::any()),
+            token if token.starts_with('[') => Separators::parse_class(&token[1..token.len() - 1]),
+            token if token.starts_with('{') => Separators::parse_family(&token[1..token.len() - 1]),
+            token => token
+                .chars()
+                .next()
+                .map(Separators::separator)
+                .filter(|s| !s.is_empty()),
+        }
+        .ok_or(ParseError::Syntax(
+            "Invalid separator character",
+            start,
+            end,
+        ))?;
+        let (start, end) = tokens[10];
+        let check: Option<char> = match &p[start..end] {
+            "?" => None,
+            token => match token.chars().next() {
+                Some(check) if CHECKSUM_TABLE.contains(&check) => Some(check),
+                _ => {
+                    return Err(ParseError::Syntax("Invalid checksum character", start, end));
+                }
+            },
         };
 
-        match (i1, i2, i3) {
-            (Some(0), Some(0), Some(0)) | (Some(0), Some(0), Some(1)) => {
-                return Err(ParseError::Identifier(
-                    "Invalid identifier too small",
-                    7,
-                    10,
-                ));
+        let pattern = SsnPattern {
+            d1: SsnPattern::parse_digits(p, tokens[0])?,
+            d2: SsnPattern::parse_digits(p, tokens[1])?,
+            m1: SsnPattern::parse_digits(p, tokens[2])?,
+            m2: SsnPattern::parse_digits(p, tokens[3])?,
+            y1: SsnPattern::parse_digits(p, tokens[4])?,
+            y2: SsnPattern::parse_digits(p, tokens[5])?,
+            sep,
+            i1: SsnPattern::parse_digits(p, tokens[7])?,
+            i2: SsnPattern::parse_digits(p, tokens[8])?,
+            i3,
+            check,
+        };
+        // Validation reports positions of the pattern fields, map them to the tokens in the string
+        pattern
+            .validate()
+            .map_err(|err| err.with_range(tokens[err.start()].0, tokens[err.end() - 1].1))?;
+        Ok(pattern)
+    }
+
+    /// Validate pattern fields.
+    fn validate(&self) -> Result<(), ParseError<'static>> {
+        let digits = [
+            (self.d1, 0),
+            (self.d2, 1),
+            (self.m1, 2),
+            (self.m2, 3),
+            (self.y1, 4),
+            (self.y2, 5),
+            (self.i1, 7),
+            (self.i2, 8),
+            (self.i3, 9),
+        ];
+        for &(digits, index) in digits.iter() {
+            if digits.is_empty() {
+                return Err(ParseError::Syntax("Empty digit class", index, index + 1));
             }
-            _ => {}
+        }
+        if self.sep.is_empty() {
+            return Err(ParseError::Syntax("Invalid separator character", 6, 7));
+        }
+        if self
+            .check
+            .is_some_and(|check| !CHECKSUM_TABLE.contains(&check))
+        {
+            return Err(ParseError::Syntax("Invalid checksum character", 10, 11));
         }
 
-        match (d1, d2, m1, m2, y1, y2, sep) {
-            (Some(0), Some(0), _, _, _, _, _) => {
-                return Err(ParseError::Day("Invalid day too small", 0, 1));
-            }
-            (Some(d1), Some(d2), _, _, _, _, _) if (d1 * 10 + d2) > 31 => {
-                return Err(ParseError::Day("Invalid day too large", 0, 1));
-            }
-            (Some(d1), None, _, _, _, _, _) if d1 > 3 => {
-                return Err(ParseError::Day("Invalid day too large", 0, 1));
-            }
-            (_, _, Some(0), Some(0), _, _, _) => {
-                return Err(ParseError::Month("Invalid month too small", 0, 1));
-            }
-            (_, _, Some(m1), Some(m2), _, _, _) if m1 * 10 + m2 > 12 => {
-                return Err(ParseError::Month("Invalid month too large", 2, 3));
-            }
-            (_, _, Some(m1), None, _, _, _) if m1 > 1 => {
-                return Err(ParseError::Month("Invalid month too large", 2, 3));
-            }
-            (Some(d1), Some(d2), Some(0), Some(2), Some(y1), Some(y2), Some(sep))
-                if (d1 * 10 + d2) as usize
-                    > days_in_month(2, from_separator(&sep)? + y1 as usize * 10 + y2 as usize) =>
+        let pairs = |first: Digits, second: Digits| {
+            first
+                .iter()
+                .flat_map(move |a| second.iter().map(move |b| a as usize * 10 + b as usize))
+        };
+        if pairs(self.i1, self.i2)
+            .flat_map(|i| self.i3.iter().map(move |i3| i * 10 + i3 as usize))
+            .all(|i| i < 2)
+        {
+            return Err(ParseError::Identifier(
+                "Invalid identifier too small",
+                7,
+                10,
+            ));
+        }
+        if pairs(self.d1, self.d2).all(|d| d < 1) {
+            return Err(ParseError::Day("Invalid day too small", 0, 1));
+        }
+        if !pairs(self.d1, self.d2).any(|d| (1..=31).contains(&d)) {
+            return Err(ParseError::Day("Invalid day too large", 0, 1));
+        }
+        if pairs(self.m1, self.m2).all(|m| m < 1) {
+            return Err(ParseError::Month("Invalid month too small", 0, 1));
+        }
+        if !pairs(self.m1, self.m2).any(|m| (1..=12).contains(&m)) {
+            return Err(ParseError::Month("Invalid month too large", 2, 3));
+        }
+
+        if let (Some(d1), Some(d2), Some(0), Some(2), Some(y1), Some(y2), Some(sep)) = (
+            self.d1.single(),
+            self.d2.single(),
+            self.m1.single(),
+            self.m2.single(),
+            self.y1.single(),
+            self.y2.single(),
+            self.sep.single(),
+        ) {
+            if (d1 * 10 + d2) as usize
+                > days_in_month(2, from_separator(&sep)? + y1 as usize * 10 + y2 as usize)
             {
                 return Err(ParseError::Month("Invalid day too large", 2, 7));
             }
-            // (_, _, _, _, Some(y1), Some(sep)) if from_separator(&sep)? == 1800 && y1 < 5 => {
-            //     return Err(ParseError::Year("Invalid year before 1850", 4, 7));
-            // }
-            _ => {}
         }
+        // if from_separator(&sep)? == 1800 && y1 < 5 {
+        //     return Err(ParseError::Year("Invalid year before 1850", 4, 7));
+        // }
+        Ok(())
+    }
+}
 
-        Ok(SsnPattern {
-            d1,
-            d2,
-            m1,
-            m2,
-            y1,
-            y2,
-            sep,
-            i1,
-            i2,
-            i3,
-            check,
-        })
+impl fmt::Display for SsnPattern {
+    /// Format pattern using a '?' character for wildcards.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}{}{}{}{}{}{}{}{}",
+            self.d1, self.d2, self.m1, self.m2, self.y1, self.y2, self.sep, self.i1, self.i2
+        )?;
+        if self.i3 == Digits::even() {
+            write!(f, "f")?;
+        } else if self.i3 == Digits::odd() {
+            write!(f, "m")?;
+        } else {
+            write!(f, "{}", self.i3)?;
+        }
+        write!(f, "{}", self.check.unwrap_or('?'))
     }
 }
 
@@ -858,6 +1292,40 @@ pub enum Gender {
     Male,
 }
 
+impl Gender {
+    /// Every gender.
+    pub fn all() -> &'static [Gender] {
+        &[Gender::Female, Gender::Male]
+    }
+
+    /// Name of the gender in lower case.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Gender::Female => "female",
+            Gender::Male => "male",
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a str> for Gender {
+    type Error = ();
+
+    /// Parse gender from its name.
+    fn try_from(name: &'a str) -> Result<Self, Self::Error> {
+        Gender::all()
+            .iter()
+            .find(|gender| gender.name() == name)
+            .copied()
+            .ok_or(())
+    }
+}
+
+impl fmt::Display for Gender {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum ParseError<'a> {
     Syntax(&'a str, usize, usize),
@@ -868,6 +1336,48 @@ pub enum ParseError<'a> {
     Checksum(&'a str, usize, usize, char),
 }
 
+impl<'a> ParseError<'a> {
+    /// Same error for a different index range.
+    fn with_range(self, start: usize, end: usize) -> ParseError<'a> {
+        match self {
+            ParseError::Syntax(desc, _, _) => ParseError::Syntax(desc, start, end),
+            ParseError::Day(desc, _, _) => ParseError::Day(desc, start, end),
+            ParseError::Month(desc, _, _) => ParseError::Month(desc, start, end),
+            ParseError::Year(desc, _, _) => ParseError::Year(desc, start, end),
+            ParseError::Identifier(desc, _, _) => ParseError::Identifier(desc, start, end),
+            ParseError::Checksum(desc, _, _, checksum) => {
+                ParseError::Checksum(desc, start, end, checksum)
+            }
+        }
+    }
+}
+
+impl ParseError<'_> {
+    /// Kind of the error: `syntax`, `day`, `month`, `year`, `identifier` or `checksum`.
+    pub fn kind(&self) -> &'static str {
+        match *self {
+            ParseError::Syntax(_, _, _) => "syntax",
+            ParseError::Day(_, _, _) => "day",
+            ParseError::Month(_, _, _) => "month",
+            ParseError::Year(_, _, _) => "year",
+            ParseError::Identifier(_, _, _) => "identifier",
+            ParseError::Checksum(_, _, _, _) => "checksum",
+        }
+    }
+
+    /// Message describing the error, e.g. `Invalid length`.
+    pub fn message(&self) -> &str {
+        match *self {
+            ParseError::Syntax(desc, _, _)
+            | ParseError::Day(desc, _, _)
+            | ParseError::Month(desc, _, _)
+            | ParseError::Year(desc, _, _)
+            | ParseError::Identifier(desc, _, _)
+            | ParseError::Checksum(desc, _, _, _) => desc,
+        }
+    }
+}
+
 impl fmt::Display for ParseError<'_> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
@@ -947,6 +1457,7 @@ impl ErrorIndexRange for ParseError<'_> {
     }
 }
 
+/// Number of days in month, or zero for an invalid month number.
 fn days_in_month(month: usize, year: usize) -> usize {
     match month {
         1 => 31,
@@ -967,28 +1478,27 @@ fn days_in_month(month: usize, year: usize) -> usize {
         10 => 31,
         11 => 30,
         12 => 31,
-        _ => panic!("Invalid month {} in year {}", month, year),
+        _ => 0,
     }
 }
 
-static CHECKSUM_TABLE: [char; 31] = [
+/// Checksum characters indexed by the remainder of the nine digit number of birth date and identifier divided by 31.
+pub static CHECKSUM_TABLE: [char; 31] = [
     '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', 'A', 'B', 'C', 'D', 'E', 'F', 'H', 'J', 'K',
     'L', 'M', 'N', 'P', 'R', 'S', 'T', 'U', 'V', 'W', 'X', 'Y',
 ];
 
-fn checksum(ssn: &str) -> char {
-    let mut hello: String = ssn[..6].to_string();
-    hello.push_str(&ssn[7..10]);
-    let nums: usize = hello.parse().unwrap();
-    CHECKSUM_TABLE[nums % 31]
+fn checksum(day: usize, month: usize, year: usize, identifier: usize) -> char {
+    CHECKSUM_TABLE[checksum_number(day, month, year, identifier) % 31]
+}
+
+/// Nine digit number of birth date and identifier that the checksum is calculated from.
+fn checksum_number(day: usize, month: usize, year: usize, identifier: usize) -> usize {
+    day * 10_000_000 + month * 100_000 + (year % 100) * 1_000 + identifier
 }
-// fn checksum_num(day: usize, month: usize, year: usize, identifier: usize) -> char {
-//     let nums = day * 10_000_000 + month * 100_000 + (year % 100) * 1_000 + identifier;
-//     CHECKSUM_TABLE[nums % 31]
-// }
 
 fn is_leap_year(year: usize) -> bool {
-    ((year % 4 == 0) && (year % 100 != 0)) || (year % 400 == 0)
+    (year.is_multiple_of(4) && !year.is_multiple_of(100)) || year.is_multiple_of(400)
 }
 
 #[cfg(test)]
@@ -1092,6 +1602,8 @@ mod tests {
                 month: 1,
                 year: 1895,
                 gender: Gender::Male,
+                separator: '+',
+                identifier: 433,
             }
         );
     }
@@ -1104,6 +1616,8 @@ mod tests {
                 month: 1,
                 year: 1997,
                 gender: Gender::Female,
+                separator: '-',
+                identifier: 100,
             }
         );
     }
@@ -1116,10 +1630,33 @@ mod tests {
                 month: 1,
                 year: 2014,
                 gender: Gender::Male,
+                separator: 'A',
+                identifier: 173,
             }
         );
     }
     #[test]
+    fn test_parse_temporary() {
+        assert_eq!(
+            Ssn::try_from("010195-9000").unwrap(),
+            Ssn {
+                day: 1,
+                month: 1,
+                year: 1995,
+                gender: Gender::Female,
+                separator: '-',
+                identifier: 900,
+            }
+        );
+        assert_eq!(
+            Ssn::try_from("010195-0010"),
+            Err(ParseError::Identifier("Invalid identifier number", 10, 11))
+        );
+        let pattern = SsnPattern::try_from("????9?-9???").unwrap();
+        assert!(pattern.matches_str("010195-9000"));
+        assert!(!pattern.matches_str("010195-900R"));
+    }
+    #[test]
     fn test_parse_leap_year() {
         // pass when given valid finnishSSN with leap year, divisible only by 4
         assert_eq!(
@@ -1129,6 +1666,8 @@ mod tests {
                 month: 2,
                 year: 1996,
                 gender: Gender::Female,
+                separator: '-',
+                identifier: 780,
             }
         );
     }
@@ -1150,6 +1689,8 @@ mod tests {
                 month: 2,
                 year: 2000,
                 gender: Gender::Female,
+                separator: 'A',
+                identifier: 248,
             }
         );
     }
@@ -1170,6 +1711,119 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_signed_identifier() {
+        assert!(
+            Ssn::try_from("010195-+12X").unwrap_err()
+                == ParseError::Identifier("Invalid identifier", 7, 10),
+            "fail when identifier has a sign"
+        );
+    }
+    #[test]
+    fn test_parse_multibyte_characters() {
+        assert!(
+            Ssn::try_from("0101ä-433X").unwrap_err()
+                == ParseError::Syntax("Date not integer", 0, 6),
+            "fail when given multibyte characters"
+        );
+    }
+
+    #[test]
+    fn test_pattern_new_invalid_month() {
+        assert!(SsnPattern::new(
+            None,
+            None,
+            Some(1),
+            Some(9),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_iter_invalid_pattern() {
+        let pattern = SsnPattern {
+            m1: Digits::digit(1),
+            m2: Digits::digit(9),
+            ..SsnPattern::default()
+        };
+        assert!(Ssn::iter(&pattern).is_err());
+        assert!(Ssn::generate_by_pattern(&pattern).is_err());
+    }
+
+    #[test]
+    fn test_iter_no_matching_codes() {
+        let pattern = SsnPattern::try_from("3102??-????").unwrap();
+        assert_eq!(Ssn::iter(&pattern).unwrap().next(), None);
+    }
+
+    #[test]
+    fn test_gender_name() {
+        for gender in Gender::all() {
+            assert_eq!(Gender::try_from(gender.name()), Ok(*gender));
+        }
+        assert_eq!(Gender::Female.to_string(), "female");
+        assert_eq!(Gender::try_from("f"), Err(()));
+    }
+
+    #[test]
+    fn test_error_kind_and_message() {
+        let err = Ssn::try_from("291269-2764").unwrap_err();
+        assert_eq!(err.kind(), "checksum");
+        assert_eq!(err.message(), "Incorrect checksum");
+        let err = Ssn::try_from("291269").unwrap_err();
+        assert_eq!(err.kind(), "syntax");
+        assert_eq!(err.message(), "Invalid length");
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            Ssn::try_from("290200A248A").unwrap().to_string(),
+            "290200A248A"
+        );
+    }
+
+    #[test]
+    fn test_accessors() {
+        let ssn = Ssn::try_from("010195Y9000").unwrap();
+        assert_eq!(ssn.separator(), 'Y');
+        assert_eq!(ssn.identifier(), 900);
+        assert_eq!(ssn.checksum(), '0');
+    }
+
+    #[test]
+    fn test_pattern_display() {
+        assert_eq!(
+            SsnPattern::try_from("?1?2??A??8?").unwrap().to_string(),
+            "?1?2??A??8?"
+        );
+        assert_eq!(SsnPattern::default().to_string(), "???????????");
+    }
+
+    #[test]
+    fn test_pattern_matches() {
+        let pattern = SsnPattern::try_from("????9?-8???").unwrap();
+        assert!(pattern.matches_str("010195-800S"));
+        assert!(!pattern.matches_str("010195Y800S"), "separator is fixed");
+        assert!(!pattern.matches_str("010185-8006"), "decade is fixed");
+        assert!(!pattern.matches_str("010195-700J"), "identifier is fixed");
+        assert!(!pattern.matches_str("010195-800R"), "must be valid");
+    }
+
+    #[test]
+    fn test_pattern_matches_checksum() {
+        let pattern = SsnPattern::try_from("??????????P").unwrap();
+        assert!(pattern.matches_str("010197-100P"));
+        assert!(!pattern.matches_str("010114A173M"));
+    }
+
     #[test]
     fn test_generate() {
         let ssn = Ssn::generate();
@@ -1179,7 +1833,7 @@ mod tests {
     #[test]
     fn test_iter() {
         let pattern = SsnPattern::try_from("010197-100P").unwrap();
-        let mut iter = Ssn::iter(&pattern);
+        let mut iter = Ssn::iter(&pattern).unwrap();
         let generated = iter.next().unwrap();
         assert!(Ssn::try_from(generated.as_str()).is_ok());
     }
@@ -1187,7 +1841,7 @@ mod tests {
     #[test]
     fn test_iter_wildcard() {
         let pattern = SsnPattern::try_from("???????????").unwrap();
-        let mut iter = Ssn::iter(&pattern);
+        let mut iter = Ssn::iter(&pattern).unwrap();
         let generated = iter.next().unwrap();
         assert!(
             Ssn::try_from(generated.as_str()).is_ok(),
@@ -1199,16 +1853,46 @@ mod tests {
     #[test]
     fn test_iter_fixed_repeated() {
         let pattern = SsnPattern::try_from("010197-100P").unwrap();
-        let mut iter = Ssn::iter(&pattern);
+        let mut iter = Ssn::iter(&pattern).unwrap();
         let first = iter.next().unwrap();
         let second = iter.next().unwrap();
         assert_eq!(first, second);
     }
 
+    #[test]
+    fn test_iter_with_rng() {
+        use rand::{SeedableRng, StdRng};
+        let constraints = Constraints::try_from("born:1990..1999 female").unwrap();
+        let generate = |seed: usize| -> Vec<String> {
+            let mut rng = StdRng::from_seed(&[seed][..]);
+            Ssn::iter_by_constraints_with_rng(&constraints, &mut rng)
+                .unwrap()
+                .take(10)
+                .collect()
+        };
+        let codes = generate(1);
+        assert_eq!(codes, generate(1));
+        assert!(codes.iter().all(|code| constraints.matches_str(code)));
+    }
+
     #[test]
     fn test_iter_wildcard_repeated() {
+        // January 1997 has four days ending with 1, so the iterator repeats after four codes
         let pattern = SsnPattern::try_from("?10197-100?").unwrap();
-        let mut iter = Ssn::iter(&pattern);
+        let mut iter = Ssn::iter(&pattern).unwrap();
+        let first: Vec<String> = iter.by_ref().take(4).collect();
+        let second: Vec<String> = iter.by_ref().take(4).collect();
+        assert_eq!(first, second);
+        let mut days: Vec<&str> = first.iter().map(|ssn| &ssn[..2]).collect();
+        days.sort_unstable();
+        assert_eq!(days, vec!["01", "11", "21", "31"]);
+    }
+
+    #[test]
+    fn test_iter_wildcard_repeated_short_month() {
+        // February 1997 has three days ending with 1, so the iterator repeats after three codes
+        let pattern = SsnPattern::try_from("?10297-100?").unwrap();
+        let mut iter = Ssn::iter(&pattern).unwrap();
         let first = vec![
             iter.next().unwrap(),
             iter.next().unwrap(),
@@ -1238,6 +1922,12 @@ mod tests {
         pattern_parse_valid_year: "????4?+????",
         pattern_parse_leap_year: "290224A????",
         pattern_parse_not_leap_year: "280225A????",
+        pattern_parse_digit_class: "[0-2]?[01]???-????",
+        pattern_parse_digit_class_negated: "??????-[^9]???",
+        pattern_parse_separator_class: "??????[-A]????",
+        pattern_parse_separator_family: "??????{19}????",
+        pattern_parse_gender_female: "??????-??f?",
+        pattern_parse_gender_male: "??????-??m?",
     }
 
     macro_rules! pattern_parse_failure {
@@ -1267,6 +1957,85 @@ mod tests {
         pattern_parse_day_too_large_leap_year: "300224A????",
         pattern_parse_day_too_large_not_leap_year: "290225A????",
         pattern_parse_day_too_large: "32????????A",
+        pattern_parse_day_class_too_large: "[4-9]??????????",
+        pattern_parse_month_class_too_large: "??[2-9]????????",
+        pattern_parse_empty_class: "[]??????????",
+        pattern_parse_unclosed_class: "[0-2??????????",
+        pattern_parse_reversed_range: "[2-0]??????????",
+        pattern_parse_letter_class: "[a]??????????",
+        pattern_parse_invalid_separator_class: "??????[-_]????",
+        pattern_parse_invalid_separator_family: "??????{17}????",
+        pattern_parse_gender_not_last_digit: "??????-f???",
+    }
+
+    #[test]
+    fn test_pattern_parse_class_error_range() {
+        assert_eq!(
+            SsnPattern::try_from("??[2-9]????????").unwrap_err(),
+            ParseError::Month("Invalid month too large", 2, 7)
+        );
+        assert_eq!(
+            SsnPattern::try_from("??????{17}????").unwrap_err(),
+            ParseError::Syntax("Invalid separator character", 6, 10)
+        );
+    }
+
+    #[test]
+    fn test_pattern_display_classes() {
+        for pattern in &[
+            "[0-2]?[01]???-????",
+            "??????{19}??f?",
+            "[013-5]?????[-A]??m?",
+        ] {
+            assert_eq!(
+                &SsnPattern::try_from(*pattern).unwrap().to_string(),
+                pattern
+            );
+        }
+        assert_eq!(
+            SsnPattern::try_from("[012]?????[UVWXY-]??[02468]?")
+                .unwrap()
+                .to_string(),
+            "[0-2]?????{19}??f?"
+        );
+    }
+
+    #[test]
+    fn test_pattern_matches_classes() {
+        let pattern = SsnPattern::try_from("????[89]?{19}9?f?").unwrap();
+        assert!(pattern.matches_str("010195-9000"));
+        assert!(pattern.matches_str("010185Y908N"));
+        assert!(!pattern.matches_str("010175-900U"), "decade is 8 or 9");
+        assert!(!pattern.matches_str("010195A9000"), "separator is 1900s");
+        assert!(!pattern.matches_str("010195-9011"), "gender is female");
+    }
+
+    #[test]
+    fn test_count() {
+        let count = |p| Ssn::count(&SsnPattern::try_from(p).unwrap()).unwrap();
+        assert_eq!(count("010197-100P"), 1);
+        assert_eq!(count("010197-100A"), 0);
+        assert_eq!(count("?10297-100?"), 3);
+        assert_eq!(count("010197{19}100?"), 6);
+        assert_eq!(count("010197-10m?"), 5);
+        // 1800s only generates years from 1850
+        assert_eq!(count("??????+???A"), 529010);
+    }
+
+    #[test]
+    fn test_count_matches_iter() {
+        use std::collections::HashSet;
+        for p in &[
+            "[0-1]1[01]2[89]7-10??",
+            "3??2??{19}[2-3]0f?",
+            "??0[1-2]9?-?[0-1]0A",
+        ] {
+            let pattern = SsnPattern::try_from(*p).unwrap();
+            let count = Ssn::count(&pattern).unwrap();
+            let codes: HashSet<String> = Ssn::iter(&pattern).unwrap().take(count * 2).collect();
+            assert_eq!(codes.len(), count, "{}", p);
+            assert!(codes.iter().all(|code| pattern.matches_str(code)), "{}", p);
+        }
     }
 
     #[test]
@@ -1327,6 +2096,31 @@ mod tests {
         )*}
     }
 
+    macro_rules! ssn_generate_class_success {
+        ($($name:ident: $value:expr,)*) => {$(
+            #[test]
+            fn $name() {
+                let pattern = &SsnPattern::try_from($value).unwrap();
+                for _ in 0..100 {
+                    let generated = Ssn::generate_by_pattern(pattern).unwrap();
+                    assert!(pattern.matches_str(&generated), "generate matching valid SSN: {}", &generated);
+                }
+            }
+        )*}
+    }
+
+    ssn_generate_class_success! {
+        generate_day_class: "[0-1]??????????",
+        generate_month_class: "??[0-1][1-2]???????",
+        generate_decade_class: "????[5-7]??????",
+        generate_separator_class: "??????[+A]????",
+        generate_separator_family: "??????{20}????",
+        generate_female: "?????????f?",
+        generate_male: "?????????m?",
+        generate_not_zero_identifier: "???????[^0][^0]??",
+        generate_classes_fixed_checksum: "[1-2]?0[1-3]9?{19}??fH",
+    }
+
     ssn_generate_failure! {
         // identifier_too_small_wildcard: "???????001?",
         // identifier_too_small_fixed: "???????001A",
@@ -0,0 +1,762 @@
+use std::convert::TryFrom;
+use std::fmt;
+
+use super::{Gender, GenerateError, Mismatch, ParseError, QueryError};
+
+/// Language of error messages and decoded information.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Default)]
+pub enum Language {
+    #[default]
+    English,
+    Finnish,
+    Swedish,
+}
+
+/// Kinds of parse errors in English, Finnish and Swedish, by the name of the kind.
+static ERROR_KINDS: &[(&str, &str, &str, &str)] = &[
+    (
+        "syntax",
+        "Invalid syntax",
+        "Virheellinen muoto",
+        "Ogiltig syntax",
+    ),
+    ("day", "Invalid day", "Virheellinen päivä", "Ogiltig dag"),
+    (
+        "month",
+        "Invalid month",
+        "Virheellinen kuukausi",
+        "Ogiltig månad",
+    ),
+    ("year", "Invalid year", "Virheellinen vuosi", "Ogiltigt år"),
+    (
+        "identifier",
+        "Invalid identifier",
+        "Virheellinen yksilönumero",
+        "Ogiltigt individnummer",
+    ),
+    (
+        "checksum",
+        "Invalid checksum",
+        "Virheellinen tarkiste",
+        "Ogiltigt kontrolltecken",
+    ),
+];
+
+/// Messages of parse errors in Finnish and Swedish, by the name of the kind and the English message.
+///
+/// Messages follow the kind in an error, so they don't repeat it.
+static ERROR_MESSAGES: &[(&str, &str, &str, &str)] = &[
+    ("syntax", "Invalid length", "väärä pituus", "fel längd"),
+    (
+        "syntax",
+        "Date not integer",
+        "päivämäärä ei ole kokonaisluku",
+        "datumet är inte ett heltal",
+    ),
+    (
+        "syntax",
+        "Invalid separator",
+        "tuntematon välimerkki",
+        "okänt skiljetecken",
+    ),
+    (
+        "syntax",
+        "Invalid separator character",
+        "tuntematon välimerkki",
+        "okänt skiljetecken",
+    ),
+    (
+        "syntax",
+        "Invalid checksum character",
+        "tuntematon tarkistemerkki",
+        "okänt kontrolltecken",
+    ),
+    (
+        "syntax",
+        "Invalid date format",
+        "päivämäärä ei ole muotoa VVVV-KK-PP",
+        "datumet har inte formen ÅÅÅÅ-MM-DD",
+    ),
+    (
+        "syntax",
+        "Unclosed class",
+        "sulkematon luokka",
+        "oavslutad klass",
+    ),
+    (
+        "syntax",
+        "Empty digit class",
+        "tyhjä numeroluokka",
+        "tom sifferklass",
+    ),
+    (
+        "syntax",
+        "Separator introduced in 2023",
+        "vuonna 2023 käyttöön otettu välimerkki",
+        "skiljetecken infört år 2023",
+    ),
+    (
+        "day",
+        "Invalid day number",
+        "päivää ei ole kuukaudessa",
+        "dagen finns inte i månaden",
+    ),
+    (
+        "day",
+        "Day not integer",
+        "ei kokonaisluku",
+        "inte ett heltal",
+    ),
+    ("day", "Invalid day too small", "liian pieni", "för liten"),
+    ("day", "Invalid day too large", "liian suuri", "för stor"),
+    (
+        "month",
+        "Invalid month number",
+        "ei välillä 1-12",
+        "inte mellan 1 och 12",
+    ),
+    (
+        "month",
+        "Month not integer",
+        "ei kokonaisluku",
+        "inte ett heltal",
+    ),
+    (
+        "month",
+        "Invalid month too small",
+        "liian pieni",
+        "för liten",
+    ),
+    (
+        "month",
+        "Invalid month too large",
+        "liian suuri",
+        "för stor",
+    ),
+    (
+        "month",
+        "Invalid day too large",
+        "päivä on liian suuri helmikuulle",
+        "dagen är för stor för februari",
+    ),
+    (
+        "year",
+        "Year not integer",
+        "ei kokonaisluku",
+        "inte ett heltal",
+    ),
+    (
+        "identifier",
+        "Invalid identifier",
+        "ei kokonaisluku",
+        "inte ett heltal",
+    ),
+    (
+        "identifier",
+        "Invalid identifier number",
+        "numerot 000 ja 001 eivät ole käytössä",
+        "nummer 000 och 001 används inte",
+    ),
+    (
+        "identifier",
+        "Invalid identifier too small",
+        "liian pieni",
+        "för litet",
+    ),
+    (
+        "checksum",
+        "Incorrect checksum",
+        "väärä tarkiste",
+        "fel kontrolltecken",
+    ),
+];
+
+/// Messages in English, Finnish and Swedish.
+///
+/// Messages are looked up by their English text. `{}` marks where arguments are placed, in the same order in every
+/// language.
+static CATALOG: &[(&str, &str, &str)] = &[
+    ("expected {}", "odotettiin {}", "förväntades {}"),
+    // Query errors
+    ("Invalid query", "Virheellinen kysely", "Ogiltig fråga"),
+    (
+        "Conflicting constraint",
+        "Ristiriitainen ehto",
+        "Motstridigt villkor",
+    ),
+    ("Unknown constraint", "Tuntematon ehto", "Okänt villkor"),
+    (
+        "Conflicting constraints",
+        "Ristiriitaiset ehdot",
+        "Motstridiga villkor",
+    ),
+    ("Invalid age", "Virheellinen ikä", "Ogiltig ålder"),
+    (
+        "Invalid birth date",
+        "Virheellinen syntymäaika",
+        "Ogiltigt födelsedatum",
+    ),
+    ("Invalid gender", "Virheellinen sukupuoli", "Ogiltigt kön"),
+    ("Invalid pattern", "Virheellinen malli", "Ogiltigt mönster"),
+    (
+        "Invalid separator",
+        "Virheellinen välimerkki",
+        "Ogiltigt skiljetecken",
+    ),
+    (
+        "Invalid reference date",
+        "Virheellinen vertailupäivä",
+        "Ogiltigt referensdatum",
+    ),
+    (
+        "Invalid temporary flag",
+        "Virheellinen väliaikaisuus",
+        "Ogiltig tillfällighet",
+    ),
+    ("Missing age", "Ikä puuttuu", "Ålder saknas"),
+    (
+        "Missing birth date",
+        "Syntymäaika puuttuu",
+        "Födelsedatum saknas",
+    ),
+    ("Missing gender", "Sukupuoli puuttuu", "Kön saknas"),
+    ("Missing pattern", "Malli puuttuu", "Mönster saknas"),
+    (
+        "Missing separator",
+        "Välimerkki puuttuu",
+        "Skiljetecken saknas",
+    ),
+    (
+        "Missing temporary flag",
+        "Väliaikaisuus puuttuu",
+        "Tillfällighet saknas",
+    ),
+    // Generation errors
+    (
+        "Unable to generate matching personal identity code",
+        "Ehtoja vastaavaa henkilötunnusta ei voitu luoda",
+        "Det gick inte att skapa en personbeteckning som uppfyller villkoren",
+    ),
+    // Plausibility warnings
+    (
+        "Birth date in the future",
+        "Syntymäaika tulevaisuudessa",
+        "Födelsedatum i framtiden",
+    ),
+    ("Age over 120", "Ikä yli 120", "Ålder över 120"),
+    (
+        "Born in the 1800s",
+        "Syntynyt 1800-luvulla",
+        "Född på 1800-talet",
+    ),
+    (
+        "Separator used before its introduction",
+        "Välimerkki käytössä ennen käyttöönottoaan",
+        "Skiljetecken använt före dess införande",
+    ),
+    (
+        "Temporary identifier",
+        "Väliaikainen yksilönumero",
+        "Tillfälligt individnummer",
+    ),
+    (
+        "Separator not understood by legacy systems",
+        "Vanhat järjestelmät eivät tunne välimerkkiä",
+        "Skiljetecken som äldre system inte förstår",
+    ),
+    (
+        "Birth date {} is after {}",
+        "Syntymäaika {} on päivän {} jälkeen",
+        "Födelsedatum {} är efter {}",
+    ),
+    (
+        "Age {} on {} is over {}",
+        "Ikä {} päivänä {} on yli {}",
+        "Åldern {} den {} är över {}",
+    ),
+    (
+        "Separator + is for birth dates in the 1800s, born {}",
+        "Välimerkki + on 1800-luvulla syntyneille, syntynyt {}",
+        "Skiljetecknet + är för födda på 1800-talet, född {}",
+    ),
+    (
+        "Separator {} was introduced in 2023, after {}",
+        "Välimerkki {} otettiin käyttöön vuonna 2023, päivän {} jälkeen",
+        "Skiljetecknet {} infördes år 2023, efter {}",
+    ),
+    (
+        "Identifier {} is temporary",
+        "Yksilönumero {} on väliaikainen",
+        "Individnumret {} är tillfälligt",
+    ),
+    (
+        "Separator {} is not understood by systems built before 2023",
+        "Ennen vuotta 2023 tehdyt järjestelmät eivät tunne välimerkkiä {}",
+        "System byggda före 2023 förstår inte skiljetecknet {}",
+    ),
+    // Mismatches between claims and codes
+    (
+        "Claimed birth date {} does not match birth date {}",
+        "Ilmoitettu syntymäaika {} ei vastaa syntymäaikaa {}",
+        "Uppgivet födelsedatum {} stämmer inte med födelsedatum {}",
+    ),
+    (
+        "Claimed gender {} does not match gender {}",
+        "Ilmoitettu sukupuoli {} ei vastaa sukupuolta {}",
+        "Uppgivet kön {} stämmer inte med kön {}",
+    ),
+    (
+        "Claimed age {} does not match age {} on {}",
+        "Ilmoitettu ikä {} ei vastaa ikää {} päivänä {}",
+        "Uppgiven ålder {} stämmer inte med åldern {} den {}",
+    ),
+    (
+        "Claimed age {} does not match, not born on {}",
+        "Ilmoitettu ikä {} ei täsmää, ei syntynyt päivänä {}",
+        "Uppgiven ålder {} stämmer inte, inte född den {}",
+    ),
+    // Decoded information
+    ("Birth date", "Syntymäaika", "Födelsedatum"),
+    ("Age", "Ikä", "Ålder"),
+    ("{} on {}", "{} päivänä {}", "{} den {}"),
+    (
+        "not born on {}",
+        "ei syntynyt päivänä {}",
+        "inte född den {}",
+    ),
+    ("Century", "Vuosisata", "Århundrade"),
+    ("{}00s", "{}00-luku", "{}00-talet"),
+    ("Separator", "Välimerkki", "Skiljetecken"),
+    (
+        "legacy, in use before 2023",
+        "vanha, käytössä ennen vuotta 2023",
+        "äldre, i bruk före 2023",
+    ),
+    (
+        "introduced in 2023",
+        "otettu käyttöön vuonna 2023",
+        "infört år 2023",
+    ),
+    ("Gender", "Sukupuoli", "Kön"),
+    ("female", "nainen", "kvinna"),
+    ("male", "mies", "man"),
+    (
+        "unknown (gender-neutral code)",
+        "tuntematon (sukupuolineutraali tunnus)",
+        "okänd (könsneutral beteckning)",
+    ),
+    (
+        "Gender is not encoded by gender-neutral codes",
+        "Sukupuolineutraali tunnus ei kerro sukupuolta",
+        "Könsneutral beteckning anger inte kön",
+    ),
+    (
+        "identifier is {}",
+        "yksilönumero on {}",
+        "individnumret är {}",
+    ),
+    ("even", "parillinen", "jämnt"),
+    ("odd", "pariton", "udda"),
+    ("Identifier", "Yksilönumero", "Individnummer"),
+    ("Temporary", "Väliaikainen", "Tillfällig"),
+    (
+        "yes (identifier in range 900-999)",
+        "kyllä (yksilönumero välillä 900-999)",
+        "ja (individnummer mellan 900 och 999)",
+    ),
+    ("no", "ei", "nej"),
+    ("Checksum", "Tarkiste", "Kontrolltecken"),
+    (
+        "birth date {} and identifier {}",
+        "syntymäaika {} ja yksilönumero {}",
+        "födelsedatum {} och individnummer {}",
+    ),
+    ("birth date", "syntymäaika", "födelsedatum"),
+    ("identifier", "yksilönumero", "individnummer"),
+    // Weekdays
+    ("Monday", "maanantai", "måndag"),
+    ("Tuesday", "tiistai", "tisdag"),
+    ("Wednesday", "keskiviikko", "onsdag"),
+    ("Thursday", "torstai", "torsdag"),
+    ("Friday", "perjantai", "fredag"),
+    ("Saturday", "lauantai", "lördag"),
+    ("Sunday", "sunnuntai", "söndag"),
+];
+
+impl Language {
+    /// Every language.
+    pub fn all() -> &'static [Language] {
+        &[Language::English, Language::Finnish, Language::Swedish]
+    }
+
+    /// ISO 639-1 code of the language.
+    pub fn code(&self) -> &'static str {
+        match *self {
+            Language::English => "en",
+            Language::Finnish => "fi",
+            Language::Swedish => "sv",
+        }
+    }
+
+    /// Language of a POSIX locale or a language tag, e.g. `fi_FI.UTF-8` or `sv-FI`.
+    ///
+    /// Returns `None` for other languages and for the `C` and `POSIX` locales.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hetu::Language;
+    ///
+    /// assert_eq!(Language::from_locale("fi_FI.UTF-8"), Some(Language::Finnish));
+    /// assert_eq!(Language::from_locale("sv-FI"), Some(Language::Swedish));
+    /// assert_eq!(Language::from_locale("C"), None);
+    /// ```
+    pub fn from_locale(locale: &str) -> Option<Language> {
+        let code = locale
+            .split(['_', '-', '.', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        Language::try_from(code.as_str()).ok()
+    }
+
+    /// Message in the language, or the English message if it has no translation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hetu::Language;
+    ///
+    /// assert_eq!(Language::Finnish.translate("Birth date"), "Syntymäaika");
+    /// assert_eq!(Language::Swedish.translate("Anything else"), "Anything else");
+    /// ```
+    pub fn translate<'a>(&self, message: &'a str) -> &'a str {
+        CATALOG
+            .iter()
+            .find(|entry| entry.0 == message)
+            .map(|entry| match *self {
+                Language::English => entry.0,
+                Language::Finnish => entry.1,
+                Language::Swedish => entry.2,
+            })
+            .unwrap_or(message)
+    }
+
+    /// Translated message with each `{}` replaced by the next argument.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hetu::Language;
+    ///
+    /// assert_eq!(Language::Swedish.format("{} on {}", &[&56, &"2026-01-01"]), "56 den 2026-01-01");
+    /// ```
+    pub fn format(&self, message: &str, args: &[&dyn fmt::Display]) -> String {
+        let mut parts = self.translate(message).split("{}");
+        let mut res = parts.next().unwrap_or_default().to_string();
+        for (i, part) in parts.enumerate() {
+            if let Some(arg) = args.get(i) {
+                res.push_str(&arg.to_string());
+            }
+            res.push_str(part);
+        }
+        res
+    }
+
+    /// Name of a gender.
+    pub fn gender(&self, gender: Gender) -> &'static str {
+        self.translate(gender.name())
+    }
+
+    /// Name of the century a year is in, e.g. 1900s.
+    pub fn century(&self, year: usize) -> String {
+        self.format("{}00s", &[&(year / 100)])
+    }
+
+    /// Entry of a message table in the language.
+    fn pick(&self, entry: &(&str, &'static str, &'static str, &'static str)) -> &'static str {
+        match *self {
+            Language::English => entry.1,
+            Language::Finnish => entry.2,
+            Language::Swedish => entry.3,
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a str> for Language {
+    type Error = ();
+
+    /// Parse language from its ISO 639-1 code.
+    fn try_from(code: &'a str) -> Result<Self, Self::Error> {
+        Language::all()
+            .iter()
+            .find(|language| language.code() == code)
+            .copied()
+            .ok_or(())
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+impl ParseError<'_> {
+    /// Message describing the error in a language, `message()` in English.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hetu::{Language, Ssn};
+    /// use std::convert::TryFrom;
+    ///
+    /// let err = Ssn::try_from("291269-276").unwrap_err();
+    /// assert_eq!(err.message_in(Language::Swedish), "fel längd");
+    /// ```
+    pub fn message_in(&self, language: Language) -> &str {
+        if language == Language::English {
+            return self.message();
+        }
+        ERROR_MESSAGES
+            .iter()
+            .find(|entry| entry.0 == self.kind() && entry.1 == self.message())
+            .map_or(self.message(), |entry| language.pick(entry))
+    }
+
+    /// Error message in a language, `to_string()` in English.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hetu::{Language, Ssn};
+    /// use std::convert::TryFrom;
+    ///
+    /// let err = Ssn::try_from("291269-2764").unwrap_err();
+    /// assert_eq!(err.to_string_in(Language::Finnish), "Virheellinen tarkiste: odotettiin 3");
+    /// ```
+    pub fn to_string_in(&self, language: Language) -> String {
+        if language == Language::English {
+            return self.to_string();
+        }
+        let kind = ERROR_KINDS
+            .iter()
+            .find(|entry| entry.0 == self.kind())
+            .map_or("", |entry| language.pick(entry));
+        match *self {
+            ParseError::Checksum(_, _, _, checksum) => {
+                format!("{}: {}", kind, language.format("expected {}", &[&checksum]))
+            }
+            _ => format!("{}: {}", kind, self.message_in(language)),
+        }
+    }
+}
+
+impl QueryError {
+    /// Error message in a language, `to_string()` in English.
+    pub fn to_string_in(&self, language: Language) -> String {
+        format!(
+            "{}: {}",
+            language.translate("Invalid query"),
+            language.translate(self.0)
+        )
+    }
+}
+
+impl Mismatch {
+    /// Description of the mismatch in a language, `to_string()` in English.
+    pub fn to_string_in(&self, language: Language) -> String {
+        match *self {
+            Mismatch::BirthDate { claimed, actual } => language.format(
+                "Claimed birth date {} does not match birth date {}",
+                &[&claimed, &actual],
+            ),
+            Mismatch::Gender { claimed, actual } => language.format(
+                "Claimed gender {} does not match gender {}",
+                &[&language.gender(claimed), &language.gender(actual)],
+            ),
+            Mismatch::Age {
+                claimed,
+                actual: Some(actual),
+                on,
+            } => language.format(
+                "Claimed age {} does not match age {} on {}",
+                &[&claimed, &actual, &on],
+            ),
+            Mismatch::Age {
+                claimed,
+                actual: None,
+                on,
+            } => language.format(
+                "Claimed age {} does not match, not born on {}",
+                &[&claimed, &on],
+            ),
+        }
+    }
+}
+
+impl GenerateError {
+    /// Error message in a language, `to_string()` in English.
+    pub fn to_string_in(&self, language: Language) -> String {
+        language.translate(&self.to_string()).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use {Ssn, WarningKind};
+
+    #[test]
+    fn test_language_code() {
+        for language in Language::all() {
+            assert_eq!(Language::try_from(language.code()), Ok(*language));
+        }
+        assert_eq!(Language::try_from("de"), Err(()));
+        assert_eq!(Language::from_locale("en_US"), Some(Language::English));
+        assert_eq!(Language::from_locale("SV_fi.utf8"), Some(Language::Swedish));
+        assert_eq!(Language::from_locale("fi@euro"), Some(Language::Finnish));
+        assert_eq!(Language::from_locale("POSIX"), None);
+        assert_eq!(Language::from_locale(""), None);
+    }
+
+    #[test]
+    fn test_catalog() {
+        for entry in CATALOG.iter() {
+            // Every translation takes the same arguments
+            let args = entry.0.matches("{}").count();
+            assert_eq!(entry.1.matches("{}").count(), args, "{}", entry.0);
+            assert_eq!(entry.2.matches("{}").count(), args, "{}", entry.0);
+            assert_eq!(
+                CATALOG.iter().filter(|e| e.0 == entry.0).count(),
+                1,
+                "{}",
+                entry.0
+            );
+        }
+        for entry in ERROR_MESSAGES.iter() {
+            assert!(
+                ERROR_KINDS.iter().any(|kind| kind.0 == entry.0),
+                "{}",
+                entry.0
+            );
+            assert_eq!(
+                ERROR_MESSAGES
+                    .iter()
+                    .filter(|e| (e.0, e.1) == (entry.0, entry.1))
+                    .count(),
+                1,
+                "{}",
+                entry.1
+            );
+        }
+    }
+
+    #[test]
+    fn test_warnings_translated() {
+        for kind in WarningKind::all() {
+            for &language in &[Language::Finnish, Language::Swedish] {
+                assert_ne!(language.translate(kind.description()), kind.description());
+                assert_ne!(language.translate(kind.message()), kind.message());
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_error_to_string_in() {
+        for code in &[
+            "291269-276",
+            "321269-2763",
+            "291369-2763",
+            "291269-0013",
+            "291269-2764",
+        ] {
+            let err = Ssn::try_from(*code).unwrap_err();
+            assert_eq!(err.to_string_in(Language::English), err.to_string());
+            assert_eq!(err.message_in(Language::English), err.message());
+        }
+        let err = Ssn::try_from("291269-2764").unwrap_err();
+        assert_eq!(
+            err.to_string_in(Language::Swedish),
+            "Ogiltigt kontrolltecken: förväntades 3"
+        );
+    }
+
+    #[test]
+    fn test_parse_error_variants_in() {
+        let errors = [
+            (
+                ParseError::Syntax("Invalid length", 0, 10),
+                "Virheellinen muoto: väärä pituus",
+                "Ogiltig syntax: fel längd",
+            ),
+            (
+                ParseError::Day("Invalid day number", 0, 2),
+                "Virheellinen päivä: päivää ei ole kuukaudessa",
+                "Ogiltig dag: dagen finns inte i månaden",
+            ),
+            (
+                ParseError::Month("Invalid month number", 2, 4),
+                "Virheellinen kuukausi: ei välillä 1-12",
+                "Ogiltig månad: inte mellan 1 och 12",
+            ),
+            (
+                ParseError::Year("Year not integer", 0, 4),
+                "Virheellinen vuosi: ei kokonaisluku",
+                "Ogiltigt år: inte ett heltal",
+            ),
+            (
+                ParseError::Identifier("Invalid identifier number", 10, 11),
+                "Virheellinen yksilönumero: numerot 000 ja 001 eivät ole käytössä",
+                "Ogiltigt individnummer: nummer 000 och 001 används inte",
+            ),
+            (
+                ParseError::Checksum("Incorrect checksum", 10, 11, 'N'),
+                "Virheellinen tarkiste: odotettiin N",
+                "Ogiltigt kontrolltecken: förväntades N",
+            ),
+        ];
+        for &(err, finnish, swedish) in &errors {
+            assert_eq!(err.to_string_in(Language::Finnish), finnish);
+            assert_eq!(err.to_string_in(Language::Swedish), swedish);
+        }
+        // Every message of the library is translated without repeating the kind
+        for entry in ERROR_MESSAGES.iter() {
+            let err = match entry.0 {
+                "syntax" => ParseError::Syntax(entry.1, 0, 1),
+                "day" => ParseError::Day(entry.1, 0, 1),
+                "month" => ParseError::Month(entry.1, 0, 1),
+                "year" => ParseError::Year(entry.1, 0, 1),
+                "identifier" => ParseError::Identifier(entry.1, 0, 1),
+                _ => continue,
+            };
+            for &language in &[Language::Finnish, Language::Swedish] {
+                let message = err.to_string_in(language);
+                let (kind, desc) = message.split_once(": ").unwrap();
+                assert!(
+                    !desc.to_lowercase().contains(&kind.to_lowercase()),
+                    "{}",
+                    message
+                );
+                assert!(!message.contains("Invalid"), "{}", message);
+            }
+        }
+    }
+
+    #[test]
+    fn test_decoded_info() {
+        assert_eq!(Language::Finnish.gender(Gender::Female), "nainen");
+        assert_eq!(Language::Swedish.gender(Gender::Male), "man");
+        assert_eq!(Language::English.century(1969), "1900s");
+        assert_eq!(Language::Finnish.century(2005), "2000-luku");
+        assert_eq!(Language::Swedish.century(1850), "1800-talet");
+        assert_eq!(
+            QueryError("Missing gender", 0, 7).to_string_in(Language::Finnish),
+            "Virheellinen kysely: Sukupuoli puuttuu"
+        );
+        assert_eq!(
+            GenerateError.to_string_in(Language::Swedish),
+            "Det gick inte att skapa en personbeteckning som uppfyller villkoren"
+        );
+    }
+}
@@ -0,0 +1,166 @@
+//! Finnish name frequency lists.
+//!
+//! Counts are approximate numbers of living people with the name as their first given name or as their surname, rounded
+//! from the name statistics of the Digital and Population Data Services Agency. Only the most common names are included.
+
+/// Female first names and their counts.
+pub static FEMALE_FIRST_NAMES: [(&str, u32); 50] = [
+    ("Anne", 31_000),
+    ("Tuula", 30_000),
+    ("Päivi", 29_000),
+    ("Anna", 28_000),
+    ("Ritva", 27_000),
+    ("Leena", 27_000),
+    ("Pirjo", 26_000),
+    ("Sari", 26_000),
+    ("Minna", 25_000),
+    ("Marja", 25_000),
+    ("Tiina", 24_000),
+    ("Riitta", 24_000),
+    ("Aino", 20_000),
+    ("Eeva", 20_000),
+    ("Laura", 19_000),
+    ("Pirkko", 19_000),
+    ("Hanna", 18_000),
+    ("Sanna", 17_000),
+    ("Johanna", 17_000),
+    ("Sirpa", 17_000),
+    ("Eija", 16_000),
+    ("Seija", 16_000),
+    ("Merja", 16_000),
+    ("Satu", 16_000),
+    ("Kirsi", 16_000),
+    ("Emma", 15_000),
+    ("Helena", 14_000),
+    ("Liisa", 14_000),
+    ("Elina", 13_000),
+    ("Heidi", 13_000),
+    ("Katja", 12_000),
+    ("Sofia", 11_000),
+    ("Ella", 11_000),
+    ("Aila", 10_000),
+    ("Sinikka", 10_000),
+    ("Kristiina", 10_000),
+    ("Maarit", 10_000),
+    ("Marjatta", 9_000),
+    ("Jenni", 9_000),
+    ("Sara", 9_000),
+    ("Emilia", 9_000),
+    ("Olivia", 8_000),
+    ("Venla", 8_000),
+    ("Aada", 7_000),
+    ("Kaisa", 7_000),
+    ("Linnea", 6_000),
+    ("Helmi", 6_000),
+    ("Elsa", 6_000),
+    ("Iida", 5_000),
+    ("Åsa", 1_000),
+];
+
+/// Male first names and their counts.
+pub static MALE_FIRST_NAMES: [(&str, u32); 50] = [
+    ("Juha", 37_000),
+    ("Timo", 35_000),
+    ("Matti", 33_000),
+    ("Kari", 32_000),
+    ("Mikko", 32_000),
+    ("Jari", 30_000),
+    ("Antti", 29_000),
+    ("Jukka", 28_000),
+    ("Mika", 27_000),
+    ("Markku", 26_000),
+    ("Pekka", 25_000),
+    ("Hannu", 24_000),
+    ("Heikki", 23_000),
+    ("Janne", 22_000),
+    ("Seppo", 22_000),
+    ("Ari", 21_000),
+    ("Sami", 20_000),
+    ("Petri", 19_000),
+    ("Ville", 18_000),
+    ("Jussi", 17_000),
+    ("Marko", 17_000),
+    ("Tuomas", 16_000),
+    ("Pentti", 16_000),
+    ("Lauri", 15_000),
+    ("Jani", 15_000),
+    ("Teemu", 15_000),
+    ("Eero", 14_000),
+    ("Juho", 14_000),
+    ("Tommi", 13_000),
+    ("Ilkka", 12_000),
+    ("Veikko", 12_000),
+    ("Erkki", 12_000),
+    ("Jarmo", 11_000),
+    ("Kalle", 11_000),
+    ("Esa", 11_000),
+    ("Onni", 10_000),
+    ("Eino", 10_000),
+    ("Leo", 9_000),
+    ("Elias", 9_000),
+    ("Oliver", 8_000),
+    ("Aleksi", 8_000),
+    ("Niko", 8_000),
+    ("Jesse", 7_000),
+    ("Joonas", 7_000),
+    ("Olli", 7_000),
+    ("Väinö", 6_000),
+    ("Aatos", 5_000),
+    ("Eetu", 5_000),
+    ("Otto", 5_000),
+    ("Björn", 1_000),
+];
+
+/// Surnames and their counts.
+pub static SURNAMES: [(&str, u32); 50] = [
+    ("Korhonen", 22_000),
+    ("Virtanen", 22_000),
+    ("Mäkinen", 20_000),
+    ("Nieminen", 20_000),
+    ("Mäkelä", 19_000),
+    ("Hämäläinen", 19_000),
+    ("Laine", 18_000),
+    ("Heikkinen", 17_000),
+    ("Koskinen", 17_000),
+    ("Järvinen", 16_000),
+    ("Lehtonen", 16_000),
+    ("Lehtinen", 15_000),
+    ("Saarinen", 15_000),
+    ("Salminen", 14_000),
+    ("Heinonen", 14_000),
+    ("Niemi", 14_000),
+    ("Heikkilä", 13_000),
+    ("Kinnunen", 13_000),
+    ("Salonen", 13_000),
+    ("Turunen", 13_000),
+    ("Salo", 12_000),
+    ("Laitinen", 12_000),
+    ("Tuominen", 12_000),
+    ("Rantanen", 12_000),
+    ("Karjalainen", 11_000),
+    ("Jokinen", 11_000),
+    ("Mattila", 11_000),
+    ("Savolainen", 10_000),
+    ("Lahtinen", 10_000),
+    ("Ahonen", 10_000),
+    ("Leinonen", 10_000),
+    ("Hiltunen", 9_000),
+    ("Hirvonen", 9_000),
+    ("Kallio", 9_000),
+    ("Miettinen", 9_000),
+    ("Leppänen", 9_000),
+    ("Aaltonen", 8_000),
+    ("Pitkänen", 8_000),
+    ("Manninen", 8_000),
+    ("Väisänen", 8_000),
+    ("Kärkkäinen", 8_000),
+    ("Anttila", 8_000),
+    ("Räsänen", 8_000),
+    ("Seppälä", 7_000),
+    ("Koivisto", 7_000),
+    ("Lindroos", 4_000),
+    ("Nyström", 4_000),
+    ("Lindqvist", 4_000),
+    ("Johansson", 4_000),
+    ("Öhman", 2_000),
+];
@@ -0,0 +1,302 @@
+use rand::{Rng, ThreadRng};
+use std::collections::HashSet;
+use std::convert::TryFrom;
+use std::fmt;
+
+use super::names::{FEMALE_FIRST_NAMES, MALE_FIRST_NAMES, SURNAMES};
+use super::{Date, Gender, GenerateError, Separators, Ssn};
+
+/// Finnish population by five-year age group from 0-4 to 100-104, in thousands.
+static POPULATION_BY_AGE_GROUP: [u32; 21] = [
+    235, 285, 305, 300, 320, 350, 365, 360, 350, 320, 335, 355, 360, 350, 365, 280, 175, 100, 40,
+    8, 1,
+];
+
+/// Oldest age of generated people.
+const MAX_AGE: usize = 104;
+
+/// Number of attempts to find an unused personal identity code before generation ends.
+const MAX_ATTEMPTS: usize = 1000;
+
+/// Distribution of the ages of generated people.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Default)]
+pub enum AgeDistribution {
+    /// Every age from 0 to 99 is equally likely.
+    #[default]
+    Uniform,
+    /// Ages follow the age structure of the population of Finland.
+    Population,
+}
+
+impl AgeDistribution {
+    /// Every age distribution.
+    pub fn all() -> &'static [AgeDistribution] {
+        &[AgeDistribution::Uniform, AgeDistribution::Population]
+    }
+
+    /// Name of the age distribution in kebab case.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            AgeDistribution::Uniform => "uniform",
+            AgeDistribution::Population => "population",
+        }
+    }
+
+    /// Oldest age in full years.
+    fn max_age(&self) -> usize {
+        match *self {
+            AgeDistribution::Uniform => 99,
+            AgeDistribution::Population => MAX_AGE,
+        }
+    }
+
+    /// Random age in full years.
+    fn age<R: Rng>(&self, rng: &mut R) -> usize {
+        match *self {
+            AgeDistribution::Uniform => rng.gen_range(0, 100),
+            AgeDistribution::Population => {
+                let group = weighted(rng, &POPULATION_BY_AGE_GROUP, |&count| count);
+                (group * 5 + rng.gen_range(0, 5)).min(MAX_AGE)
+            }
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a str> for AgeDistribution {
+    type Error = ();
+
+    /// Parse age distribution from its name.
+    fn try_from(name: &'a str) -> Result<Self, Self::Error> {
+        AgeDistribution::all()
+            .iter()
+            .find(|distribution| distribution.name() == name)
+            .copied()
+            .ok_or(())
+    }
+}
+
+impl fmt::Display for AgeDistribution {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Synthetic person with a personal identity code and a name that matches the gender of the code.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Person {
+    pub ssn: Ssn,
+    pub first_name: &'static str,
+    pub last_name: &'static str,
+}
+
+/// Iterator over synthetic people with distinct personal identity codes.
+///
+/// Names are picked by their frequency among Finnish people, and ages on the reference date follow an age
+/// distribution. Codes use the legacy century separators and identifiers in range 002-899.
+///
+/// # Example
+///
+/// ```
+/// use hetu::{AgeDistribution, Date, People};
+///
+/// let on = Date::new(2026, 1, 1).unwrap();
+/// for person in People::new(AgeDistribution::Population, on).unwrap().take(3) {
+///     assert!(person.ssn.age_on(&on).unwrap() <= 104);
+/// }
+/// ```
+#[derive(Debug)]
+pub struct People<R: Rng = ThreadRng> {
+    distribution: AgeDistribution,
+    on: Date,
+    rng: R,
+    /// Birth dates and identifiers of returned people.
+    used: HashSet<(Date, usize)>,
+}
+
+impl People {
+    /// Iterator over people with ages on a reference date.
+    ///
+    /// Returns an error if people of some age on the reference date would be born outside years 1800-2099, which
+    /// personal identity codes can't express.
+    pub fn new(distribution: AgeDistribution, on: Date) -> Result<People, GenerateError> {
+        People::with_rng(distribution, on, rand::thread_rng())
+    }
+}
+
+impl<R: Rng> People<R> {
+    /// Iterator over people with ages on a reference date, generated by a random number generator.
+    ///
+    /// A seeded random number generator returns the same people in the same order on every run.
+    ///
+    /// Returns an error if people of some age on the reference date would be born outside years 1800-2099.
+    pub fn with_rng(
+        distribution: AgeDistribution,
+        on: Date,
+        rng: R,
+    ) -> Result<People<R>, GenerateError> {
+        let earliest = on.years_before(distribution.max_age() + 1).add_days(1);
+        if earliest.year < 1800 || on.year > 2099 {
+            return Err(GenerateError);
+        }
+        Ok(People {
+            distribution,
+            on,
+            rng,
+            used: HashSet::new(),
+        })
+    }
+
+    fn person(&mut self) -> Person {
+        let age = self.distribution.age(&mut self.rng);
+        // Birth dates of people of the age on the reference date are a year long range that ends on the date
+        let latest = self.on.years_before(age);
+        let earliest = self.on.years_before(age + 1).add_days(1);
+        let born = loop {
+            let born = latest.add_days(-self.rng.gen_range(0, 366));
+            if born >= earliest {
+                break born;
+            }
+        };
+        let gender = if self.rng.gen() {
+            Gender::Female
+        } else {
+            Gender::Male
+        };
+        let identifier = match gender {
+            Gender::Female => self.rng.gen_range(1, 450) * 2,
+            Gender::Male => self.rng.gen_range(1, 450) * 2 + 1,
+        };
+        let separator = Separators::century(born.year / 100 * 100)
+            .intersection(Separators::legacy())
+            .single()
+            .expect("birth dates are limited to years 1800-2099");
+        let first_names: &[(&str, u32)] = match gender {
+            Gender::Female => &FEMALE_FIRST_NAMES,
+            Gender::Male => &MALE_FIRST_NAMES,
+        };
+        Person {
+            ssn: Ssn {
+                day: born.day,
+                month: born.month,
+                year: born.year,
+                gender,
+                separator,
+                identifier,
+            },
+            first_name: first_names[weighted(&mut self.rng, first_names, |name| name.1)].0,
+            last_name: SURNAMES[weighted(&mut self.rng, &SURNAMES, |name| name.1)].0,
+        }
+    }
+}
+
+impl<R: Rng> Iterator for People<R> {
+    type Item = Person;
+
+    /// Next person, `None` if no unused personal identity code was found.
+    fn next(&mut self) -> Option<Self::Item> {
+        for _ in 0..MAX_ATTEMPTS {
+            let person = self.person();
+            if self
+                .used
+                .insert((person.ssn.birth_date(), person.ssn.identifier))
+            {
+                return Some(person);
+            }
+        }
+        None
+    }
+}
+
+/// Index of a random item, picked with probability proportional to its weight.
+fn weighted<R: Rng, T, F: Fn(&T) -> u32>(rng: &mut R, items: &[T], weight: F) -> usize {
+    let total: u32 = items.iter().map(&weight).sum();
+    let mut n = rng.gen_range(0, total);
+    for (i, item) in items.iter().enumerate() {
+        if n < weight(item) {
+            return i;
+        }
+        n -= weight(item);
+    }
+    items.len() - 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{SeedableRng, StdRng};
+
+    #[test]
+    fn test_people() {
+        let on = Date::new(2026, 1, 1).unwrap();
+        let people: Vec<Person> = People::new(AgeDistribution::Uniform, on)
+            .unwrap()
+            .take(1000)
+            .collect();
+        assert_eq!(people.len(), 1000);
+        let codes: HashSet<String> = people.iter().map(|p| p.ssn.to_string()).collect();
+        assert_eq!(codes.len(), 1000);
+        for person in &people {
+            let ssn = Ssn::try_from(person.ssn.to_string().as_str()).unwrap();
+            assert_eq!(ssn, person.ssn);
+            assert!(ssn.age_on(&on).unwrap() < 100);
+            assert!(!ssn.is_temporary());
+            let names: &[(&str, u32)] = match ssn.gender {
+                Gender::Female => &FEMALE_FIRST_NAMES,
+                Gender::Male => &MALE_FIRST_NAMES,
+            };
+            assert!(names.iter().any(|name| name.0 == person.first_name));
+        }
+    }
+
+    #[test]
+    fn test_people_population() {
+        let on = Date::new(2026, 1, 1).unwrap();
+        let ages: Vec<usize> = People::new(AgeDistribution::Population, on)
+            .unwrap()
+            .take(2000)
+            .map(|p| p.ssn.age_on(&on).unwrap())
+            .collect();
+        assert!(ages.iter().all(|&age| age <= MAX_AGE));
+        // A fifth of uniform ages are 80 or more, but only about 5% of the population of Finland
+        let old = ages.iter().filter(|&&age| age >= 80).count();
+        assert!(old < 200, "{} people over 80", old);
+    }
+
+    #[test]
+    fn test_people_seed() {
+        let on = Date::new(2026, 1, 1).unwrap();
+        let people = |seed| -> Vec<Person> {
+            People::with_rng(AgeDistribution::Uniform, on, StdRng::from_seed(&[seed][..]))
+                .unwrap()
+                .take(10)
+                .collect()
+        };
+        assert_eq!(people(1), people(1));
+        assert_ne!(people(1), people(2));
+    }
+
+    #[test]
+    fn test_people_century() {
+        let date = |year, month, day| Date::new(year, month, day).unwrap();
+        assert!(People::new(AgeDistribution::Uniform, date(1820, 1, 1)).is_err());
+        assert!(People::new(AgeDistribution::Uniform, date(2100, 1, 1)).is_err());
+        assert!(People::new(AgeDistribution::Population, date(1899, 12, 31)).is_err());
+        for &on in &[date(1899, 12, 31), date(1950, 6, 15), date(2099, 12, 31)] {
+            for person in People::new(AgeDistribution::Uniform, on).unwrap().take(200) {
+                let ssn = Ssn::try_from(person.ssn.to_string().as_str()).unwrap();
+                assert_eq!(ssn.birth_date(), person.ssn.birth_date(), "{}", ssn);
+            }
+        }
+    }
+
+    #[test]
+    fn test_age_distribution_name() {
+        for distribution in AgeDistribution::all() {
+            assert_eq!(
+                AgeDistribution::try_from(distribution.name()),
+                Ok(*distribution)
+            );
+        }
+        assert_eq!(AgeDistribution::try_from("pyramid"), Err(()));
+    }
+}
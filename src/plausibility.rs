@@ -0,0 +1,368 @@
+use std::convert::TryFrom;
+use std::fmt;
+
+use super::{Date, Language, Profile, Ssn};
+
+/// Oldest plausible age of a living person.
+const MAX_PLAUSIBLE_AGE: usize = 120;
+
+/// Kind of suspicious but valid personal identity code.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum WarningKind {
+    /// Birth date is after the reference date.
+    FutureBirthDate,
+    /// Age on the reference date is over 120.
+    OldAge,
+    /// `+` separator of people born in the 1800s.
+    PlusSeparator,
+    /// Separator introduced in 2023 used before 2023-01-01.
+    EarlySeparator,
+    /// Temporary identifier in range 900-999.
+    Temporary,
+    /// Separator outside the legacy compatibility profile, which breaks systems built before 2023.
+    LegacyIncompatible,
+}
+
+impl WarningKind {
+    /// Every kind of warning.
+    pub fn all() -> &'static [WarningKind] {
+        &[
+            WarningKind::FutureBirthDate,
+            WarningKind::OldAge,
+            WarningKind::PlusSeparator,
+            WarningKind::EarlySeparator,
+            WarningKind::Temporary,
+            WarningKind::LegacyIncompatible,
+        ]
+    }
+
+    /// Name of the kind in kebab case.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            WarningKind::FutureBirthDate => "future-birth-date",
+            WarningKind::OldAge => "old-age",
+            WarningKind::PlusSeparator => "plus-separator",
+            WarningKind::EarlySeparator => "early-separator",
+            WarningKind::Temporary => "temporary",
+            WarningKind::LegacyIncompatible => "legacy-incompatible",
+        }
+    }
+
+    /// Short description of the kind.
+    pub fn description(&self) -> &'static str {
+        match *self {
+            WarningKind::FutureBirthDate => "Birth date in the future",
+            WarningKind::OldAge => "Age over 120",
+            WarningKind::PlusSeparator => "Born in the 1800s",
+            WarningKind::EarlySeparator => "Separator used before its introduction",
+            WarningKind::Temporary => "Temporary identifier",
+            WarningKind::LegacyIncompatible => "Separator not understood by legacy systems",
+        }
+    }
+
+    /// Message of a warning of the kind in English, with `{}` where its arguments are placed.
+    pub(crate) fn message(&self) -> &'static str {
+        match *self {
+            WarningKind::FutureBirthDate => "Birth date {} is after {}",
+            WarningKind::OldAge => "Age {} on {} is over {}",
+            WarningKind::PlusSeparator => "Separator + is for birth dates in the 1800s, born {}",
+            WarningKind::EarlySeparator => "Separator {} was introduced in 2023, after {}",
+            WarningKind::Temporary => "Identifier {} is temporary",
+            WarningKind::LegacyIncompatible => {
+                "Separator {} is not understood by systems built before 2023"
+            }
+        }
+    }
+
+    /// Severity of the kind unless configured otherwise.
+    pub fn default_severity(&self) -> Severity {
+        match *self {
+            WarningKind::Temporary => Severity::Info,
+            WarningKind::LegacyIncompatible => Severity::Off,
+            _ => Severity::Warning,
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a str> for WarningKind {
+    type Error = ();
+
+    /// Parse kind from its name.
+    fn try_from(name: &'a str) -> Result<Self, Self::Error> {
+        WarningKind::all()
+            .iter()
+            .find(|kind| kind.name() == name)
+            .copied()
+            .ok_or(())
+    }
+}
+
+impl fmt::Display for WarningKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Severity of a warning, from ignored to as bad as an invalid code.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    Off,
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    /// Every severity, from lowest to highest.
+    pub fn all() -> &'static [Severity] {
+        &[
+            Severity::Off,
+            Severity::Info,
+            Severity::Warning,
+            Severity::Error,
+        ]
+    }
+
+    /// Name of the severity in lower case.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Severity::Off => "off",
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a str> for Severity {
+    type Error = ();
+
+    /// Parse severity from its name.
+    fn try_from(name: &'a str) -> Result<Severity, ()> {
+        Severity::all()
+            .iter()
+            .find(|severity| severity.name() == name)
+            .copied()
+            .ok_or(())
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Suspicious property of a valid personal identity code.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Warning {
+    pub kind: WarningKind,
+    pub severity: Severity,
+    /// Values placed in the message of the kind, e.g. the birth date.
+    pub arguments: Vec<String>,
+}
+
+impl Warning {
+    /// Message of the warning in a language, `to_string()` in English.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hetu::{Date, Language, Plausibility, Ssn};
+    /// use std::convert::TryFrom;
+    ///
+    /// let plausibility = Plausibility::new(Date::new(2026, 1, 1).unwrap());
+    /// let warnings = plausibility.check(&Ssn::try_from("010199-9001").unwrap());
+    /// assert_eq!(warnings[0].to_string(), "Identifier 900 is temporary");
+    /// assert_eq!(warnings[0].to_string_in(Language::Finnish), "Yksilönumero 900 on väliaikainen");
+    /// ```
+    pub fn to_string_in(&self, language: Language) -> String {
+        let arguments: Vec<&dyn fmt::Display> = self
+            .arguments
+            .iter()
+            .map(|argument| argument as &dyn fmt::Display)
+            .collect();
+        language.format(self.kind.message(), &arguments)
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_string_in(Language::English))
+    }
+}
+
+/// Checks of valid personal identity codes for implausible or suspicious properties.
+///
+/// Codes are checked against a reference date, e.g. when the record was created. Every kind of warning has a severity,
+/// and warnings of kinds with severity `Off` are not reported.
+///
+/// # Example
+///
+/// ```
+/// use hetu::{Date, Plausibility, Severity, Ssn, WarningKind};
+/// use std::convert::TryFrom;
+///
+/// let plausibility = Plausibility::new(Date::new(2022, 6, 1).unwrap())
+///     .with_severity(WarningKind::EarlySeparator, Severity::Error)
+///     .with_severity(WarningKind::Temporary, Severity::Off);
+/// let warnings = plausibility.check(&Ssn::try_from("010594Y9032").unwrap());
+/// assert_eq!(warnings.len(), 1);
+/// assert_eq!(warnings[0].kind, WarningKind::EarlySeparator);
+/// assert_eq!(warnings[0].severity, Severity::Error);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Plausibility {
+    on: Date,
+    /// Severities in the order of `WarningKind::all()`.
+    severities: [Severity; 6],
+}
+
+impl Plausibility {
+    /// Checks against a reference date with the default severity of every kind.
+    pub fn new(on: Date) -> Plausibility {
+        let mut severities = [Severity::Off; 6];
+        for (severity, kind) in severities.iter_mut().zip(WarningKind::all()) {
+            *severity = kind.default_severity();
+        }
+        Plausibility { on, severities }
+    }
+
+    /// Checks with the severity of a kind of warning changed.
+    pub fn with_severity(mut self, kind: WarningKind, severity: Severity) -> Plausibility {
+        self.severities[kind as usize] = severity;
+        self
+    }
+
+    /// Severity of a kind of warning.
+    pub fn severity(&self, kind: WarningKind) -> Severity {
+        self.severities[kind as usize]
+    }
+
+    /// Warnings of a code, in the order of `WarningKind::all()`.
+    pub fn check(&self, ssn: &Ssn) -> Vec<Warning> {
+        let born = ssn.birth_date();
+        let modern = Date {
+            year: 2023,
+            month: 1,
+            day: 1,
+        };
+        let mut warnings = Vec::new();
+        let mut warn = |kind: WarningKind, arguments: &[&dyn fmt::Display]| {
+            let severity = self.severity(kind);
+            if severity != Severity::Off {
+                warnings.push(Warning {
+                    kind,
+                    severity,
+                    arguments: arguments.iter().map(|arg| arg.to_string()).collect(),
+                });
+            }
+        };
+        match ssn.age_on(&self.on) {
+            None => warn(WarningKind::FutureBirthDate, &[&born, &self.on]),
+            Some(age) if age > MAX_PLAUSIBLE_AGE => {
+                warn(WarningKind::OldAge, &[&age, &self.on, &MAX_PLAUSIBLE_AGE])
+            }
+            Some(_) => {}
+        }
+        if ssn.separator == '+' {
+            warn(WarningKind::PlusSeparator, &[&born]);
+        }
+        if self.on < modern && !Profile::Legacy.accepts(ssn) {
+            warn(WarningKind::EarlySeparator, &[&ssn.separator, &self.on]);
+        }
+        if ssn.is_temporary() {
+            warn(WarningKind::Temporary, &[&format!("{:03}", ssn.identifier)]);
+        }
+        if !Profile::Legacy.accepts(ssn) {
+            warn(WarningKind::LegacyIncompatible, &[&ssn.separator]);
+        }
+        warnings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(plausibility: &Plausibility, code: &str) -> Vec<WarningKind> {
+        plausibility
+            .check(&Ssn::try_from(code).unwrap())
+            .iter()
+            .map(|warning| warning.kind)
+            .collect()
+    }
+
+    #[test]
+    fn test_check() {
+        let plausibility = Plausibility::new(Date::new(2026, 1, 1).unwrap());
+        assert!(kinds(&plausibility, "291269-2763").is_empty());
+        assert!(kinds(&plausibility, "010594Y123W").is_empty());
+        assert_eq!(
+            kinds(&plausibility, "010130A1235"),
+            vec![WarningKind::FutureBirthDate]
+        );
+        assert_eq!(
+            kinds(&plausibility, "010101+123N"),
+            vec![WarningKind::OldAge, WarningKind::PlusSeparator]
+        );
+        assert_eq!(
+            kinds(&plausibility, "010199-9001"),
+            vec![WarningKind::Temporary]
+        );
+        let before = Plausibility::new(Date::new(2022, 12, 31).unwrap());
+        assert_eq!(
+            kinds(&before, "010594Y123W"),
+            vec![WarningKind::EarlySeparator]
+        );
+        assert!(kinds(&before, "010594-123W").is_empty());
+        let legacy = Plausibility::new(Date::new(2026, 1, 1).unwrap())
+            .with_severity(WarningKind::LegacyIncompatible, Severity::Warning);
+        assert_eq!(
+            kinds(&legacy, "010594Y123W"),
+            vec![WarningKind::LegacyIncompatible]
+        );
+        assert!(kinds(&legacy, "010594-123W").is_empty());
+    }
+
+    #[test]
+    fn test_severity() {
+        let on = Date::new(2026, 1, 1).unwrap();
+        let plausibility = Plausibility::new(on)
+            .with_severity(WarningKind::OldAge, Severity::Off)
+            .with_severity(WarningKind::PlusSeparator, Severity::Error);
+        let warnings = plausibility.check(&Ssn::try_from("010101+123N").unwrap());
+        assert_eq!(
+            warnings,
+            vec![Warning {
+                kind: WarningKind::PlusSeparator,
+                severity: Severity::Error,
+                arguments: vec!["1801-01-01".to_string()],
+            }]
+        );
+        assert_eq!(
+            warnings[0].to_string(),
+            "Separator + is for birth dates in the 1800s, born 1801-01-01"
+        );
+        assert_eq!(
+            warnings[0].to_string_in(Language::Swedish),
+            "Skiljetecknet + är för födda på 1800-talet, född 1801-01-01"
+        );
+        assert_eq!(
+            Plausibility::new(on).severity(WarningKind::Temporary),
+            Severity::Info
+        );
+        assert!(Severity::Info < Severity::Warning);
+    }
+
+    #[test]
+    fn test_names() {
+        for kind in WarningKind::all() {
+            assert_eq!(WarningKind::try_from(kind.name()), Ok(*kind));
+        }
+        for severity in Severity::all() {
+            assert_eq!(Severity::try_from(severity.name()), Ok(*severity));
+        }
+        assert_eq!(WarningKind::try_from("nonsense"), Err(()));
+    }
+}
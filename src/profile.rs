@@ -0,0 +1,180 @@
+use std::convert::TryFrom;
+use std::fmt;
+
+use super::{Constraints, ParseError, Separators, Ssn};
+
+/// Compatibility profile of the separators accepted when parsing and emitted when generating.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Default)]
+pub enum Profile {
+    /// Every separator, including the ones introduced in 2023.
+    #[default]
+    Modern,
+    /// Only the `+`, `-` and `A` separators understood by systems built before 2023.
+    Legacy,
+}
+
+impl Profile {
+    /// Every profile.
+    pub fn all() -> &'static [Profile] {
+        &[Profile::Modern, Profile::Legacy]
+    }
+
+    /// Name of the profile in kebab case.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Profile::Modern => "modern",
+            Profile::Legacy => "legacy",
+        }
+    }
+
+    /// Separators of the profile.
+    pub fn separators(&self) -> Separators {
+        match *self {
+            Profile::Modern => Separators::any(),
+            Profile::Legacy => Separators::legacy(),
+        }
+    }
+
+    /// Test whether a personal identity code can be used by systems of the profile.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hetu::{Profile, Ssn};
+    /// use std::convert::TryFrom;
+    ///
+    /// let ssn = Ssn::try_from("010594Y9032").unwrap();
+    /// assert!(Profile::Modern.accepts(&ssn));
+    /// assert!(!Profile::Legacy.accepts(&ssn));
+    /// ```
+    pub fn accepts(&self, ssn: &Ssn) -> bool {
+        self.separators().contains(ssn.separator)
+    }
+}
+
+impl<'a> TryFrom<&'a str> for Profile {
+    type Error = ();
+
+    /// Parse profile from its name.
+    fn try_from(name: &'a str) -> Result<Self, Self::Error> {
+        Profile::all()
+            .iter()
+            .find(|profile| profile.name() == name)
+            .copied()
+            .ok_or(())
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl Ssn {
+    /// Parse personal identity code, rejecting separators outside the profile.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hetu::{ParseError, Profile, Ssn};
+    ///
+    /// assert!(Ssn::parse_with_profile("010594-9032", Profile::Legacy).is_ok());
+    /// assert_eq!(
+    ///     Ssn::parse_with_profile("010594Y9032", Profile::Legacy),
+    ///     Err(ParseError::Syntax("Separator introduced in 2023", 6, 7))
+    /// );
+    /// ```
+    pub fn parse_with_profile(ssn: &str, profile: Profile) -> Result<Ssn, ParseError<'_>> {
+        let parsed = Ssn::parse(ssn)?;
+        if profile.accepts(&parsed) {
+            Ok(parsed)
+        } else {
+            Err(ParseError::Syntax("Separator introduced in 2023", 6, 7))
+        }
+    }
+}
+
+impl Constraints {
+    /// Constraints that only match codes with a separator of the profile.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hetu::{Constraints, Profile, Ssn, SsnPattern};
+    /// use std::convert::TryFrom;
+    ///
+    /// let pattern = SsnPattern::try_from("0101[89]??????").unwrap();
+    /// let legacy = Constraints::from(pattern).with_profile(Profile::Legacy);
+    /// let code = Ssn::generate_by_constraints(&legacy).unwrap();
+    /// assert!(Ssn::parse_with_profile(&code, Profile::Legacy).is_ok());
+    /// ```
+    pub fn with_profile(&self, profile: Profile) -> Constraints {
+        let mut constraints = *self;
+        constraints.pattern.sep = constraints.pattern.sep.intersection(profile.separators());
+        constraints
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use SsnPattern;
+
+    #[test]
+    fn test_generate_with_profile() {
+        for _ in 0..200 {
+            let code = Ssn::generate_with_profile(Profile::Legacy);
+            let ssn = Ssn::parse_with_profile(&code, Profile::Legacy).unwrap();
+            assert!(!ssn.is_temporary());
+            assert!((1890..2016).contains(&ssn.year));
+        }
+        for _ in 0..200 {
+            let code = Ssn::generate_with_profile(Profile::Modern);
+            assert!((1890..2016).contains(&Ssn::try_from(code.as_str()).unwrap().year));
+        }
+    }
+
+    #[test]
+    fn test_generate_with_profile_century() {
+        // About an eighth of generated codes are born in the 2000s
+        let born_2000s = |profile: Profile| {
+            (0..2000)
+                .map(|_| Ssn::try_from(Ssn::generate_with_profile(profile).as_str()).unwrap())
+                .filter(|ssn| ssn.year >= 2000)
+                .collect::<Vec<_>>()
+        };
+        let modern = born_2000s(Profile::Modern);
+        assert!(!modern.is_empty());
+        assert!(modern.iter().all(|ssn| "ABCDEF".contains(ssn.separator)));
+        let legacy = born_2000s(Profile::Legacy);
+        assert!(!legacy.is_empty());
+        assert!(legacy.iter().all(|ssn| ssn.separator == 'A'));
+    }
+
+    #[test]
+    fn test_constraints_with_profile() {
+        let pattern = SsnPattern::try_from("010594?????").unwrap();
+        let constraints = Constraints::from(pattern);
+        let legacy = Ssn::count_by_constraints(&constraints.with_profile(Profile::Legacy)).unwrap();
+        assert!(legacy > 0);
+        assert!(legacy < Ssn::count_by_constraints(&constraints).unwrap());
+        assert_eq!(
+            Ssn::count_by_constraints(&constraints.with_profile(Profile::Modern)),
+            Ssn::count_by_constraints(&constraints)
+        );
+        let modern = SsnPattern::try_from("010594Y????").unwrap();
+        assert!(Ssn::generate_by_constraints(
+            &Constraints::from(modern).with_profile(Profile::Legacy)
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_profile_name() {
+        for profile in Profile::all() {
+            assert_eq!(Profile::try_from(profile.name()), Ok(*profile));
+        }
+        assert_eq!(Profile::try_from("2023"), Err(()));
+    }
+}
@@ -0,0 +1,525 @@
+use std::convert::TryFrom;
+use std::error;
+use std::fmt;
+use std::iter;
+
+use super::{Date, Digits, ErrorIndexRange, Separators, Ssn, SsnPattern};
+
+/// Constraints for generated personal identity codes.
+///
+/// Constraints combine a pattern with a range of birth dates, and can be parsed from a query of whitespace
+/// separated terms:
+///
+/// * `born:1980-01-01..1989-12-31`, `born:1980..1989`, `born:1985-06`, `born:..1999` or `born:2000..`
+/// * `age:>=18`, `age:<65`, `age:18..30` or `age:=40`, optionally on a reference date `age:>=18@2026-10-01`
+/// * `adult` or `minor` for `age:>=18` and `age:<18`
+/// * `gender:female`, `gender:male`, or just `female` or `male`
+/// * `temporary:yes` or `temporary:no`
+/// * `separator:legacy` for `+`, `-` and `A`, `separator:modern` for the separator letters introduced in 2023, or
+///   a separator pattern token such as `separator:{19}` or `separator:[-A]`
+/// * `pattern:??????-???A`
+///
+/// # Example
+///
+/// ```
+/// use hetu::{Constraints, Ssn};
+/// use std::convert::TryFrom;
+///
+/// let constraints = Constraints::try_from("born:1980..1989 gender:female separator:legacy").unwrap();
+/// let ssn = Ssn::generate_by_constraints(&constraints).unwrap();
+/// assert!(constraints.matches_str(&ssn));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub struct Constraints {
+    pub pattern: SsnPattern,
+    /// Earliest birth date.
+    pub earliest: Option<Date>,
+    /// Latest birth date.
+    pub latest: Option<Date>,
+}
+
+impl From<SsnPattern> for Constraints {
+    fn from(pattern: SsnPattern) -> Constraints {
+        Constraints {
+            pattern,
+            earliest: None,
+            latest: None,
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a str> for Constraints {
+    type Error = QueryError;
+
+    /// Parse constraints from a query.
+    fn try_from(query: &'a str) -> Result<Self, Self::Error> {
+        Constraints::parse(query)
+    }
+}
+
+impl Constraints {
+    /// Test whether personal identity code matches the pattern and the birth date range.
+    pub fn matches(&self, ssn: &Ssn) -> bool {
+        let born = ssn.birth_date();
+        self.pattern.matches(ssn)
+            && self.earliest.is_none_or(|earliest| earliest <= born)
+            && self.latest.is_none_or(|latest| born <= latest)
+    }
+
+    /// Test whether string is a valid personal identity code that matches the constraints.
+    pub fn matches_str(&self, ssn: &str) -> bool {
+        Ssn::try_from(ssn).is_ok_and(|ssn| self.matches(&ssn))
+    }
+
+    /// Constraints that match codes matched by both constraints, `None` if no code can match both.
+    pub fn intersection(&self, other: &Constraints) -> Option<Constraints> {
+        let mut constraints = Constraints {
+            pattern: self.pattern.intersection(&other.pattern)?,
+            ..*self
+        };
+        constraints.limit_born(other.earliest, other.latest)?;
+        Some(constraints)
+    }
+
+    /// Inclusive birth date range, `None` if birth date is not limited.
+    pub(crate) fn born(&self) -> Option<(Date, Date)> {
+        if self.earliest.is_none() && self.latest.is_none() {
+            return None;
+        }
+        Some((
+            self.earliest.unwrap_or(Date {
+                year: 0,
+                month: 1,
+                day: 1,
+            }),
+            self.latest.unwrap_or(Date {
+                year: 9999,
+                month: 12,
+                day: 31,
+            }),
+        ))
+    }
+
+    fn parse(query: &str) -> Result<Constraints, QueryError> {
+        let mut constraints = Constraints::default();
+        for (start, term) in terms(query) {
+            let end = start + term.len();
+            let (key, value) = match term.find(':') {
+                Some(i) => (&term[..i], Some((start + i + 1, &term[i + 1..]))),
+                None => (term, None),
+            };
+            let value_of = |desc: &'static str| match value {
+                Some((start, value)) if !value.is_empty() => Ok((start, value)),
+                _ => Err(QueryError(desc, start, end)),
+            };
+            let conflict = QueryError("Conflicting constraint", start, end);
+            match key {
+                "born" => {
+                    let (start, value) = value_of("Missing birth date")?;
+                    let (earliest, latest) = parse_range(value, start, parse_born)?;
+                    constraints.limit_born(earliest, latest).ok_or(conflict)?;
+                }
+                "age" => {
+                    let (start, value) = value_of("Missing age")?;
+                    let (earliest, latest) = parse_age(value, start)?;
+                    constraints.limit_born(earliest, latest).ok_or(conflict)?;
+                }
+                "adult" | "minor" if value.is_none() => {
+                    let today = Date::today();
+                    let (earliest, latest) = if key == "adult" {
+                        (None, Some(today.years_before(18)))
+                    } else {
+                        (Some(today.years_before(18).add_days(1)), Some(today))
+                    };
+                    constraints.limit_born(earliest, latest).ok_or(conflict)?;
+                }
+                "gender" | "female" | "male" => {
+                    let (start, value) = match key {
+                        "gender" => value_of("Missing gender")?,
+                        _ if value.is_none() => (start, key),
+                        _ => return Err(QueryError("Unknown constraint", start, end)),
+                    };
+                    let digits = match value {
+                        "female" | "f" => Digits::even(),
+                        "male" | "m" => Digits::odd(),
+                        _ => return Err(QueryError("Invalid gender", start, end)),
+                    };
+                    constraints.pattern.i3 =
+                        intersect(constraints.pattern.i3, digits).ok_or(conflict)?;
+                }
+                "temporary" => {
+                    let (start, value) = value_of("Missing temporary flag")?;
+                    let digits = match value {
+                        "yes" | "true" => Digits::digit(9),
+                        "no" | "false" => Digits::range(0, 8),
+                        _ => return Err(QueryError("Invalid temporary flag", start, end)),
+                    };
+                    constraints.pattern.i1 =
+                        intersect(constraints.pattern.i1, digits).ok_or(conflict)?;
+                }
+                "separator" => {
+                    let (start, value) = value_of("Missing separator")?;
+                    let separators = match value {
+                        "legacy" => Separators::legacy(),
+                        "modern" => Separators::modern(),
+                        _ => {
+                            let pattern = format!("??????{}????", value);
+                            SsnPattern::try_from(pattern.as_str())
+                                .map_err(|_| QueryError("Invalid separator", start, end))?
+                                .sep
+                        }
+                    };
+                    let separators = constraints.pattern.sep.intersection(separators);
+                    if separators.is_empty() {
+                        return Err(conflict);
+                    }
+                    constraints.pattern.sep = separators;
+                }
+                "pattern" => {
+                    let (start, value) = value_of("Missing pattern")?;
+                    let pattern = SsnPattern::try_from(value).map_err(|err| {
+                        QueryError("Invalid pattern", start + err.start(), start + err.end())
+                    })?;
+                    constraints.pattern =
+                        constraints.pattern.intersection(&pattern).ok_or(conflict)?;
+                }
+                _ => return Err(QueryError("Unknown constraint", start, start + key.len())),
+            }
+        }
+        Ok(constraints)
+    }
+
+    /// Limit birth date range, returns `None` if the range becomes empty.
+    fn limit_born(&mut self, earliest: Option<Date>, latest: Option<Date>) -> Option<()> {
+        self.earliest = self.earliest.max(earliest);
+        self.latest = match (self.latest, latest) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        match (self.earliest, self.latest) {
+            (Some(earliest), Some(latest)) if earliest > latest => None,
+            _ => Some(()),
+        }
+    }
+}
+
+fn intersect(a: Digits, b: Digits) -> Option<Digits> {
+    let digits = a.intersection(b);
+    if digits.is_empty() {
+        None
+    } else {
+        Some(digits)
+    }
+}
+
+/// Split query into whitespace separated terms with their byte offsets.
+fn terms(query: &str) -> impl Iterator<Item = (usize, &str)> {
+    // Every term after the first starts where the preceding whitespace character ends
+    let starts = query
+        .match_indices(char::is_whitespace)
+        .map(|(i, whitespace)| i + whitespace.len());
+    iter::once(0)
+        .chain(starts)
+        .zip(query.split(char::is_whitespace))
+        .filter(|(_, term)| !term.is_empty())
+}
+
+/// Parse an inclusive range `a..b`, `a..`, `..b` or a single value `a` with bound parsers.
+fn parse_range<F>(
+    value: &str,
+    start: usize,
+    parse: F,
+) -> Result<(Option<Date>, Option<Date>), QueryError>
+where
+    F: Fn(&str, usize) -> Result<(Date, Date), QueryError>,
+{
+    match value.find("..") {
+        Some(i) => {
+            let (first, last) = (&value[..i], &value[i + 2..]);
+            let earliest = if first.is_empty() {
+                None
+            } else {
+                Some(parse(first, start)?.0)
+            };
+            let latest = if last.is_empty() {
+                None
+            } else {
+                Some(parse(last, start + i + 2)?.1)
+            };
+            Ok((earliest, latest))
+        }
+        None => {
+            let (earliest, latest) = parse(value, start)?;
+            Ok((Some(earliest), Some(latest)))
+        }
+    }
+}
+
+/// Parse year, month or date into the first and last day of the period.
+fn parse_born(value: &str, start: usize) -> Result<(Date, Date), QueryError> {
+    let err = QueryError("Invalid birth date", start, start + value.len());
+    let number = |s: &str| {
+        s.parse::<usize>()
+            .ok()
+            .filter(|_| s.bytes().all(|b| b.is_ascii_digit()))
+    };
+    match value.len() {
+        4 => {
+            let year = number(value).ok_or(err)?;
+            Ok((
+                Date::new(year, 1, 1).ok_or(err)?,
+                Date::new(year, 12, 31).ok_or(err)?,
+            ))
+        }
+        7 if value.as_bytes()[4] == b'-' => {
+            let year = number(&value[..4]).ok_or(err)?;
+            let month = number(&value[5..]).ok_or(err)?;
+            let first = Date::new(year, month, 1).ok_or(err)?;
+            let last = Date::new(year, month, super::days_in_month(month, year)).ok_or(err)?;
+            Ok((first, last))
+        }
+        _ => {
+            let date = Date::try_from(value).map_err(|e| {
+                QueryError("Invalid birth date", start + e.start(), start + e.end())
+            })?;
+            Ok((date, date))
+        }
+    }
+}
+
+/// Parse age comparison or range with an optional reference date into a birth date range.
+fn parse_age(value: &str, start: usize) -> Result<(Option<Date>, Option<Date>), QueryError> {
+    let (age, on) = match value.find('@') {
+        Some(i) => {
+            let date = &value[i + 1..];
+            let on = Date::try_from(date).map_err(|e| {
+                QueryError(
+                    "Invalid reference date",
+                    start + i + 1 + e.start(),
+                    start + i + 1 + e.end(),
+                )
+            })?;
+            (&value[..i], on)
+        }
+        None => (value, Date::today()),
+    };
+    let end = start + age.len();
+    let number = |s: &str, start: usize| {
+        s.parse::<usize>()
+            .ok()
+            .filter(|_| s.bytes().all(|b| b.is_ascii_digit()))
+            .ok_or(QueryError("Invalid age", start, end))
+    };
+    // Born on or before this date to be at least `age` years old
+    let at_least = |age: usize| on.years_before(age);
+    // Born on or after this date to be at most `age` years old
+    let at_most = |age: usize, start: usize| match age.checked_add(1) {
+        Some(next) => Ok(on.years_before(next).add_days(1)),
+        None => Err(QueryError("Invalid age", start, end)),
+    };
+    let (earliest, latest) = if let Some(rest) = age.strip_prefix(">=") {
+        (None, Some(at_least(number(rest, start + 2)?)))
+    } else if let Some(rest) = age.strip_prefix("<=") {
+        (Some(at_most(number(rest, start + 2)?, start + 2)?), None)
+    } else if let Some(rest) = age.strip_prefix('>') {
+        let age = number(rest, start + 1)?.checked_add(1).ok_or(QueryError(
+            "Invalid age",
+            start + 1,
+            end,
+        ))?;
+        (None, Some(at_least(age)))
+    } else if let Some(rest) = age.strip_prefix('<') {
+        let age = number(rest, start + 1)?;
+        if age == 0 {
+            return Err(QueryError("Invalid age", start, end));
+        }
+        (Some(at_most(age - 1, start + 1)?), None)
+    } else if let Some(i) = age.find("..") {
+        let min = number(&age[..i], start)?;
+        let max = number(&age[i + 2..], start + i + 2)?;
+        (Some(at_most(max, start + i + 2)?), Some(at_least(min)))
+    } else {
+        let offset = if age.starts_with('=') { 1 } else { 0 };
+        let age = number(&age[offset..], start + offset)?;
+        (Some(at_most(age, start + offset)?), Some(at_least(age)))
+    };
+    // Nobody can have an age before they're born
+    Ok((earliest, Some(latest.map_or(on, |latest| latest.min(on)))))
+}
+
+/// Error in a constraint query, with the byte range of the invalid part of the query.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct QueryError(pub &'static str, pub usize, pub usize);
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid query: {}", self.0)
+    }
+}
+
+impl error::Error for QueryError {
+    fn description(&self) -> &str {
+        "Invalid query"
+    }
+
+    fn cause(&self) -> Option<&(dyn error::Error + 'static)> {
+        None
+    }
+}
+
+impl ErrorIndexRange for QueryError {
+    fn start(&self) -> usize {
+        self.1
+    }
+    fn end(&self) -> usize {
+        self.2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(date: &str) -> Option<Date> {
+        Some(Date::try_from(date).unwrap())
+    }
+
+    #[test]
+    fn test_parse_born() {
+        let constraints = Constraints::try_from("born:1980-01-01..1989-12-31").unwrap();
+        assert_eq!(constraints.earliest, date("1980-01-01"));
+        assert_eq!(constraints.latest, date("1989-12-31"));
+        assert_eq!(
+            Constraints::try_from("born:1980..1989").unwrap(),
+            constraints
+        );
+        let constraints = Constraints::try_from("born:2000-02").unwrap();
+        assert_eq!(constraints.earliest, date("2000-02-01"));
+        assert_eq!(constraints.latest, date("2000-02-29"));
+        let constraints = Constraints::try_from("born:..1999").unwrap();
+        assert_eq!(constraints.earliest, None);
+        assert_eq!(constraints.latest, date("1999-12-31"));
+    }
+
+    #[test]
+    fn test_intersection() {
+        let a = Constraints::try_from("born:1980..1989 female").unwrap();
+        let b = Constraints::try_from("born:1985..1999 pattern:????????1??").unwrap();
+        assert_eq!(
+            a.intersection(&b),
+            Some(Constraints::try_from("born:1985..1989 female pattern:????????1??").unwrap())
+        );
+        assert_eq!(
+            a.intersection(&Constraints::try_from("born:1990").unwrap()),
+            None
+        );
+        assert_eq!(
+            a.intersection(&Constraints::try_from("male").unwrap()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_age() {
+        let constraints = Constraints::try_from("age:>=18@2026-10-01").unwrap();
+        assert_eq!(constraints.earliest, None);
+        assert_eq!(constraints.latest, date("2008-10-01"));
+        let constraints = Constraints::try_from("age:<18@2026-10-01").unwrap();
+        assert_eq!(constraints.earliest, date("2008-10-02"));
+        assert_eq!(constraints.latest, date("2026-10-01"));
+        let constraints = Constraints::try_from("age:20..29@2026-10-01").unwrap();
+        assert_eq!(constraints.earliest, date("1996-10-02"));
+        assert_eq!(constraints.latest, date("2006-10-01"));
+        assert_eq!(
+            Constraints::try_from("age:=40@2026-10-01").unwrap(),
+            Constraints::try_from("age:40..40@2026-10-01").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_combined() {
+        let constraints = Constraints::try_from(
+            "born:1980-01-01..1989-12-31 gender:female temporary:no separator:legacy age:>=18@2026-10-01",
+        )
+        .unwrap();
+        assert_eq!(constraints.pattern.to_string(), "??????[+-A][0-8]?f?");
+        assert_eq!(constraints.earliest, date("1980-01-01"));
+        assert_eq!(constraints.latest, date("1989-12-31"));
+    }
+
+    #[test]
+    fn test_parse_keywords() {
+        assert_eq!(
+            Constraints::try_from("female").unwrap(),
+            Constraints::try_from("gender:f").unwrap()
+        );
+        assert!(Constraints::try_from("adult").unwrap().latest.is_some());
+    }
+
+    #[test]
+    fn test_parse_pattern() {
+        let constraints = Constraints::try_from("pattern:??????-???A male").unwrap();
+        assert_eq!(constraints.pattern.to_string(), "??????-??mA");
+    }
+
+    macro_rules! query_parse_failure {
+        ($($name:ident: $value:expr => $err:expr,)*) => {$(
+            #[test]
+            fn $name() {
+                assert_eq!(Constraints::try_from($value).unwrap_err(), $err);
+            }
+        )*}
+    }
+
+    query_parse_failure! {
+        query_parse_unknown: "born:1980 sex:f" => QueryError("Unknown constraint", 10, 13),
+        query_parse_missing_value: "gender:" => QueryError("Missing gender", 0, 7),
+        query_parse_invalid_gender: "gender:x" => QueryError("Invalid gender", 7, 8),
+        query_parse_invalid_born: "born:1980-13" => QueryError("Invalid birth date", 5, 12),
+        query_parse_invalid_born_date: "born:1980-02-30" => QueryError("Invalid birth date", 13, 15),
+        query_parse_invalid_age: "age:>=x" => QueryError("Invalid age", 6, 7),
+        query_parse_invalid_reference: "age:>=18@2026-1-1" => QueryError("Invalid reference date", 9, 17),
+        query_parse_invalid_pattern: "pattern:??13???????" => QueryError("Invalid pattern", 10, 11),
+        query_parse_conflicting_gender: "female male" => QueryError("Conflicting constraint", 7, 11),
+        query_parse_conflicting_born: "born:1990 born:1980" => QueryError("Conflicting constraint", 10, 19),
+        query_parse_conflicting_separator: "separator:legacy separator:modern" => QueryError("Conflicting constraint", 17, 33),
+        query_parse_multibyte_born: "born:1234é1" => QueryError("Invalid birth date", 5, 12),
+        query_parse_overflow_older: "age:>18446744073709551615" => QueryError("Invalid age", 5, 25),
+        query_parse_overflow_at_most: "age:<=18446744073709551615" => QueryError("Invalid age", 6, 26),
+        query_parse_overflow_equal: "age:=18446744073709551615" => QueryError("Invalid age", 5, 25),
+        query_parse_overflow_range: "age:0..18446744073709551615" => QueryError("Invalid age", 7, 27),
+        query_parse_unicode_whitespace: "female\u{a0}sex:x" => QueryError("Unknown constraint", 8, 11),
+        query_parse_ideographic_space: "female\u{3000}sex:x" => QueryError("Unknown constraint", 9, 12),
+    }
+
+    #[test]
+    fn test_generate() {
+        for query in &[
+            "born:1980..1989 gender:female separator:legacy",
+            "age:>=18@2026-10-01 temporary:yes",
+            "born:1840..1849",
+            "born:2000-02-29 male",
+            "age:18..20@2026-10-01 separator:modern",
+        ] {
+            let constraints = Constraints::try_from(*query).unwrap();
+            for _ in 0..20 {
+                let ssn = Ssn::generate_by_constraints(&constraints).unwrap();
+                assert!(constraints.matches_str(&ssn), "{} for {}", ssn, query);
+            }
+        }
+    }
+
+    #[test]
+    fn test_count() {
+        let constraints = Constraints::try_from("born:2000-02-29 pattern:??????A00??").unwrap();
+        assert_eq!(Ssn::count_by_constraints(&constraints), Ok(8));
+        assert_eq!(
+            Ssn::iter_by_constraints(&constraints)
+                .unwrap()
+                .take(8)
+                .collect::<std::collections::HashSet<_>>()
+                .len(),
+            8
+        );
+    }
+}
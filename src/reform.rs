@@ -0,0 +1,140 @@
+use std::convert::TryFrom;
+use std::fmt;
+
+use super::{Date, Gender, Ssn};
+
+/// Interpretation of the gender of personal identity codes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Default)]
+pub enum GenderRule {
+    /// Gender by identifier parity, even for female and odd for male.
+    #[default]
+    Parity,
+    /// No gender, as proposed by the reform of personal identity codes.
+    Neutral,
+}
+
+impl GenderRule {
+    /// Every rule.
+    pub fn all() -> &'static [GenderRule] {
+        &[GenderRule::Parity, GenderRule::Neutral]
+    }
+
+    /// Name of the rule in kebab case.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            GenderRule::Parity => "parity",
+            GenderRule::Neutral => "neutral",
+        }
+    }
+
+    /// Gender of a personal identity code by the rule, `None` if unknown.
+    pub fn gender(&self, ssn: &Ssn) -> Option<Gender> {
+        match *self {
+            GenderRule::Parity => Some(ssn.gender),
+            GenderRule::Neutral => None,
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a str> for GenderRule {
+    type Error = ();
+
+    /// Parse rule from its name.
+    fn try_from(name: &'a str) -> Result<Self, Self::Error> {
+        GenderRule::all()
+            .iter()
+            .find(|rule| rule.name() == name)
+            .copied()
+            .ok_or(())
+    }
+}
+
+impl fmt::Display for GenderRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Reform of personal identity codes after which they no longer encode gender.
+///
+/// The rule in force is selected by a date, e.g. the date the code was issued or the reference date of a record.
+///
+/// # Example
+///
+/// ```
+/// use hetu::{Date, Gender, GenderReform, GenderRule, Ssn};
+/// use std::convert::TryFrom;
+///
+/// let reform = GenderReform::new(Date::new(2027, 1, 1).unwrap());
+/// let ssn = Ssn::try_from("291269-2763").unwrap();
+/// let before = reform.rule_on(&Date::new(2026, 12, 31).unwrap());
+/// assert_eq!(ssn.gender_by(before), Some(Gender::Female));
+/// let after = reform.rule_on(&Date::new(2027, 1, 1).unwrap());
+/// assert_eq!(after, GenderRule::Neutral);
+/// assert_eq!(ssn.gender_by(after), None);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct GenderReform {
+    /// First date of the gender-neutral rule.
+    pub neutral_from: Date,
+}
+
+impl GenderReform {
+    /// Reform that takes effect on a date.
+    pub fn new(neutral_from: Date) -> GenderReform {
+        GenderReform { neutral_from }
+    }
+
+    /// Rule in force on a date.
+    pub fn rule_on(&self, date: &Date) -> GenderRule {
+        if *date < self.neutral_from {
+            GenderRule::Parity
+        } else {
+            GenderRule::Neutral
+        }
+    }
+}
+
+impl Ssn {
+    /// Gender of the personal identity code by a rule, `None` if the rule does not encode gender.
+    ///
+    /// The `gender` field is always derived from identifier parity.
+    pub fn gender_by(&self, rule: GenderRule) -> Option<Gender> {
+        rule.gender(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rule_on() {
+        let reform = GenderReform::new(Date::new(2027, 1, 1).unwrap());
+        assert_eq!(
+            reform.rule_on(&Date::new(1999, 1, 1).unwrap()),
+            GenderRule::Parity
+        );
+        assert_eq!(
+            reform.rule_on(&Date::new(2027, 1, 1).unwrap()),
+            GenderRule::Neutral
+        );
+        assert_eq!(
+            reform.rule_on(&Date::new(2030, 6, 1).unwrap()),
+            GenderRule::Neutral
+        );
+    }
+
+    #[test]
+    fn test_gender_by() {
+        let female = Ssn::try_from("291269-2763").unwrap();
+        let male = Ssn::try_from("010594Y123W").unwrap();
+        assert_eq!(female.gender_by(GenderRule::Parity), Some(Gender::Female));
+        assert_eq!(male.gender_by(GenderRule::default()), Some(Gender::Male));
+        assert_eq!(female.gender_by(GenderRule::Neutral), None);
+        assert_eq!(male.gender_by(GenderRule::Neutral), None);
+        for rule in GenderRule::all() {
+            assert_eq!(GenderRule::try_from(rule.name()), Ok(*rule));
+        }
+    }
+}
@@ -0,0 +1,271 @@
+//! Integration of parse and query errors with the `miette` and `codespan-reporting` diagnostic crates.
+//!
+//! Enable the `miette` feature to use the errors as `miette::Diagnostic`s, and the `codespan-reporting` feature to
+//! convert them into `codespan_reporting::diagnostic::Diagnostic`s. Both show the same error code, labels and help.
+
+use super::{ErrorIndexRange, ParseError, QueryError};
+
+/// Span of input with a message.
+struct Span {
+    start: usize,
+    end: usize,
+    message: String,
+    /// Primary spans mark the error, secondary spans the input it depends on.
+    primary: bool,
+}
+
+/// Error that can be reported with labelled spans of its input.
+trait Reportable: ErrorIndexRange {
+    /// Stable code of the kind of error, e.g. `hetu::checksum`.
+    fn code(&self) -> String;
+
+    /// Labelled spans of the input.
+    fn spans(&self) -> Vec<Span>;
+
+    /// How to fix the error.
+    fn help(&self) -> Option<String> {
+        None
+    }
+}
+
+impl Reportable for ParseError<'_> {
+    fn code(&self) -> String {
+        format!("hetu::{}", self.kind())
+    }
+
+    fn spans(&self) -> Vec<Span> {
+        let span = |start, end, message: &str, primary| Span {
+            start,
+            end,
+            message: message.to_string(),
+            primary,
+        };
+        match *self {
+            ParseError::Checksum(_, start, end, expected) => vec![
+                span(start, end, &format!("expected {}", expected), true),
+                span(0, 6, "birth date", false),
+                span(7, 10, "identifier", false),
+            ],
+            _ => vec![span(self.start(), self.end(), self.message(), true)],
+        }
+    }
+
+    fn help(&self) -> Option<String> {
+        match *self {
+            ParseError::Checksum(_, _, _, expected) => Some(format!(
+                "the checksum of the birth date and identifier is {}",
+                expected
+            )),
+            _ => None,
+        }
+    }
+}
+
+impl Reportable for QueryError {
+    fn code(&self) -> String {
+        "hetu::query".to_string()
+    }
+
+    fn spans(&self) -> Vec<Span> {
+        vec![Span {
+            start: self.1,
+            end: self.2,
+            message: self.0.to_string(),
+            primary: true,
+        }]
+    }
+}
+
+#[cfg(feature = "miette")]
+mod miette_impl {
+    use miette::{Diagnostic, LabeledSpan, MietteDiagnostic, SourceSpan};
+    use std::fmt::Display;
+
+    use super::{ParseError, QueryError, Reportable};
+
+    fn labels<R: Reportable>(err: &R) -> Box<dyn Iterator<Item = LabeledSpan>> {
+        Box::new(err.spans().into_iter().map(|span| {
+            let location = SourceSpan::from(span.start..span.end.max(span.start));
+            if span.primary {
+                LabeledSpan::new_primary_with_span(Some(span.message), location)
+            } else {
+                LabeledSpan::new_with_span(Some(span.message), location)
+            }
+        }))
+    }
+
+    /// Owned report of an error with the input it was found in.
+    fn report<R: Reportable + Display>(err: &R, input: &str) -> miette::Report {
+        let mut diagnostic = MietteDiagnostic::new(err.to_string())
+            .with_code(err.code())
+            .with_labels(labels(err));
+        if let Some(help) = err.help() {
+            diagnostic = diagnostic.with_help(help);
+        }
+        miette::Report::new(diagnostic).with_source_code(input.to_string())
+    }
+
+    impl Diagnostic for ParseError<'_> {
+        fn code<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+            Some(Box::new(Reportable::code(self)))
+        }
+
+        fn help<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+            Reportable::help(self).map(|help| Box::new(help) as Box<dyn Display>)
+        }
+
+        fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+            Some(labels(self))
+        }
+    }
+
+    impl Diagnostic for QueryError {
+        fn code<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+            Some(Box::new(Reportable::code(self)))
+        }
+
+        fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+            Some(labels(self))
+        }
+    }
+
+    impl ParseError<'_> {
+        /// Report of the error with the input it was found in as source code.
+        ///
+        /// The report owns its data, unlike the error that borrows the input.
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use hetu::Ssn;
+        /// use std::convert::TryFrom;
+        ///
+        /// let input = String::from("291269-2764");
+        /// let report = Ssn::try_from(input.as_str()).map_err(|err| err.to_report(&input));
+        /// assert_eq!(report.unwrap_err().to_string(), "Invalid checksum: expected 3");
+        /// ```
+        pub fn to_report(&self, input: &str) -> miette::Report {
+            report(self, input)
+        }
+    }
+
+    impl QueryError {
+        /// Report of the error with the query it was found in as source code.
+        pub fn to_report(&self, query: &str) -> miette::Report {
+            report(self, query)
+        }
+    }
+}
+
+#[cfg(feature = "codespan-reporting")]
+mod codespan_impl {
+    use codespan_reporting::diagnostic::{Diagnostic, Label};
+    use std::fmt::Display;
+
+    use super::{ParseError, QueryError, Reportable};
+
+    fn diagnostic<R: Reportable + Display, F: Copy>(err: &R, file_id: F) -> Diagnostic<F> {
+        let labels = err
+            .spans()
+            .into_iter()
+            .map(|span| {
+                let range = span.start..span.end.max(span.start);
+                let label = if span.primary {
+                    Label::primary(file_id, range)
+                } else {
+                    Label::secondary(file_id, range)
+                };
+                label.with_message(span.message)
+            })
+            .collect();
+        Diagnostic::error()
+            .with_code(err.code())
+            .with_message(err.to_string())
+            .with_labels(labels)
+            .with_notes(err.help().into_iter().collect())
+    }
+
+    impl ParseError<'_> {
+        /// Diagnostic of the error in a file of a `codespan_reporting` file database.
+        ///
+        /// The error's byte offsets are relative to the input that was parsed, so the file should contain only the
+        /// input.
+        pub fn to_codespan_diagnostic<F: Copy>(&self, file_id: F) -> Diagnostic<F> {
+            diagnostic(self, file_id)
+        }
+    }
+
+    impl QueryError {
+        /// Diagnostic of the error in a file of a `codespan_reporting` file database.
+        pub fn to_codespan_diagnostic<F: Copy>(&self, file_id: F) -> Diagnostic<F> {
+            diagnostic(self, file_id)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+    use Ssn;
+
+    #[cfg(feature = "miette")]
+    #[test]
+    fn test_miette() {
+        use miette::{Diagnostic, NarratableReportHandler};
+        use Constraints;
+
+        let err = Ssn::try_from("291269-2764").unwrap_err();
+        assert_eq!(err.code().unwrap().to_string(), "hetu::checksum");
+        assert_eq!(
+            err.help().unwrap().to_string(),
+            "the checksum of the birth date and identifier is 3"
+        );
+        let labels: Vec<_> = err.labels().unwrap().collect();
+        assert_eq!(labels.len(), 3);
+        assert!(labels[0].primary());
+        assert_eq!((labels[0].offset(), labels[0].len()), (10, 1));
+        assert_eq!(labels[1].label(), Some("birth date"));
+
+        let mut out = String::new();
+        let report = err.to_report("291269-2764");
+        NarratableReportHandler::new()
+            .render_report(&mut out, report.as_ref())
+            .unwrap();
+        assert!(out.starts_with("Invalid checksum: expected 3\n"));
+        assert!(out.contains("diagnostic code: hetu::checksum"));
+        assert!(out.contains("label at line 1, column 11: expected 3"));
+
+        let err = Constraints::try_from("gender:x").unwrap_err();
+        assert_eq!(err.code().unwrap().to_string(), "hetu::query");
+        let label = err.labels().unwrap().next().unwrap();
+        assert_eq!(label.label(), Some("Invalid gender"));
+        assert_eq!((label.offset(), label.len()), (7, 1));
+    }
+
+    #[cfg(feature = "codespan-reporting")]
+    #[test]
+    fn test_codespan() {
+        use codespan_reporting::diagnostic::{LabelStyle, Severity};
+        use SsnPattern;
+
+        let err = Ssn::try_from("291269-2764").unwrap_err();
+        let diagnostic = err.to_codespan_diagnostic(());
+        assert_eq!(diagnostic.severity, Severity::Error);
+        assert_eq!(diagnostic.code.as_deref(), Some("hetu::checksum"));
+        assert_eq!(diagnostic.message, "Invalid checksum: expected 3");
+        assert_eq!(diagnostic.labels[0].style, LabelStyle::Primary);
+        assert_eq!(diagnostic.labels[0].range, 10..11);
+        assert_eq!(diagnostic.labels[2].style, LabelStyle::Secondary);
+        assert_eq!(diagnostic.labels[2].message, "identifier");
+        assert_eq!(
+            diagnostic.notes,
+            vec!["the checksum of the birth date and identifier is 3"]
+        );
+
+        let err = SsnPattern::try_from("[0-").unwrap_err();
+        let diagnostic = err.to_codespan_diagnostic(1);
+        assert_eq!(diagnostic.code.as_deref(), Some("hetu::syntax"));
+        assert_eq!(diagnostic.labels.len(), 1);
+        assert!(diagnostic.notes.is_empty());
+    }
+}
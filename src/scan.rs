@@ -0,0 +1,133 @@
+use std::convert::TryFrom;
+
+use super::Ssn;
+
+/// Valid personal identity code found in text.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Match {
+    /// Byte offset of the first character.
+    pub start: usize,
+    /// Byte offset after the last character.
+    pub end: usize,
+    pub ssn: Ssn,
+}
+
+impl Match {
+    /// Matched personal identity code in the text it was found in.
+    pub fn as_str<'a>(&self, text: &'a str) -> &'a str {
+        &text[self.start..self.end]
+    }
+}
+
+/// Iterator over valid personal identity codes in text.
+#[derive(Debug)]
+pub struct Matches<'a> {
+    text: &'a str,
+    offset: usize,
+}
+
+impl<'a> Iterator for Matches<'a> {
+    type Item = Match;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let bytes = self.text.as_bytes();
+        while self.offset + 11 <= bytes.len() {
+            let start = self.offset;
+            self.offset += 1;
+            if !bytes[start].is_ascii_digit()
+                || (start > 0 && bytes[start - 1].is_ascii_alphanumeric())
+                || bytes
+                    .get(start + 11)
+                    .is_some_and(|b| b.is_ascii_alphanumeric())
+            {
+                continue;
+            }
+            let ssn = match self.text.get(start..start + 11).map(Ssn::try_from) {
+                Some(Ok(ssn)) => ssn,
+                _ => continue,
+            };
+            self.offset = start + 11;
+            return Some(Match {
+                start,
+                end: start + 11,
+                ssn,
+            });
+        }
+        None
+    }
+}
+
+/// Find valid personal identity codes in text.
+///
+/// Only codes that pass validation and are not part of a longer alphanumeric word are found.
+///
+/// # Example
+///
+/// ```
+/// use hetu::scan;
+///
+/// let text = "Customer 010101-123N, order 123456-7890";
+/// let found: Vec<&str> = scan::find(text).map(|m| m.as_str(text)).collect();
+/// assert_eq!(found, vec!["010101-123N"]);
+/// ```
+pub fn find(text: &str) -> Matches<'_> {
+    Matches { text, offset: 0 }
+}
+
+/// Replace every character of valid personal identity codes in text with a mask character.
+///
+/// # Example
+///
+/// ```
+/// use hetu::scan;
+///
+/// assert_eq!(scan::redact("id: 010101-123N", '*'), "id: ***********");
+/// ```
+pub fn redact(text: &str, mask: char) -> String {
+    let mut res = String::with_capacity(text.len());
+    let mut offset = 0;
+    for m in find(text) {
+        res.push_str(&text[offset..m.start]);
+        res.extend((m.start..m.end).map(|_| mask));
+        offset = m.end;
+    }
+    res.push_str(&text[offset..]);
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn found(text: &str) -> Vec<&str> {
+        find(text).map(|m| m.as_str(text)).collect()
+    }
+
+    #[test]
+    fn test_find() {
+        assert_eq!(
+            found("010101-123N,010101A123N\n010101+123N"),
+            vec!["010101-123N", "010101A123N", "010101+123N"]
+        );
+        assert_eq!(found("010101-123N"), vec!["010101-123N"]);
+        assert_eq!(found("ä010101-123Nö"), vec!["010101-123N"]);
+    }
+
+    #[test]
+    fn test_find_rejects_invalid_and_embedded() {
+        assert!(found("010101-123M").is_empty());
+        assert!(found("0010101-123N").is_empty());
+        assert!(found("010101-123N0").is_empty());
+        assert!(found("x010101-123N").is_empty());
+        assert!(found("0101").is_empty());
+    }
+
+    #[test]
+    fn test_redact() {
+        assert_eq!(
+            redact("a 010101-123N b 010101-123M", 'X'),
+            "a XXXXXXXXXXX b 010101-123M"
+        );
+        assert_eq!(redact("ö 010101-123N", '*'), "ö ***********");
+    }
+}
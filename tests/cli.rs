@@ -0,0 +1,1030 @@
+#![cfg(feature = "cli")]
+
+extern crate hetu;
+extern crate zip;
+
+use hetu::Ssn;
+use std::convert::TryFrom;
+use std::env;
+use std::fs;
+use std::io::{BufRead, BufReader, Cursor, ErrorKind, Read, Write};
+use std::net::TcpStream;
+use std::process::{Command, Output, Stdio};
+
+fn hetu(args: &[&str], input: &str) -> Output {
+    // Messages are in English unless a test sets the language
+    let mut child = Command::new(env!("CARGO_BIN_EXE_hetu"))
+        .args(args)
+        .env_remove("LC_ALL")
+        .env_remove("LC_MESSAGES")
+        .env_remove("LANG")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    // Commands that fail on their arguments exit without reading standard input
+    if let Err(err) = child.stdin.take().unwrap().write_all(input.as_bytes()) {
+        assert_eq!(err.kind(), ErrorKind::BrokenPipe);
+    }
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+#[test]
+fn test_validate() {
+    assert_eq!(
+        hetu(&["validate", "291269-2763"], "").status.code(),
+        Some(0)
+    );
+    assert_eq!(
+        hetu(&["validate", "291269-2764"], "").status.code(),
+        Some(1)
+    );
+    assert_eq!(
+        hetu(&["validate"], "291269-2763\n010101-123N\n")
+            .status
+            .code(),
+        Some(0)
+    );
+    assert_eq!(hetu(&["validate", "-"], "bad\n").status.code(), Some(1));
+}
+
+#[test]
+fn test_validate_color() {
+    let stderr = |args: &[&str]| String::from_utf8(hetu(args, "").stderr).unwrap();
+    let plain = stderr(&["validate", "291269-2764"]);
+    assert_eq!(
+        plain,
+        "Error: Invalid checksum: expected 3\n\n  291269-2764\n            ^ expected 3\n  ------ birth date\n         --- identifier\n"
+    );
+    assert_eq!(
+        stderr(&["validate", "291269-2764", "--color", "never"]),
+        plain
+    );
+    let colored = stderr(&["--color", "always", "validate", "291269-2764"]);
+    assert!(colored.contains("\x1b[31m^\x1b[0m expected \x1b[1;32m3\x1b[0m"));
+}
+
+#[test]
+fn test_lang() {
+    let stderr = String::from_utf8(hetu(&["--lang", "fi", "validate", "291269-2764"], "").stderr);
+    assert_eq!(
+        stderr.unwrap(),
+        "Error: Virheellinen tarkiste: odotettiin 3\n\n  291269-2764\n            ^ odotettiin 3\n  ------ syntymäaika\n         --- yksilönumero\n"
+    );
+    let output = Command::new(env!("CARGO_BIN_EXE_hetu"))
+        .args(["info", "291269-2763", "--on", "2026-01-01"])
+        .env("LC_ALL", "")
+        .env("LANG", "sv_FI.UTF-8")
+        .output()
+        .unwrap();
+    let info = stdout(&output);
+    assert!(
+        info.starts_with("Födelsedatum:   1969-12-29, måndag\nÅlder:          56 den 2026-01-01\n")
+    );
+    assert!(info.contains("Kön:            kvinna (individnumret är jämnt)\n"));
+    assert_eq!(
+        hetu(&["--lang", "de", "info", "291269-2763"], "")
+            .status
+            .code(),
+        Some(2)
+    );
+}
+
+#[test]
+fn test_validate_batch() {
+    let input = "291269-2763\nbad\n\n291269-2764\n010101-123N\n";
+    let output = hetu(&["validate"], input);
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains(" --> -:2\n"));
+    assert!(stderr.contains(" --> -:4\n"));
+    assert!(stderr.contains("Validated 4 HETUs: 2 valid, 2 invalid"));
+    assert!(stderr.contains("1 checksum: Incorrect checksum"));
+
+    let output = hetu(&["validate", "--fail-fast"], input);
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Validated 2 HETUs: 1 valid, 1 invalid"));
+}
+
+#[test]
+fn test_validate_threshold() {
+    let input = "291269-2763\nbad\n291269-2764\n010101-123N\n";
+    let status = |threshold: &str| {
+        hetu(&["validate", "--max-invalid", threshold], input)
+            .status
+            .code()
+    };
+    assert_eq!(status("1"), Some(1));
+    assert_eq!(status("2"), Some(0));
+    assert_eq!(status("49%"), Some(1));
+    assert_eq!(status("50%"), Some(0));
+    assert_eq!(status("x"), Some(2));
+}
+
+#[test]
+fn test_validate_warnings() {
+    let input = "010101+123N\n010594Y123W\n010199-9001\n";
+    let output = hetu(&["validate", "--on", "2022-06-01"], input);
+    assert_eq!(output.status.code(), Some(0));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Warning: -:1: 010101+123N: Age 221 on 2022-06-01 is over 120\n"));
+    assert!(stderr.contains(
+        "Warning: -:2: 010594Y123W: Separator Y was introduced in 2023, after 2022-06-01\n"
+    ));
+    assert!(stderr.contains("Info: -:3: 010199-9001: Identifier 900 is temporary\n"));
+
+    let output = hetu(
+        &[
+            "validate",
+            "--on",
+            "2022-06-01",
+            "--warn",
+            "early-separator=error",
+            "--warn",
+            "old-age=off",
+        ],
+        input,
+    );
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(!stderr.contains("over 120"));
+    assert!(stderr.contains("Validated 3 HETUs: 2 valid, 1 invalid\n"));
+    assert!(stderr.contains("1 early-separator: Separator used before its introduction\n"));
+    assert_eq!(
+        hetu(&["validate", "--warn", "temporary"], input)
+            .status
+            .code(),
+        Some(2)
+    );
+
+    let args = [
+        "validate",
+        "010101A900R",
+        "--warn",
+        "temporary=error",
+        "--on",
+        "2026-01-01",
+        "--format",
+    ];
+    let output = hetu(&[&args[..], &["json"]].concat(), "");
+    assert_eq!(output.status.code(), Some(1));
+    let out = stdout(&output);
+    assert!(out.contains(r#""valid":false"#), "{}", out);
+    assert!(out.contains(
+        r#""warnings":[{"kind":"temporary","message":"Identifier 900 is temporary","severity":"error"}]"#
+    ));
+    let output = hetu(&[&args[..], &["csv"]].concat(), "");
+    assert!(stdout(&output)
+        .ends_with("\n010101A900R,,false,2001-01-01,2000,female,900,A,true,25,,,,,,temporary\n"));
+
+    let output = hetu(&["--lang", "fi", "validate", "--on", "2022-06-01"], input);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Info: -:3: 010199-9001: Yksilönumero 900 on väliaikainen\n"));
+}
+
+#[test]
+fn test_profile() {
+    let input = "010594Y123W\n010594-123W\n";
+    let output = hetu(&["validate", "--profile", "legacy"], input);
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Separator introduced in 2023"));
+    assert!(stderr.contains("Validated 2 HETUs: 1 valid, 1 invalid\n"));
+    assert_eq!(hetu(&["validate"], input).status.code(), Some(0));
+
+    let output = hetu(
+        &["generate", "-n", "50", "--seed", "1", "--profile", "legacy"],
+        "",
+    );
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout.lines().count(), 50);
+    assert!(stdout
+        .lines()
+        .all(|code| "+-A".contains(code.chars().nth(6).unwrap())));
+    assert_eq!(
+        hetu(
+            &["generate", "-p", "010594Y????", "--profile", "legacy"],
+            ""
+        )
+        .status
+        .code(),
+        Some(3)
+    );
+}
+
+#[test]
+fn test_validate_format() {
+    let input = "291269-2763\n291269-2764\n";
+    let output = hetu(
+        &["validate", "--format", "jsonl", "--on", "2026-01-01"],
+        input,
+    );
+    assert_eq!(output.status.code(), Some(1));
+    let lines: Vec<String> = stdout(&output).lines().map(String::from).collect();
+    assert_eq!(
+        lines,
+        vec![
+            r#"{"age":56,"birth_date":"1969-12-29","century":1900,"code":"291269-2763","gender":"female","identifier":276,"location":"-:1","separator":"-","temporary":false,"valid":true,"warnings":[]}"#,
+            r#"{"code":"291269-2764","error_end":11,"error_kind":"checksum","error_message":"Incorrect checksum","error_start":10,"expected_checksum":"3","location":"-:2","valid":false}"#,
+        ]
+    );
+    assert!(!String::from_utf8(output.stderr).unwrap().contains("-->"));
+
+    let output = hetu(
+        &["validate", "--format", "csv", "--on", "2026-01-01"],
+        input,
+    );
+    assert_eq!(
+        stdout(&output),
+        "code,location,valid,birth_date,century,gender,identifier,separator,temporary,age,\
+         error_kind,error_message,expected_checksum,error_start,error_end,warnings\n\
+         291269-2763,-:1,true,1969-12-29,1900,female,276,-,false,56,,,,,,\n\
+         291269-2764,-:2,false,,,,,,,,checksum,Incorrect checksum,3,10,11,\n"
+    );
+
+    let output = hetu(&["validate", "--format", "json", "291269-2763"], "");
+    assert!(stdout(&output).starts_with(r#"[{"age":"#));
+}
+
+#[test]
+fn test_generate() {
+    let output = hetu(
+        &[
+            "generate",
+            "-n",
+            "5",
+            "--gender",
+            "male",
+            "--born",
+            "1980..1989",
+        ],
+        "",
+    );
+    assert_eq!(output.status.code(), Some(0));
+    let codes: Vec<String> = stdout(&output).lines().map(String::from).collect();
+    assert_eq!(codes.len(), 5);
+    for code in &codes {
+        let ssn = Ssn::try_from(code.as_str()).unwrap();
+        assert_eq!(ssn.gender, hetu::Gender::Male);
+        assert!(1980 <= ssn.year && ssn.year <= 1989);
+    }
+}
+
+#[test]
+fn test_generate_format() {
+    let output = hetu(&["generate", "-n", "3", "--format", "csv"], "");
+    assert_eq!(output.status.code(), Some(0));
+    let out = stdout(&output);
+    let mut lines = out.lines();
+    assert!(lines.next().unwrap().starts_with("code,location,valid,"));
+    assert_eq!(lines.filter(|line| line.contains(",true,")).count(), 3);
+}
+
+#[test]
+fn test_generate_seed() {
+    let args = ["generate", "-n", "3", "--seed", "42"];
+    assert_eq!(stdout(&hetu(&args, "")), stdout(&hetu(&args, "")));
+}
+
+#[test]
+fn test_generate_failures() {
+    assert_eq!(hetu(&["generate", "-p", "29x"], "").status.code(), Some(2));
+    assert_eq!(
+        hetu(&["generate", "-p", "010197-100?", "-n", "2"], "")
+            .status
+            .code(),
+        Some(3)
+    );
+    assert_eq!(hetu(&["unknown"], "").status.code(), Some(2));
+}
+
+#[test]
+fn test_info() {
+    let output = hetu(&["info", "291269-2763", "--on", "2026-10-18"], "");
+    assert_eq!(output.status.code(), Some(0));
+    let out = stdout(&output);
+    assert!(out.contains("Birth date: 1969-12-29, Monday\n"));
+    assert!(out.contains("Age:        56 on 2026-10-18\n"));
+    assert!(out.contains("Separator:  - (legacy, in use before 2023)\n"));
+    assert!(out.contains("    291269276 mod 31 = 3\n"));
+    assert!(out.contains("    CHECKSUM_TABLE[3] = '3'\n"));
+    let out = stdout(&hetu(&["info", "010150B902E"], ""));
+    assert!(out.contains("Separator:  B (introduced in 2023)\n"));
+    assert!(out.contains("Temporary:  yes"));
+    assert_eq!(hetu(&["info", "291269-2764"], "").status.code(), Some(1));
+    let output = hetu(&["info", "291269-2764", "--format", "jsonl"], "");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout(&output).contains(r#""expected_checksum":"3""#));
+}
+
+#[test]
+fn test_gender_neutral() {
+    let neutral = ["--on", "2027-06-01", "--gender-neutral-from", "2027-01-01"];
+    let output = hetu(&[&["info", "291269-2763"][..], &neutral[..]].concat(), "");
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stdout(&output).contains("Gender:     unknown (gender-neutral code)\n"));
+    let output = hetu(
+        &[
+            "info",
+            "291269-2763",
+            "--on",
+            "2026-12-31",
+            "--gender-neutral-from",
+            "2027-01-01",
+        ],
+        "",
+    );
+    assert!(stdout(&output).contains("Gender:     female (identifier is even)\n"));
+
+    let output = hetu(
+        &[
+            &["validate", "--format", "jsonl", "291269-2763"][..],
+            &neutral[..],
+        ]
+        .concat(),
+        "",
+    );
+    assert!(stdout(&output).contains(r#""gender":null"#));
+    let output = hetu(
+        &[&["generate", "--gender", "female"][..], &neutral[..]].concat(),
+        "",
+    );
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("Gender is not encoded by gender-neutral codes"));
+}
+
+#[test]
+fn test_csv() {
+    let input = "\u{feff}nimi;hetu;kaupunki\n\
+                 \"Äijälä, Öljy\";291269-2763;Hämeenlinna\n\
+                 Åsa;291269-2764;\"Turku\nÅbo\"\n";
+    let output = hetu(
+        &["csv", "-d", ";", "-c", "hetu", "--on", "2026-01-01"],
+        input,
+    );
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        "\u{feff}nimi;hetu;kaupunki;hetu_valid;hetu_error;hetu_birth_date;hetu_gender;hetu_age\n\
+         \"Äijälä, Öljy\";291269-2763;Hämeenlinna;true;;1969-12-29;female;56\n\
+         Åsa;291269-2764;\"Turku\nÅbo\";false;checksum;;;\n"
+    );
+}
+
+#[test]
+fn test_csv_crlf() {
+    let input = "\u{feff}nimi;hetu;born;sex\r\n\
+                 \"Äijö\";291269-2763;30.12.1969;male\r\n\
+                 \"Åsa\";\"291269-2764\";;\r\n";
+    let output = hetu(
+        &[
+            "csv",
+            "-d",
+            ";",
+            "-c",
+            "hetu",
+            "--on",
+            "2026-01-01",
+            "--birth-date-column",
+            "born",
+            "--gender-column",
+            "sex",
+        ],
+        input,
+    );
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        "\u{feff}nimi;hetu;born;sex;hetu_valid;hetu_error;hetu_birth_date;hetu_gender;hetu_age;hetu_mismatches\r\n\
+         \"Äijö\";291269-2763;30.12.1969;male;true;;1969-12-29;female;56;\"birth_date;gender\"\r\n\
+         \"Åsa\";\"291269-2764\";;;false;checksum;;;;\r\n"
+    );
+}
+
+#[test]
+fn test_csv_claims() {
+    let input = "hetu,born,gender,age\n\
+                 291269-2763,29.12.1969,F,56\n\
+                 291269-2763,1969-12-30,male,x\n\
+                 291269-2764,1969-12-29,,\n";
+    let output = hetu(
+        &[
+            "csv",
+            "--birth-date-column",
+            "born",
+            "--gender-column",
+            "gender",
+            "--age-column",
+            "4",
+            "--on",
+            "2026-01-01",
+        ],
+        input,
+    );
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        "hetu,born,gender,age,hetu_valid,hetu_error,hetu_birth_date,hetu_gender,hetu_age,hetu_mismatches\n\
+         291269-2763,29.12.1969,F,56,true,,1969-12-29,female,56,\n\
+         291269-2763,1969-12-30,male,x,true,,1969-12-29,female,56,birth_date;gender;age\n\
+         291269-2764,1969-12-29,,,false,checksum,,,,\n"
+    );
+    assert_eq!(
+        hetu(&["csv", "--gender-column", "sex"], input)
+            .status
+            .code(),
+        Some(2)
+    );
+}
+
+#[test]
+fn test_csv_gender_neutral() {
+    let input = "hetu,gender\n291269-2763,male\n";
+    let args = [
+        "csv",
+        "--gender-column",
+        "gender",
+        "--on",
+        "2027-06-01",
+        "--gender-neutral-from",
+    ];
+    let output = hetu(&[&args[..], &["2027-01-01"]].concat(), input);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        "hetu,gender,hetu_valid,hetu_error,hetu_birth_date,hetu_gender,hetu_age,hetu_mismatches\n\
+         291269-2763,male,true,,1969-12-29,,57,\n"
+    );
+    let output = hetu(&[&args[..], &["2028-01-01"]].concat(), input);
+    assert!(stdout(&output).ends_with(",1969-12-29,female,57,gender\n"));
+}
+
+#[test]
+fn test_csv_quarantine() {
+    let quarantine = env::temp_dir().join(format!("hetu-quarantine-{}.csv", std::process::id()));
+    let input = "id,hetu\n1,291269-2763\n2,bad\n";
+    let output = hetu(
+        &[
+            "csv",
+            "--column",
+            "2",
+            "--on",
+            "2026-01-01",
+            "--quarantine",
+            quarantine.to_str().unwrap(),
+        ],
+        input,
+    );
+    assert_eq!(
+        stdout(&output),
+        "id,hetu,hetu_valid,hetu_error,hetu_birth_date,hetu_gender,hetu_age\n\
+         1,291269-2763,true,,1969-12-29,female,56\n"
+    );
+    assert_eq!(fs::read_to_string(&quarantine).unwrap(), "id,hetu\n2,bad\n");
+    fs::remove_file(&quarantine).unwrap();
+
+    let output = hetu(&["csv", "--no-header", "--drop-invalid"], "bad\n");
+    assert_eq!(stdout(&output), "");
+    assert_eq!(
+        hetu(&["csv", "--no-header", "-c", "hetu"], "")
+            .status
+            .code(),
+        Some(2)
+    );
+}
+
+#[test]
+fn test_scan_and_redact() {
+    let text = "id 291269-2763 and 291269-2764\n";
+    let output = hetu(&["scan"], text);
+    assert_eq!(output.status.code(), Some(5));
+    assert_eq!(stdout(&output), "-:1:4: id *********** and 291269-2764\n");
+    assert_eq!(hetu(&["scan"], "nothing\n").status.code(), Some(0));
+    assert_eq!(
+        stdout(&hetu(&["redact", "--mask", "X"], text)),
+        "id XXXXXXXXXXX and 291269-2764\n"
+    );
+}
+
+#[test]
+fn test_people() {
+    let args = [
+        "people",
+        "-n",
+        "200",
+        "--format",
+        "csv",
+        "--seed",
+        "3",
+        "--on",
+        "2026-01-01",
+    ];
+    let output = hetu(&args, "");
+    assert_eq!(output.status.code(), Some(0));
+    let out = stdout(&output);
+    let mut lines = out.lines();
+    assert_eq!(
+        lines.next(),
+        Some("code,first_name,last_name,birth_date,gender,age")
+    );
+    let rows: Vec<Vec<&str>> = lines.map(|line| line.split(',').collect()).collect();
+    assert_eq!(rows.len(), 200);
+    let mut codes: Vec<&str> = rows.iter().map(|row| row[0]).collect();
+    codes.sort();
+    codes.dedup();
+    assert_eq!(codes.len(), 200);
+    for row in &rows {
+        let ssn = Ssn::try_from(row[0]).unwrap();
+        assert_eq!(row[3], ssn.birth_date().to_string());
+        assert_eq!(row[4], ssn.gender.name());
+    }
+    assert_eq!(stdout(&hetu(&args, "")), out);
+
+    let output = hetu(
+        &[
+            "people",
+            "-n",
+            "2",
+            "--ages",
+            "population",
+            "--format",
+            "json",
+        ],
+        "",
+    );
+    assert!(stdout(&output).starts_with(r#"[{"age":"#));
+
+    for on in &["1820-01-01", "9999-12-31"] {
+        let output = hetu(&["people", "--on", on], "");
+        assert_eq!(output.status.code(), Some(2));
+        assert_eq!(stdout(&output), "");
+    }
+}
+
+/// Temporary directory with files, removed when dropped.
+struct TempDir(std::path::PathBuf);
+
+impl TempDir {
+    fn new(name: &str, files: &[(&str, &str)]) -> TempDir {
+        let dir = env::temp_dir().join(format!("hetu-{}-{}", name, std::process::id()));
+        for &(path, content) in files {
+            let path = dir.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        TempDir(dir)
+    }
+
+    fn path(&self, path: &str) -> String {
+        self.0.join(path).to_str().unwrap().to_string()
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+#[test]
+fn test_scan_directory() {
+    let dir = TempDir::new(
+        "scan",
+        &[
+            (".gitignore", "*.log\n"),
+            ("allow.txt", "# test codes\n010101-123?\n"),
+            ("src/a.txt", "customer=291269-2763 ok\n"),
+            ("src/b.txt", "test 010101-123N\ntemporary 010150B902E\n"),
+            ("src/c.bin", "\x00 291269-2763"),
+            ("logs/x.log", "291269-2763\n"),
+            (".hidden/x.txt", "291269-2763\n"),
+        ],
+    );
+    let root = dir.path("");
+    let output = hetu(
+        &[
+            "scan",
+            &root,
+            "--allowlist",
+            &dir.path("allow.txt"),
+            "--allow-temporary",
+        ],
+        "",
+    );
+    assert_eq!(output.status.code(), Some(5));
+    assert_eq!(
+        stdout(&output),
+        format!("{}:1:10: customer=*********** ok\n", dir.path("src/a.txt"))
+    );
+
+    let output = hetu(
+        &["scan", &root, "--exclude", "src", "--exclude", "allow.txt"],
+        "",
+    );
+    assert_eq!(output.status.code(), Some(0));
+    let output = hetu(
+        &[
+            "scan",
+            &root,
+            "--hidden",
+            "--exclude",
+            "src",
+            "--exclude",
+            "allow.txt",
+        ],
+        "",
+    );
+    assert_eq!(output.status.code(), Some(5));
+}
+
+#[test]
+fn test_scan_corrupt_document() {
+    let dir = TempDir::new(
+        "corrupt",
+        &[
+            ("a.jar", "PK\x03\x04 not really an archive"),
+            ("b.txt", "291269-2763\n"),
+        ],
+    );
+    let output = hetu(&["scan", &dir.path("")], "");
+    assert_eq!(output.status.code(), Some(5));
+    assert!(String::from_utf8_lossy(&output.stderr).contains(&dir.path("a.jar")));
+    assert_eq!(
+        stdout(&output),
+        format!("{}:1:1: ***********\n", dir.path("b.txt"))
+    );
+}
+
+#[test]
+fn test_scan_walk_error() {
+    let dir = TempDir::new("walk", &[("a.txt", "291269-2763\n"), ("b.txt", "none\n")]);
+    let missing = dir.path("missing");
+    let output = hetu(&["scan", &missing, &dir.path("a.txt")], "");
+    assert_eq!(output.status.code(), Some(5));
+    assert!(String::from_utf8_lossy(&output.stderr).contains(&missing));
+    assert_eq!(
+        stdout(&output),
+        format!("{}:1:1: ***********\n", dir.path("a.txt"))
+    );
+    let output = hetu(&["scan", &missing, &dir.path("b.txt")], "");
+    assert_eq!(output.status.code(), Some(4));
+}
+
+#[test]
+fn test_scan_sarif() {
+    let dir = TempDir::new("sarif", &[("a.txt", "ö 291269-2763\n")]);
+    let output = hetu(&["scan", &dir.path("a.txt"), "--format", "sarif"], "");
+    assert_eq!(output.status.code(), Some(5));
+    let out = stdout(&output);
+    assert!(out.contains(r#""version":"2.1.0""#));
+    assert!(out.contains(r#""ruleId":"hetu/personal-identity-code""#));
+    assert!(out.contains(r#""startColumn":3"#));
+    assert!(out.contains(r#""snippet":{"text":"ö ***********"}"#));
+    assert!(!out.contains("291269-2763"));
+
+    let output = hetu(&["scan", &dir.path("a.txt"), "--format", "json"], "");
+    assert!(stdout(&output).contains(r#""column":3,"line":1,"#));
+}
+
+/// ZIP container of parts.
+fn container(parts: &[(&str, &str)]) -> Vec<u8> {
+    let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    for &(name, content) in parts {
+        writer
+            .start_file(name, zip::write::SimpleFileOptions::default())
+            .unwrap();
+        writer.write_all(content.as_bytes()).unwrap();
+    }
+    writer.finish().unwrap().into_inner()
+}
+
+#[test]
+fn test_scan_and_redact_document() {
+    let dir = TempDir::new("document", &[]);
+    fs::create_dir_all(dir.path("")).unwrap();
+    let docx = container(&[(
+        "word/document.xml",
+        "<w:document><w:body><w:p><w:r><w:t>Title</w:t></w:r></w:p>\
+         <w:p><w:r><w:t xml:space=\"preserve\">Customer </w:t></w:r><w:r><w:t>291269-2763</w:t></w:r></w:p>\
+         </w:body></w:document>",
+    )]);
+    fs::write(dir.path("report.docx"), &docx).unwrap();
+
+    let output = hetu(&["scan", &dir.path("report.docx")], "");
+    assert_eq!(output.status.code(), Some(5));
+    assert_eq!(
+        stdout(&output),
+        format!(
+            "{}:word/document.xml:paragraph 2: Customer ***********\n",
+            dir.path("report.docx")
+        )
+    );
+    let output = hetu(&["scan", &dir.path(""), "--format", "json"], "");
+    assert!(stdout(&output).contains(r#""location":"paragraph 2","part":"word/document.xml""#));
+
+    assert_eq!(
+        hetu(&["redact", &dir.path("report.docx")], "")
+            .status
+            .code(),
+        Some(2)
+    );
+    let redacted = dir.path("redacted.docx");
+    let output = hetu(&["redact", &dir.path("report.docx"), "-o", &redacted], "");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(hetu(&["scan", &redacted], "").status.code(), Some(0));
+
+    // Masks that would break the XML of the document are rejected
+    let redacted = dir.path("masked.docx");
+    let args = ["redact", &dir.path("report.docx"), "-o", &redacted];
+    let output = hetu(&[&args[..], &["--mask", "<"]].concat(), "");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(!std::path::Path::new(&redacted).exists());
+
+    // Codes split across formatting can't be redacted
+    let split = container(&[(
+        "word/document.xml",
+        "<w:p><w:r><w:t>291269</w:t></w:r><w:r><w:t>-2763</w:t></w:r></w:p>",
+    )]);
+    fs::write(dir.path("split.docx"), &split).unwrap();
+    let redacted = dir.path("split-redacted.docx");
+    let output = hetu(&["redact", &dir.path("split.docx"), "-o", &redacted], "");
+    assert_eq!(output.status.code(), Some(5));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("1 HETUs could not be redacted"));
+    assert!(!std::path::Path::new(&redacted).exists());
+}
+
+#[test]
+fn test_fill() {
+    let template =
+        "INSERT INTO person VALUES ('{{hetu#alice gender=f}}', '{{hetu born=1980..1989}}');\n\
+                    INSERT INTO login VALUES ('{{hetu#alice}}', '{{ user }}');\n";
+    let output = hetu(&["fill", "--seed", "7"], template);
+    assert_eq!(output.status.code(), Some(0));
+    let filled = stdout(&output);
+    assert!(filled.ends_with("', '{{ user }}');\n"));
+    let codes: Vec<&str> = filled.split('\'').skip(1).step_by(2).collect();
+    assert_eq!(codes.len(), 4);
+    assert_eq!(codes[0], codes[2]);
+    assert_ne!(codes[0], codes[1]);
+    assert_eq!(
+        Ssn::try_from(codes[0]).unwrap().gender,
+        hetu::Gender::Female
+    );
+    assert_eq!(stdout(&hetu(&["fill", "--seed", "7"], template)), filled);
+
+    let output = hetu(&["fill"], "a\n  {{hetu sex=f}}\n");
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Error: -:2:3: Unknown option 'sex'"));
+    assert_eq!(
+        hetu(
+            &["fill"],
+            "{{hetu pattern=010101-123?}} {{hetu pattern=010101-123?}}"
+        )
+        .status
+        .code(),
+        Some(3)
+    );
+}
+
+fn git(dir: &TempDir, args: &[&str]) {
+    let status = Command::new("git")
+        .args(args)
+        .current_dir(&dir.0)
+        .stdout(Stdio::null())
+        .status()
+        .unwrap();
+    assert!(status.success());
+}
+
+#[test]
+fn test_diff_staged() {
+    let dir = TempDir::new(
+        "diff",
+        &[("a.txt", "first\nlast\n"), ("allow.txt", "010101-123N\n")],
+    );
+    git(&dir, &["init", "-q"]);
+    git(&dir, &["config", "user.email", "test@example.com"]);
+    git(&dir, &["config", "user.name", "Test"]);
+    git(&dir, &["add", "."]);
+    git(&dir, &["commit", "-q", "-m", "Initial"]);
+
+    let staged = || {
+        Command::new(env!("CARGO_BIN_EXE_hetu"))
+            .args(["diff", "--allowlist", "allow.txt"])
+            .current_dir(&dir.0)
+            .output()
+            .unwrap()
+    };
+    fs::write(
+        dir.path("a.txt"),
+        "first\ntest 010101-123N\ntemporary 010150B902E\nlast\n",
+    )
+    .unwrap();
+    git(&dir, &["add", "."]);
+    assert_eq!(staged().status.code(), Some(0));
+
+    fs::write(dir.path("b.txt"), "customer 291269-2763\n").unwrap();
+    git(&dir, &["add", "."]);
+    let output = staged();
+    assert_eq!(output.status.code(), Some(5));
+    assert_eq!(
+        stdout(&output),
+        "b.txt @@ -0,0 +1 @@\n  +1:10: customer ***********\n"
+    );
+}
+
+/// Server started with `hetu serve` on a free port, stopped when dropped.
+struct Server {
+    child: std::process::Child,
+    addr: String,
+}
+
+impl Server {
+    fn start(args: &[&str]) -> Server {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_hetu"))
+            .args(["serve", "--listen", "127.0.0.1:0"])
+            .args(args)
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let mut line = String::new();
+        BufReader::new(child.stderr.as_mut().unwrap())
+            .read_line(&mut line)
+            .unwrap();
+        let addr = line
+            .trim()
+            .strip_prefix("Listening on http://")
+            .unwrap()
+            .to_string();
+        Server { child, addr }
+    }
+
+    /// Send request and return the status code and body of the response.
+    fn request(&self, method: &str, path: &str, body: &str) -> (u16, String) {
+        let mut stream = TcpStream::connect(&self.addr).unwrap();
+        write!(
+            stream,
+            "{} {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            method,
+            path,
+            self.addr,
+            body.len(),
+            body
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let status = response[9..12].parse().unwrap();
+        let body = response
+            .split_once("\r\n\r\n")
+            .map_or("", |(_, body)| body)
+            .to_string();
+        (status, body)
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[test]
+fn test_serve() {
+    let server = Server::start(&["--max-body", "100"]);
+    let (status, body) = server.request("GET", "/health", "");
+    assert_eq!(status, 200);
+    assert!(body.starts_with(r#"{"status":"ok""#));
+
+    let (status, body) = server.request(
+        "POST",
+        "/validate",
+        r#"{"codes":["291269-2763","291269-2764"]}"#,
+    );
+    assert_eq!(status, 200);
+    assert!(body.contains(r#""error_end":11,"error_kind":"checksum""#));
+    assert!(body.starts_with(r#"{"invalid":1,"results":[{"age":"#));
+
+    let (status, body) = server.request(
+        "POST",
+        "/generate",
+        r#"{"count":2,"pattern":"291269-????","seed":1}"#,
+    );
+    assert_eq!(status, 200);
+    assert_eq!(body.matches("291269-").count(), 2);
+
+    let (status, body) = server.request("POST", "/redact", r#"{"text":"id 291269-2763"}"#);
+    assert_eq!(status, 200);
+    assert_eq!(body, r#"{"count":1,"text":"id ***********"}"#);
+
+    let (status, _) = server.request(
+        "POST",
+        "/redact",
+        &format!(r#"{{"text":"{}"}}"#, "a".repeat(100)),
+    );
+    assert_eq!(status, 413);
+    assert_eq!(server.request("DELETE", "/validate", "").0, 405);
+    assert_eq!(server.request("GET", "/", "").0, 404);
+}
+
+#[test]
+fn test_allocate() {
+    let state = env::temp_dir().join(format!("hetu-allocate-{}.txt", std::process::id()));
+    let state = state.to_str().unwrap();
+    let args = ["allocate", "--born", "1969-12-29", "--state", state];
+    let output = hetu(
+        &[&args[..], &["-g", "f", "-n", "2", "--reserve", "2-9"]].concat(),
+        "",
+    );
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "291269-010H\n291269-012K\n");
+    let output = hetu(&[&args[..], &["-g", "m", "--temporary"]].concat(), "");
+    assert_eq!(stdout(&output), "291269-9018\n");
+    assert_eq!(
+        fs::read_to_string(state).unwrap(),
+        "profile modern\nreserved 2-9\n1969-12-29 - 10 12 901\n"
+    );
+
+    fs::write(state, "profile legacy\n1969-12-29 - 2-897\n").unwrap();
+    let output = hetu(&[&args[..], &["-g", "f", "-n", "3"]].concat(), "");
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(stdout(&output), "291269-8985\n");
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("No permanent identifiers left for females born 1969-12-29"));
+
+    let output = hetu(
+        &[&args[..], &["-g", "f", "--profile", "modern"]].concat(),
+        "",
+    );
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("Profile modern conflicts with profile legacy of the state file"));
+    let output = hetu(
+        &[&args[..], &["-g", "f", "--profile", "legacy"]].concat(),
+        "",
+    );
+    assert_eq!(output.status.code(), Some(3));
+    for range in &["9-2", "1000", "2-1000"] {
+        let output = hetu(&[&args[..], &["-g", "f", "--reserve", range]].concat(), "");
+        assert_eq!(output.status.code(), Some(2));
+    }
+
+    // A locked state is not used, and a failed save issues no codes
+    let lock = format!("{}.lock", state);
+    fs::write(&lock, "").unwrap();
+    let output = hetu(&[&args[..], &["-g", "m"]].concat(), "");
+    assert_eq!(output.status.code(), Some(4));
+    assert_eq!(stdout(&output), "");
+    fs::remove_file(&lock).unwrap();
+    let tmp = format!("{}.tmp", state);
+    fs::create_dir(&tmp).unwrap();
+    let output = hetu(&[&args[..], &["-g", "m"]].concat(), "");
+    assert_eq!(output.status.code(), Some(4));
+    assert_eq!(stdout(&output), "");
+    fs::remove_dir(&tmp).unwrap();
+    assert!(!std::path::Path::new(&lock).exists());
+
+    fs::write(state, "1969-12-29 Q 2\n").unwrap();
+    assert_eq!(
+        hetu(&[&args[..], &["-g", "f"]].concat(), "").status.code(),
+        Some(2)
+    );
+    fs::remove_file(state).unwrap();
+}
+
+#[test]
+fn test_duplicates() {
+    let input = "291269-2763\n010594Y123W\n291269-2764\n010594-123W\n219269-2763\n";
+    let output = hetu(&["duplicates"], input);
+    assert_eq!(output.status.code(), Some(5));
+    assert_eq!(
+        stdout(&output),
+        "-:1: 291269-2763  -:3: 291269-2764  checksum (high confidence)\n\
+         -:1: 291269-2763  -:5: 219269-2763  transposition (medium confidence)\n\
+         \n\
+         -:2: 010594Y123W  -:4: 010594-123W  separator (high confidence)\n"
+    );
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("Found 3 probable duplicates in 2 groups among 5 HETUs\n"));
+    assert_eq!(
+        hetu(&["duplicates"], "291269-2763\n010594Y123W\n")
+            .status
+            .code(),
+        Some(0)
+    );
+}
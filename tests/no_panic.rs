@@ -0,0 +1,122 @@
+//! Property tests that the public API returns errors instead of panicking on any input.
+
+extern crate hetu;
+extern crate proptest;
+
+use hetu::{Constraints, Ssn, SsnPattern};
+use proptest::option;
+use proptest::prelude::*;
+use std::convert::TryFrom;
+
+type Fields = (
+    (
+        Option<u8>,
+        Option<u8>,
+        Option<u8>,
+        Option<u8>,
+        Option<u8>,
+        Option<u8>,
+    ),
+    Option<char>,
+    (Option<u8>, Option<u8>, Option<u8>),
+    Option<char>,
+);
+
+fn any_fields() -> impl Strategy<Value = Fields> {
+    let digit = || option::of(prop_oneof![0u8..=9, any::<u8>()]);
+    let character =
+        || option::of(prop_oneof!["[-+A-Y0-9]", ".{1}"].prop_map(|s| s.chars().next().unwrap()));
+    (
+        (digit(), digit(), digit(), digit(), digit(), digit()),
+        character(),
+        (digit(), digit(), digit()),
+        character(),
+    )
+}
+
+fn new_pattern(fields: Fields) -> Result<SsnPattern, hetu::ParseError<'static>> {
+    let ((d1, d2, m1, m2, y1, y2), sep, (i1, i2, i3), check) = fields;
+    SsnPattern::new(d1, d2, m1, m2, y1, y2, sep, i1, i2, i3, check)
+}
+
+const PATTERN: &str = concat!(
+    r"(\?|[0-9]|\[\^?[0-9](-[0-9])?[0-9]?\]){6}",
+    r"(\?|[-+A-Y]|\{1[7-9]\}|\{20\}|\[[-+A-F_]{1,3}\])",
+    r"(\?|[0-9]|\[\^?[0-9](-[0-9])?\]){2}",
+    r"(\?|[0-9fm]|\[[0-9]-[0-9]\])",
+    r"[0-9A-Y?]"
+);
+
+proptest! {
+    #[test]
+    fn parse_any_string(s in ".*") {
+        let _ = Ssn::try_from(s.as_str());
+    }
+
+    #[test]
+    fn parse_code_like_string(s in "[0-9]{6}[-+A-Z_][0-9+-]{3}[0-9A-Z]") {
+        let _ = Ssn::try_from(s.as_str());
+    }
+
+    #[test]
+    fn parse_any_pattern_string(s in r"[0-9?+A-Z\-\[\]{}^fm]{11,14}|.{0,16}") {
+        let _ = SsnPattern::try_from(s.as_str());
+    }
+
+    #[test]
+    fn parse_any_query(s in r"(born|age|gender|temporary|separator|pattern|adult|x)(:[-0-9.<>=@?{}\[\]a-zé]{0,12}|:[<>=]{0,2}[0-9]{18,22})?([ \u{a0}\u{3000}].{0,16})?|.*") {
+        if let Ok(constraints) = Constraints::try_from(s.as_str()) {
+            if let Ok(ssn) = Ssn::generate_by_constraints(&constraints) {
+                prop_assert!(constraints.matches_str(&ssn), "{} doesn't match {}", ssn, s);
+            }
+        }
+    }
+
+    #[test]
+    fn parse_query_multibyte_born(s in "born:[0-9]{4}é[0-9]|born:[0-9é]{4,8}") {
+        let _ = Constraints::try_from(s.as_str());
+    }
+
+    #[test]
+    fn generate_by_any_fields(fields in any_fields()) {
+        if let Ok(pattern) = new_pattern(fields) {
+            if let Ok(ssn) = Ssn::generate_by_pattern(&pattern) {
+                prop_assert!(Ssn::try_from(ssn.as_str()).is_ok(), "generated invalid {}", ssn);
+                prop_assert!(pattern.matches_str(&ssn), "{} doesn't match {}", ssn, pattern);
+            }
+        }
+    }
+
+    #[test]
+    fn generate_by_any_pattern(s in PATTERN) {
+        if let Ok(pattern) = SsnPattern::try_from(s.as_str()) {
+            let formatted = pattern.to_string();
+            prop_assert_eq!(SsnPattern::try_from(formatted.as_str()), Ok(pattern));
+            match Ssn::generate_by_pattern(&pattern) {
+                Ok(ssn) => {
+                    prop_assert!(pattern.matches_str(&ssn), "{} doesn't match {}", ssn, pattern);
+                    prop_assert!(Ssn::count(&pattern).unwrap() > 0);
+                }
+                Err(_) => prop_assert_eq!(Ssn::count(&pattern), Ok(0)),
+            }
+        }
+    }
+
+    #[test]
+    fn iterate_any_pattern(s in PATTERN) {
+        if let Ok(pattern) = SsnPattern::try_from(s.as_str()) {
+            for ssn in Ssn::iter(&pattern).unwrap().take(5) {
+                prop_assert!(pattern.matches_str(&ssn), "{} doesn't match {}", ssn, pattern);
+            }
+        }
+    }
+
+    #[test]
+    fn iterate_any_fields(fields in any_fields()) {
+        if let Ok(pattern) = new_pattern(fields) {
+            for ssn in Ssn::iter(&pattern).unwrap().take(5) {
+                prop_assert!(Ssn::try_from(ssn.as_str()).is_ok(), "generated invalid {}", ssn);
+            }
+        }
+    }
+}